@@ -9,6 +9,7 @@
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use babe::AuthorityId as BabeId;
+use codec::{Decode, Encode};
 use client::{
     block_builder::api::{self as block_builder_api, CheckInherentsResult, InherentData},
     impl_runtime_apis, runtime_api as client_api,
@@ -21,6 +22,7 @@ use sr_primitives::traits::{
     BlakeTwo256, Block as BlockT, ConvertInto, DigestFor, NumberFor, StaticLookup, Verify,
 };
 use sr_primitives::weights::Weight;
+use system::offchain::TransactionSubmitter;
 use sr_primitives::{
     create_runtime_str, generic, impl_opaque_keys, transaction_validity::TransactionValidity,
     AnySignature, ApplyResult,
@@ -60,6 +62,9 @@ pub type Index = u32;
 /// A hash of some data used by the chain.
 pub type Hash = primitives::H256;
 
+/// A timestamp: milliseconds since the unix epoch.
+pub type Moment = u64;
+
 /// Digest item type.
 pub type DigestItem = generic::DigestItem<Hash>;
 
@@ -212,7 +217,7 @@ parameter_types! {
 
 impl timestamp::Trait for Runtime {
     /// A timestamp: milliseconds since the unix epoch.
-    type Moment = u64;
+    type Moment = Moment;
     type OnTimestampSet = Babe;
     type MinimumPeriod = MinimumPeriod;
 }
@@ -229,7 +234,7 @@ impl balances::Trait for Runtime {
     /// The type for recording an account's balance.
     type Balance = Balance;
     /// What to do if an account's free balance gets zeroed.
-    type OnFreeBalanceZero = ();
+    type OnFreeBalanceZero = TrustFund;
     /// What to do if a new account is created.
     type OnNewAccount = Indices;
     /// The ubiquitous event type.
@@ -256,10 +261,159 @@ impl assets::Trait for Runtime {
     type AssetId = u32;
 }
 
+parameter_types! {
+    pub const TriggerDeposit: Balance = 10_000;
+    pub const ClockInHistoryLength: u32 = 32;
+    pub const MaxMetadataLength: u32 = 256;
+    pub const AutoDistributionsPerBlock: u32 = 16;
+    pub const HeartbeatInterval: BlockNumber = 600;
+    pub const UnclaimedTimeout: BlockNumber = 365 * DAYS;
+    pub const MinTrustDeposit: Balance = 5_000;
+    pub const AuditLogLength: u32 = 64;
+    pub const UnsignedClockInPeriod: BlockNumber = HOURS;
+    pub const GuardianActivityPeriod: BlockNumber = 180 * DAYS;
+    pub const AttestationDeposit: Balance = 1_000;
+    pub const ConfigChangeCooldown: BlockNumber = DAYS;
+    pub const DisputeDeposit: Balance = 10_000;
+    pub const DisputeWindow: BlockNumber = 7 * DAYS;
+    pub const BeneficiaryDeposit: Balance = 100;
+    pub const AssetDeposit: Balance = 100;
+    pub const MaxTasksPerBlock: u32 = 64;
+    pub const MaxScheduledWeight: Perbill = Perbill::from_percent(50);
+    pub const DepositPerByte: Balance = 1;
+    pub const DepositPerBlock: Balance = 1;
+    pub const FailureSlash: Perbill = Perbill::from_percent(10);
+    pub const ExpiryFee: Perbill = Perbill::from_percent(5);
+    pub const ForceCancelSlash: Perbill = Perbill::zero();
+    pub const ResultRetention: BlockNumber = 7 * DAYS;
+    pub const MaxRescheduleDelay: BlockNumber = 30 * DAYS;
+    pub const MaxPendingPerAccount: u32 = 256;
+    pub const MaxDependents: u32 = 16;
+    pub const MinScheduleDelay: BlockNumber = 1;
+    pub const MaxScheduleHorizon: BlockNumber = 180 * DAYS;
+    pub const OnUnmetCondition: scheduler::UnmetCondition = scheduler::UnmetCondition::Postpone;
+}
+
 impl trust_fund::Trait for Runtime {
     type Event = Event;
     type BalanceToU128 = Balance;
     type U128ToBalance = Balance;
+    type OnIdleFunds = ();
+    type AssetExistentialDeposit = ExistentialDeposit;
+    type UniqueAssets = ();
+    type Currency = Balances;
+    type TriggerDeposit = TriggerDeposit;
+    type Slashed = ();
+    type ClockInHistoryLength = ClockInHistoryLength;
+    type MaxMetadataLength = MaxMetadataLength;
+    type AutoDistributionsPerBlock = AutoDistributionsPerBlock;
+    type HeartbeatId = trust_fund::crypto::sr25519::HeartbeatId;
+    type Call = Call;
+    type SubmitTransaction = TransactionSubmitter<(), Runtime, UncheckedExtrinsic>;
+    type HeartbeatInterval = HeartbeatInterval;
+    type IdentityVerifier = ();
+    type UnclaimedTimeout = UnclaimedTimeout;
+    type MinTrustDeposit = MinTrustDeposit;
+    type AuditLogLength = AuditLogLength;
+    type Signature = Signature;
+    type UnsignedClockInPeriod = UnsignedClockInPeriod;
+    type GuardianActivityPeriod = GuardianActivityPeriod;
+    type OnDistribution = trust_fund::EstateTax<Runtime>;
+    type DisputeDeposit = DisputeDeposit;
+    type DisputeWindow = DisputeWindow;
+    type ConfigChangeCooldown = ConfigChangeCooldown;
+    type AssetConverter = ();
+    type AttestationDeposit = AttestationDeposit;
+    type BeneficiaryDeposit = BeneficiaryDeposit;
+    type AssetDeposit = AssetDeposit;
+    type WeightInfo = ();
+}
+
+/// Lets only transfers and trust fund calls be scheduled.
+pub struct ScheduleFilter;
+impl scheduler::Filter<Call> for ScheduleFilter {
+    fn filter(call: &Call) -> bool {
+        match call {
+            Call::Balances(balances::Call::transfer(..))
+            | Call::Assets(assets::Call::transfer(..))
+            | Call::TrustFund(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Conditions on chain state scheduled tasks may wait for.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TaskCondition {
+    /// The free balance of the account is at least the amount.
+    MinBalance(AccountId, Balance),
+    /// The storage item under the key holds the value.
+    StorageEquals(Vec<u8>, Vec<u8>),
+}
+
+/// Checks `TaskCondition`s against the current state.
+pub struct SchedulerCondition;
+impl scheduler::ScheduleCondition<AccountId> for SchedulerCondition {
+    type Condition = TaskCondition;
+
+    fn holds(condition: &TaskCondition, _: &AccountId) -> bool {
+        match condition {
+            TaskCondition::MinBalance(who, amount) => Balances::free_balance(who) >= *amount,
+            TaskCondition::StorageEquals(key, value) => {
+                runtime_io::storage(key).as_ref() == Some(value)
+            }
+        }
+    }
+}
+
+/// Pays the tips of scheduled tasks to the author of the block they run in.
+pub struct TipsToAuthor;
+impl support::traits::OnUnbalanced<balances::NegativeImbalance<Runtime>> for TipsToAuthor {
+    fn on_unbalanced(tip: balances::NegativeImbalance<Runtime>) {
+        use support::traits::{Currency, FindAuthor};
+        let digest = System::digest();
+        let pre_runtime = digest.logs().iter().filter_map(|log| log.as_pre_runtime());
+        let author = Babe::find_author(pre_runtime)
+            .and_then(|index| Babe::authorities().get(index as usize).cloned());
+        // Without a known author, the tip is burnt.
+        if let Some((key, _)) = author {
+            let author: AccountId = key.into();
+            Balances::resolve_creating(&author, tip);
+        }
+    }
+}
+
+impl scheduler::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type ScheduleFilter = ScheduleFilter;
+    type Signature = Signature;
+    type ScheduleAsOrigin = system::EnsureRoot<AccountId>;
+    type PauseOrigin = system::EnsureRoot<AccountId>;
+    type ForceCancelOrigin = system::EnsureRoot<AccountId>;
+    type ScheduleCondition = SchedulerCondition;
+    type OnUnmetCondition = OnUnmetCondition;
+    type MaxTasksPerBlock = MaxTasksPerBlock;
+    type MaxScheduledWeight = MaxScheduledWeight;
+    type Currency = Balances;
+    type DepositPerByte = DepositPerByte;
+    type DepositPerBlock = DepositPerBlock;
+    type FailureSlash = FailureSlash;
+    type ExpiryFee = ExpiryFee;
+    type ForceCancelSlash = ForceCancelSlash;
+    type ResultRetention = ResultRetention;
+    type MaxRescheduleDelay = MaxRescheduleDelay;
+    type MaxPendingPerAccount = MaxPendingPerAccount;
+    type MaxDependents = MaxDependents;
+    type MinScheduleDelay = MinScheduleDelay;
+    type MaxScheduleHorizon = MaxScheduleHorizon;
+    type Slashed = ();
+    type WeightToFee = ConvertInto;
+    type ExecutionFees = ();
+    type Tips = TipsToAuthor;
+    type OnTaskExecuted = ();
+    type WeightInfo = ();
 }
 
 construct_runtime!(
@@ -276,7 +430,8 @@ construct_runtime!(
 		Balances: balances::{default, Error},
 		Sudo: sudo,
 		Assets: assets::{Module, Call, Storage, Event<T>},
-		TrustFund: trust_fund::{Module, Call, Storage, Event<T>},
+		TrustFund: trust_fund::{Module, Call, Storage, Config, Event<T>, ValidateUnsigned},
+		Scheduler: scheduler::{Module, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -406,6 +561,46 @@ impl_runtime_apis! {
         }
     }
 
+    impl trust_fund_runtime_api::TrustFundApi<Block, AccountId, BlockNumber, Moment, Hash, u32, Balance> for Runtime {
+        fn trust_status(grantor: AccountId) -> trust_fund::TrustStatus<AccountId, BlockNumber, Moment, Hash> {
+            TrustFund::trust_status(grantor)
+        }
+
+        fn clock_in_stats(grantor: AccountId) -> trust_fund::ClockInStats<BlockNumber> {
+            TrustFund::clock_in_stats(grantor)
+        }
+
+        fn quote_distribution(grantor: AccountId, asset_id: u32) -> Vec<(AccountId, Balance)> {
+            TrustFund::quote_distribution(grantor, asset_id)
+        }
+
+        fn audit_log(grantor: AccountId) -> Vec<trust_fund::AuditEntry<BlockNumber>> {
+            TrustFund::audit_log(grantor)
+        }
+    }
+
+    impl scheduler_runtime_api::SchedulerApi<Block, AccountId, BlockNumber, Hash> for Runtime {
+        fn task_result(id: Hash) -> Option<scheduler::TaskResult<BlockNumber>> {
+            Scheduler::task_result(id)
+        }
+
+        fn agenda(
+            from: BlockNumber,
+            to: BlockNumber,
+            sender: Option<AccountId>,
+        ) -> Vec<scheduler::AgendaEntry<AccountId, BlockNumber, Hash>> {
+            Scheduler::agenda(from, to, sender)
+        }
+
+        fn next_execution(id: Hash) -> Option<scheduler::NextExecution<BlockNumber>> {
+            Scheduler::next_execution(id)
+        }
+
+        fn queue_depth(from: BlockNumber, to: BlockNumber) -> Vec<(BlockNumber, u32)> {
+            Scheduler::queue_depth(from, to)
+        }
+    }
+
     impl substrate_session::SessionKeys<Block> for Runtime {
         fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
             let seed = seed.as_ref().map(|s| rstd::str::from_utf8(&s).expect("Seed is an utf8 string"));
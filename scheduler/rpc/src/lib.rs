@@ -0,0 +1,70 @@
+//! RPC interface for the scheduler module.
+
+use std::sync::Arc;
+
+use client::blockchain::HeaderBackend;
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use scheduler::AgendaEntry;
+use sr_primitives::{
+    generic::BlockId,
+    traits::{Block as BlockT, ProvideRuntimeApi},
+};
+
+pub use scheduler_runtime_api::SchedulerApi as SchedulerRuntimeApi;
+
+/// Queries about scheduled tasks, for dApps and block explorers.
+#[rpc]
+pub trait SchedulerApi<BlockHash, AccountId, BlockNumber, Hash> {
+    /// The tasks due from block `from` to block `to`, in the order they would run, as of
+    /// block `at` or the best block. Only the tasks of `sender` if it is given.
+    #[rpc(name = "scheduler_agenda")]
+    fn agenda(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        sender: Option<AccountId>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<AgendaEntry<AccountId, BlockNumber, Hash>>>;
+}
+
+/// Answers `SchedulerApi` queries from the runtime of `client`.
+pub struct Scheduler<C> {
+    client: Arc<C>,
+}
+
+impl<C> Scheduler<C> {
+    pub fn new(client: Arc<C>) -> Self {
+        Scheduler { client }
+    }
+}
+
+impl<C, Block, AccountId, BlockNumber, Hash> SchedulerApi<Block::Hash, AccountId, BlockNumber, Hash>
+    for Scheduler<C>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi + HeaderBackend<Block>,
+    C::Api: SchedulerRuntimeApi<Block, AccountId, BlockNumber, Hash>,
+    AccountId: Codec,
+    BlockNumber: Codec,
+    Hash: Codec,
+{
+    fn agenda(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        sender: Option<AccountId>,
+        at: Option<Block::Hash>,
+    ) -> Result<Vec<AgendaEntry<AccountId, BlockNumber, Hash>>> {
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.client
+            .runtime_api()
+            .agenda(&at, from, to, sender)
+            .map_err(|error| Error {
+                code: ErrorCode::ServerError(1),
+                message: "Unable to query the agenda.".into(),
+                data: Some(format!("{:?}", error).into()),
+            })
+    }
+}
@@ -0,0 +1,32 @@
+//! Runtime API definition for the scheduler module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use rstd::prelude::*;
+use scheduler::{AgendaEntry, NextExecution, TaskResult};
+
+client::decl_runtime_apis! {
+    /// Read-only queries about scheduled tasks.
+    pub trait SchedulerApi<AccountId, BlockNumber, Hash> where
+        AccountId: Codec,
+        BlockNumber: Codec,
+        Hash: Codec,
+    {
+        /// What happened when the task `id` last ran, if it ran within the retention period.
+        fn task_result(id: Hash) -> Option<TaskResult<BlockNumber>>;
+
+        /// The tasks due from block `from` to block `to`, only those of `sender` if given.
+        fn agenda(
+            from: BlockNumber,
+            to: BlockNumber,
+            sender: Option<AccountId>,
+        ) -> Vec<AgendaEntry<AccountId, BlockNumber, Hash>>;
+
+        /// When the task `id` is due and where it is in that block's queue.
+        fn next_execution(id: Hash) -> Option<NextExecution<BlockNumber>>;
+
+        /// How many tasks are queued for each block from `from` to `to` that has any.
+        fn queue_depth(from: BlockNumber, to: BlockNumber) -> Vec<(BlockNumber, u32)>;
+    }
+}
@@ -0,0 +1,87 @@
+//! Benchmarks of scheduling and running tasks, run against a mock runtime with
+//! `cargo test -p scheduler --release --features runtime-benchmarks -- --nocapture bench`.
+//!
+//! Each benchmark prints the mean time for growing numbers of tasks in a block, followed by
+//! the fit `base + slope * t` in nanoseconds, for a runtime's `WeightInfo` to scale.
+
+use super::*;
+use crate::mock::*;
+
+use runtime_io::with_externalities;
+use std::time::Instant;
+use support::assert_ok;
+
+/// The block the benchmarked tasks are due at.
+const BLOCK: u64 = 10;
+
+/// How often each measurement is taken, each time on fresh storage.
+const REPEATS: u32 = 20;
+
+/// A transfer of 1 from `SENDER`, the `nonce`th task it schedules.
+fn task(nonce: u64) -> TaskOf<Test> {
+    Task {
+        method: TaskCall::Value(balances::Call::transfer(2, 1)),
+        sender: SENDER,
+        nonce,
+        block_number: BLOCK,
+        periodic: None,
+        priority: 0,
+        deposit: 0,
+        tip: 0,
+        valid_until: None,
+        condition: None,
+        dispatch_as: None,
+        idempotency_key: None,
+    }
+}
+
+/// Schedule `count` tasks for `BLOCK`.
+fn schedule(count: u32) {
+    for nonce in 0..u64::from(count) {
+        assert_ok!(Scheduler::schedule_task(task(nonce)));
+    }
+}
+
+/// Mean time in nanoseconds of `call`, run after `setup` on fresh storage.
+fn measure(setup: impl Fn(), call: impl Fn()) -> u128 {
+    let mut total = 0;
+    for _ in 0..REPEATS {
+        with_externalities(&mut new_test_ext(), || {
+            setup();
+            let start = Instant::now();
+            call();
+            total += start.elapsed().as_nanos();
+        });
+    }
+    total / u128::from(REPEATS)
+}
+
+/// Time `call` for each of `counts` and print the linear fit between the smallest and the
+/// largest count.
+fn report(name: &str, counts: &[u32], time: impl Fn(u32) -> u128) {
+    let points: Vec<_> = counts.iter().map(|&count| (count, time(count))).collect();
+    for (count, nanos) in &points {
+        println!("{} t={}: {}ns", name, count, nanos);
+    }
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let slope = last.1.saturating_sub(first.1) / u128::from((last.0 - first.0).max(1));
+    let base = first.1.saturating_sub(slope * u128::from(first.0));
+    println!("{}: {} + {} * t ns", name, base, slope);
+}
+
+#[test]
+fn bench_schedule_task() {
+    report("schedule_task", &[0, 10, 100], |count| {
+        measure(
+            || schedule(count),
+            || assert_ok!(Scheduler::schedule_task(task(u64::from(count)))),
+        )
+    });
+}
+
+#[test]
+fn bench_run_tasks() {
+    report("run_tasks", &[1, 10, 100], |count| {
+        measure(|| schedule(count), || Scheduler::run_tasks(BLOCK))
+    });
+}
@@ -4,22 +4,17 @@
 // 2) Not vulnerable to attack
 //
 // storage design
-// 1. ScheduleTask get(schedule_task): map T::Hash => Option<Task<T>>;
+// 1. ScheduleTask get(task): map T::Hash => Option<TaskOf<T>>;
 // to store delegated task on chain
-// struct Task<T: Trait> {
-//    method: T::Hash,
-//    sender: T::AccountId,
-//    nonce: T::Index,
-//    block_number: T::BlockNumber, // after which block height to run
-//    next: T::Hash, // point to next task
-// }
 //
-// 2. TaskLinkedByBlock get(block_linked_task): map T::BlockNumber => Option<LinkedTasks<T>>;
-// struct LinkedTasks<T: Trait> {
-//    head: T::Hash,
-//    tail: T::Hash,
+// 2. TaskLinkedByBlock get(block_linked_task): map T::BlockNumber => Option<LinkedTasks<T::Hash>>;
+// struct LinkedTasks<Hash> {
+//    head: Hash,
+//    tail: Hash,
+//    len: u32,
 // }
-//
+// with the links between tasks in Links get(task_links): map T::Hash => TaskLinks<T::Hash>,
+// and the tasks that are due but did not fit into their block in PendingTasks.
 //
 // 3. NextNonce get(next_nonce): map T::AccountId => T::Index;
 //
@@ -29,14 +24,15 @@
 // Insert a new task
 // 1) Verify account nonce
 // 2) Bump nonce
-// 3) query TasksByBlock by block number,
-//     if None, insert Some(Vec<TaskOf<T>>)
-//     if Some, get Vec<TaskOf<T>> and
+// 3) link the task into TaskLinkedByBlock of its block, after the tasks of the same or a
+//    higher priority
 //
-// Run tasks (triggered after each block)
+// Run tasks (in on_initialize of each block, not in an offchain worker: tasks dispatched
+// there would change no state the network agrees on, and submitting them back as unsigned
+// transactions would leave when they run up to block authors)
 // 1) query TaskLinkedByBlock by current block number,
-//     if Some, move head after the tail of TaskLinkedByBlock key=0 then clean this key
-// 2) query TaskLinkedByBlock key = 0 (pending tasks), pick first n tasks (according to config), and update head to the next pending task.
+//     if Some, move head after the tail of PendingTasks then clean this key
+// 2) pick first n tasks of PendingTasks (according to config), and update head to the next pending task.
 // 3) dispatch picked tasks to executor
 // 4) write events to track
 
@@ -48,32 +44,433 @@ extern crate support;
 use rstd::prelude::*;
 
 use codec::{Decode, Encode, Codec};
-use sr_primitives::traits::{Bounded, OffchainWorker, One, Zero, Dispatchable};
+use sr_primitives::{traits::{Bounded, Convert, One, Saturating, UniqueSaturatedInto, Zero, Dispatchable, Hash as HashT, Member, Verify}, Perbill};
+
+use support::{decl_event, decl_module, decl_storage, dispatch::{Result, Callable, Dispatchable as SupportDispatchable}, traits::{Currency, EnsureOrigin, ExistenceRequirement, Get, OnUnbalanced, ReservableCurrency, WithdrawReason}, weights::{GetDispatchInfo, Weight}, Parameter, StorageDoubleMap, StorageMap, StorageValue};
+use system::{ensure_root, ensure_signed};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+pub mod weights;
 
-use support::{decl_event, decl_module, decl_storage, dispatch::{Result, Callable, Dispatchable as SupportDispatchable}, StorageMap, StorageValue};
-use system::ensure_signed;
+pub use weights::WeightInfo;
+use weights::{
+    BatchScheduleWeight, DelayedScheduleWeight, DelegatedScheduleWeight, NotePreimageWeight,
+    PeriodicScheduleWeight, ScheduleAfterTaskWeight, ScheduleAsWeight, ScheduleHashWeight,
+    ScheduleWeight, TippedScheduleWeight,
+};
 
 #[derive(Encode, Decode, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "std", derive(Debug))]
-pub struct Task<Call, AccountId, Index, BlockNumber> {
-    method: Call,
+pub struct Task<Call, AccountId, Index, BlockNumber, Balance, Condition, Hash> {
+    method: TaskCall<Call, Hash>,
     sender: AccountId,
     nonce: Index,
     block_number: BlockNumber,
+    /// For a recurring task, its period and how many more times it runs after this time.
+    periodic: Option<(BlockNumber, u32)>,
+    /// Tasks with a lower value run first among those due at the same block.
+    priority: u8,
+    /// The deposit reserved from the sender until the task has run for the last time.
+    deposit: Balance,
+    /// The tip reserved from the sender on top of the deposit, paid to the block author when
+    /// the task runs. Tasks with a higher tip displace the others from full blocks.
+    tip: Balance,
+    /// The last block the task may run at. If it has not run by then, it expires.
+    valid_until: Option<BlockNumber>,
+    /// What must hold for the task to run. If it does not, the task is skipped or postponed
+    /// as `OnUnmetCondition` says.
+    condition: Option<Condition>,
+    /// The origin a task scheduled through `schedule_as` is dispatched with. Other tasks are
+    /// dispatched from the sender's account.
+    dispatch_as: Option<DispatchAs<AccountId>>,
+    /// The key the sender scheduled the task under, so that it is not scheduled twice.
+    idempotency_key: Option<Hash>,
+}
+
+/// The call a task dispatches, or its hash until someone notes the call with
+/// `note_preimage`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TaskCall<Call, Hash> {
+    /// The call itself.
+    Value(Call),
+    /// The hash of the encoded call.
+    Hash(Hash),
+}
+
+/// A call noted for the tasks scheduled with its hash.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Preimage<AccountId, Balance> {
+    /// The encoded call.
+    pub data: Vec<u8>,
+    /// The account that noted it, and reserved its deposit.
+    pub provider: AccountId,
+    /// The deposit reserved from the provider until the preimage is dropped.
+    pub deposit: Balance,
+}
+
+/// The origin a governance task is dispatched with.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub enum DispatchAs<AccountId> {
+    /// The root origin, e.g. to change runtime parameters.
+    Root,
+    /// A signed origin of the given account, e.g. a treasury.
+    Signed(AccountId),
+}
+
+/// Errors raised while scheduling a task.
+#[derive(Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Error {
+    /// The sender already has `MaxPendingPerAccount` tasks that have yet to run.
+    TooManyPendingTasks,
+    /// The task is due at the current block or one before it.
+    BlockInPast,
+    /// The task is due sooner than `MinScheduleDelay` blocks from now.
+    TooSoon,
+    /// The task is due later than `MaxScheduleHorizon` blocks from now.
+    TooFarAhead,
+    /// A task the sender scheduled under the same idempotency key has yet to run.
+    DuplicateKey,
+    /// `MaxDependents` tasks already wait for the task.
+    TooManyDependents,
+}
+
+impl Error {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Error::TooManyPendingTasks => "too many pending tasks",
+            Error::BlockInPast => "task must be scheduled for a future block",
+            Error::TooSoon => "task is due too soon",
+            Error::TooFarAhead => "task is due too far ahead",
+            Error::DuplicateKey => "a task with this idempotency key is pending",
+            Error::TooManyDependents => "too many tasks wait for this task",
+        }
+    }
+}
+
+impl From<Error> for &'static str {
+    fn from(err: Error) -> &'static str {
+        err.as_str()
+    }
+}
+
+/// A task `sender` signs off-chain for someone else to schedule on their behalf with
+/// `schedule_delegated`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct DelegatedTask<AccountId, Call, BlockNumber, Index> {
+    /// The account the call is dispatched from, and the deposit reserved from.
+    pub sender: AccountId,
+    /// The call to dispatch.
+    pub call: Call,
+    /// The block to dispatch the call at.
+    pub block_number: BlockNumber,
+    /// The priority of the task, as for `schedule`.
+    pub priority: u8,
+    /// The sender's next nonce, so that the payload can be used only once.
+    pub nonce: Index,
+    /// The last block the payload may be submitted at.
+    pub expiry: BlockNumber,
+}
+
+pub type DelegatedTaskOf<T> = DelegatedTask<
+    <T as system::Trait>::AccountId,
+    <T as Trait>::Call,
+    <T as system::Trait>::BlockNumber,
+    <T as system::Trait>::Index,
+>;
+
+/// The most blocks `agenda` looks at in one call.
+pub const MAX_AGENDA_BLOCKS: u32 = 1_000;
+
+/// Where a task of the agenda stands.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub enum TaskStatus<Hash> {
+    /// The task is queued for its block.
+    Queued,
+    /// The task waits for the given task to run, and is due in the block after it.
+    Waiting(Hash),
+}
+
+/// A task that has yet to run, as `agenda` lists it.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct AgendaEntry<AccountId, BlockNumber, Hash> {
+    /// The id of the task.
+    pub id: Hash,
+    /// The account the call is dispatched from.
+    pub sender: AccountId,
+    /// The encoded call the task dispatches, empty if it was scheduled by hash and its
+    /// preimage has not been noted yet.
+    pub call: Vec<u8>,
+    /// The block the task is due at.
+    pub block_number: BlockNumber,
+    /// Whether the task is queued or waits for another one.
+    pub status: TaskStatus<Hash>,
+}
+
+/// When a task that has yet to run is due.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct NextExecution<BlockNumber> {
+    /// The block the task is due at.
+    pub block_number: BlockNumber,
+    /// How many tasks run before it in that block, or `None` if it waits for another task.
+    /// Beyond `MaxTasksPerBlock`, it is postponed.
+    pub position: Option<u32>,
+}
+
+/// The ends of a queue of tasks, linked through `Links`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct LinkedTasks<Hash> {
+    /// The task that runs first.
+    pub head: Hash,
+    /// The task that runs last.
+    pub tail: Hash,
+    /// How many tasks the queue holds.
+    pub len: u32,
+}
+
+/// The neighbours of a task in its queue.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct TaskLinks<Hash> {
+    /// The task that runs before it.
+    pub prev: Option<Hash>,
+    /// The task that runs after it.
+    pub next: Option<Hash>,
+}
+
+/// A queue a task may be linked into.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Queue<BlockNumber> {
+    /// The tasks due at a block that has not started yet.
+    Block(BlockNumber),
+    /// The tasks that are due but have yet to run.
+    Pending,
+}
+
+/// What happened when a task ran, kept for `ResultRetention` blocks.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct TaskResult<BlockNumber> {
+    /// The error the call failed with, or `None` if it succeeded.
+    pub error: Option<Vec<u8>>,
+    /// The weight of the call.
+    pub weight: Weight,
+    /// The block the task ran at.
+    pub executed_at: BlockNumber,
+}
+
+pub type TaskOf<T> = Task<<T as Trait>::Call, <T as system::Trait>::AccountId, <T as system::Trait>::Index, <T as system::Trait>::BlockNumber, BalanceOf<T>, ConditionOf<T>, <T as system::Trait>::Hash>;
+
+pub type TaskCallOf<T> = TaskCall<<T as Trait>::Call, <T as system::Trait>::Hash>;
+
+pub type ConditionOf<T> = <<T as Trait>::ScheduleCondition as ScheduleCondition<
+    <T as system::Trait>::AccountId,
+>>::Condition;
+
+type BalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+
+/// Decides which calls may be scheduled.
+pub trait Filter<Call> {
+    /// Whether `call` may be scheduled and dispatched on its sender's behalf.
+    fn filter(call: &Call) -> bool;
 }
 
-pub type TaskOf<T> = Task<<T as system::Trait>::Call, <T as system::Trait>::AccountId, <T as system::Trait>::Index, <T as system::Trait>::BlockNumber>;
+impl<Call> Filter<Call> for () {
+    fn filter(_: &Call) -> bool {
+        false
+    }
+}
+
+/// Told about every run of a task, so that the module that scheduled it can follow up
+/// without reading the scheduler's events.
+pub trait OnTaskExecuted<Hash, AccountId> {
+    /// The task `id` of `sender` ran, with `result`. A skipped run is not reported.
+    fn on_task_executed(id: &Hash, sender: &AccountId, result: Result);
+}
+
+impl<Hash, AccountId> OnTaskExecuted<Hash, AccountId> for () {
+    fn on_task_executed(_: &Hash, _: &AccountId, _: Result) {}
+}
+
+/// Conditions a task may be made to wait for, checked each time it is due.
+pub trait ScheduleCondition<AccountId> {
+    /// A condition on chain state, such as a balance threshold.
+    type Condition: Parameter;
+
+    /// Whether `condition` holds for a task sent by `sender`. Must not change any state.
+    fn holds(condition: &Self::Condition, sender: &AccountId) -> bool;
+}
+
+impl<AccountId> ScheduleCondition<AccountId> for () {
+    type Condition = ();
+
+    fn holds(_: &(), _: &AccountId) -> bool {
+        true
+    }
+}
+
+/// What happens to a task that is due while its condition does not hold.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum UnmetCondition {
+    /// The task does not run this time, as if its run had been skipped.
+    Skip,
+    /// The task is moved to the next block, until it runs or expires.
+    Postpone,
+}
 
 pub trait Trait: system::Trait {
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+    /// The calls a task may dispatch.
+    type Call: Parameter + Dispatchable<Origin = Self::Origin> + GetDispatchInfo;
+
+    /// The calls that may be scheduled. Tasks are dispatched under a signed origin, so this
+    /// should only let through calls that are harmless to dispatch late. `()` lets none.
+    type ScheduleFilter: Filter<<Self as Trait>::Call>;
+
+    /// Signature of an account, for scheduling tasks on its behalf.
+    type Signature: Member + Parameter + Verify<Signer = Self::AccountId>;
+
+    /// The origin that may schedule tasks dispatched with another origin, such as root.
+    type ScheduleAsOrigin: EnsureOrigin<Self::Origin>;
+
+    /// The origin that may pause and resume running tasks.
+    type PauseOrigin: EnsureOrigin<Self::Origin>;
+
+    /// The origin that may cancel any task with `force_cancel`.
+    type ForceCancelOrigin: EnsureOrigin<Self::Origin>;
+
+    /// The conditions tasks may be made to wait for.
+    type ScheduleCondition: ScheduleCondition<Self::AccountId>;
+
+    /// What happens to a task that is due while its condition does not hold.
+    type OnUnmetCondition: Get<UnmetCondition>;
+
+    /// How many tasks run in one block at most. The rest are postponed to the next block.
+    type MaxTasksPerBlock: Get<u32>;
+
+    /// The part of `MaximumBlockWeight` the calls of tasks may use in one block. Once they have
+    /// used it up, the rest are postponed to the next block.
+    type MaxScheduledWeight: Get<Perbill>;
+
+    /// The currency scheduling deposits are reserved in.
+    type Currency: ReservableCurrency<Self::AccountId>;
+
+    /// Deposit reserved for every byte of a scheduled call.
+    type DepositPerByte: Get<BalanceOf<Self>>;
+
+    /// Deposit reserved for every block between scheduling a task and its last run.
+    type DepositPerBlock: Get<BalanceOf<Self>>;
+
+    /// The part of its deposit a task forfeits if its last run fails.
+    type FailureSlash: Get<Perbill>;
+
+    /// The part of its deposit a task forfeits if it expires before it has run.
+    type ExpiryFee: Get<Perbill>;
+
+    /// The part of its deposit a task forfeits if it is cancelled with `force_cancel`. Zero
+    /// refunds it in full.
+    type ForceCancelSlash: Get<Perbill>;
+
+    /// For how many blocks the result of a task's latest run is kept.
+    type ResultRetention: Get<Self::BlockNumber>;
+
+    /// How many blocks later than it is due `reschedule` may move a task at most.
+    type MaxRescheduleDelay: Get<Self::BlockNumber>;
+
+    /// How many tasks that have yet to run an account may have at most.
+    type MaxPendingPerAccount: Get<u32>;
+
+    /// How many tasks may wait for one task with `schedule_after_task` at most.
+    type MaxDependents: Get<u32>;
+
+    /// How many blocks from now a task must be due at least. Tasks are always due after the
+    /// current block.
+    type MinScheduleDelay: Get<Self::BlockNumber>;
+
+    /// How many blocks from now a task may be due at most.
+    type MaxScheduleHorizon: Get<Self::BlockNumber>;
+
+    /// Handler for slashed deposits.
+    type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+    /// The fee the sender pays for the weight of a task's call each time it runs.
+    type WeightToFee: Convert<Weight, BalanceOf<Self>>;
+
+    /// Handler for the fees paid for running tasks.
+    type ExecutionFees: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+    /// Handler for the tips of tasks, paid as they run, e.g. to the block author.
+    type Tips: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+    /// Told about the outcome of every run of a task.
+    type OnTaskExecuted: OnTaskExecuted<Self::Hash, Self::AccountId>;
+
+    /// Weights of scheduling and running tasks.
+    type WeightInfo: WeightInfo;
 }
 
 // This module's storage items.
 decl_storage! {
     trait Store for Module<T: Trait> as TemplateModule {
-		pub TasksByBlock get(tasks_by_block): map T::BlockNumber => Option<Vec<TaskOf<T>>>;
+		/// Every task not yet run for the last time, by its id.
+		pub ScheduleTask get(task): map T::Hash => Option<TaskOf<T>>;
+		/// The tasks due at each block that has not started yet, in the order they run.
+		pub TaskLinkedByBlock get(block_linked_task):
+			map T::BlockNumber => Option<LinkedTasks<T::Hash>>;
+		/// The tasks that are due but did not fit into their block, oldest first. They run
+		/// ahead of the tasks of the next block.
+		pub PendingTasks get(pending_tasks): Option<LinkedTasks<T::Hash>>;
+		/// The neighbours of each queued task.
+		pub Links get(task_links): map T::Hash => TaskLinks<T::Hash>;
+		/// Whether running tasks is halted. Tasks still queue up while it is.
+		pub SchedulerPaused get(paused): bool;
+		/// The task each waiting task runs after. Waiting tasks are in no block's queue.
+		pub WaitingFor get(waiting_for): map T::Hash => Option<T::Hash>;
+		/// The tasks waiting for each task.
+		pub Dependents get(dependents): map T::Hash => Vec<T::Hash>;
+		/// The result of the latest run of each task that ran in the last `ResultRetention`
+		/// blocks.
+		pub TaskResults get(task_result): map T::Hash => Option<TaskResult<T::BlockNumber>>;
+		/// The ids of the tasks whose results are dropped at each block.
+		ResultsExpiringAt: map T::BlockNumber => Vec<T::Hash>;
         pub NextNonce get(next_nonce): map T::AccountId => T::Index;
+		/// The ids of the tasks that have yet to run of each account.
+		pub TasksBySender get(tasks_by_sender): map T::AccountId => Vec<T::Hash>;
+		/// The calls noted for tasks scheduled with `schedule_hash`, by their hash.
+		pub Preimages get(preimage): map T::Hash => Option<Preimage<T::AccountId, BalanceOf<T>>>;
+		/// The task each account scheduled under each idempotency key, until it has run for the
+		/// last time or is dropped.
+		pub IdempotencyKeys get(idempotent_task):
+			double_map T::AccountId, blake2_256(T::Hash) => Option<T::Hash>;
     }
+	add_extra_genesis {
+		/// Tasks the chain starts with, such as changes to enact at a given block: the origin
+		/// each is dispatched with, its encoded call and its block. They are scheduled like
+		/// `schedule_as` tasks.
+		config(tasks): Vec<(DispatchAs<T::AccountId>, Vec<u8>, T::BlockNumber)>;
+		build(|config: &GenesisConfig<T>| {
+			for (dispatch_as, call, at) in &config.tasks {
+				let call = <T as Trait>::Call::decode(&mut &call[..])
+					.expect("genesis task calls are valid calls");
+				Module::<T>::enqueue_dispatch_as(dispatch_as.clone(), call, *at, 0);
+			}
+		});
+	}
 }
 
 // The module's dispatchable functions.
@@ -84,21 +481,442 @@ decl_module! {
         // this is needed only if you are using events in your module
         fn deposit_event() = default;
 
-		/// Run tasks.
-        fn offchain_worker(block_number: T::BlockNumber) {
-			let tasks = match <TasksByBlock<T>>::take(&block_number) {
-				Some(t) => t,
-				None => return,
+		/// How many tasks run in one block at most.
+		const MaxTasksPerBlock: u32 = T::MaxTasksPerBlock::get();
+
+		/// The part of `MaximumBlockWeight` the calls of tasks may use in one block.
+		const MaxScheduledWeight: Perbill = T::MaxScheduledWeight::get();
+
+		/// Deposit reserved for every byte of a scheduled call.
+		const DepositPerByte: BalanceOf<T> = T::DepositPerByte::get();
+
+		/// Deposit reserved for every block between scheduling a task and its last run.
+		const DepositPerBlock: BalanceOf<T> = T::DepositPerBlock::get();
+
+		/// The part of its deposit a task forfeits if its last run fails.
+		const FailureSlash: Perbill = T::FailureSlash::get();
+
+		/// The part of its deposit a task forfeits if it expires before it has run.
+		const ExpiryFee: Perbill = T::ExpiryFee::get();
+
+		/// The part of its deposit a task forfeits if it is cancelled with `force_cancel`.
+		const ForceCancelSlash: Perbill = T::ForceCancelSlash::get();
+
+		/// For how many blocks the result of a task's latest run is kept.
+		const ResultRetention: T::BlockNumber = T::ResultRetention::get();
+
+		/// How many blocks later than it is due `reschedule` may move a task at most.
+		const MaxRescheduleDelay: T::BlockNumber = T::MaxRescheduleDelay::get();
+
+		/// How many tasks that have yet to run an account may have at most.
+		const MaxPendingPerAccount: u32 = T::MaxPendingPerAccount::get();
+
+		/// How many tasks may wait for one task at most.
+		const MaxDependents: u32 = T::MaxDependents::get();
+
+		/// How many blocks from now a task must be due at least.
+		const MinScheduleDelay: T::BlockNumber = T::MinScheduleDelay::get();
+
+		/// How many blocks from now a task may be due at most.
+		const MaxScheduleHorizon: T::BlockNumber = T::MaxScheduleHorizon::get();
+
+		/// Dispatch `call` from the caller's account at block `at`. The task takes the
+		/// caller's next nonce and runs before the tasks of a higher `priority` value due at
+		/// the same block. Its weight includes the call's, since running it is not a
+		/// transaction of its own. The caller reserves a deposit for the size of the call and
+		/// how far ahead it is scheduled, refunded once it runs successfully or is cancelled.
+		/// If it has not run by block `valid_until`, for instance because it was postponed
+		/// too often, it expires and the deposit is refunded less `ExpiryFee`. A block that
+		/// holds `MaxTasksPerBlock` tasks already only takes tasks that outbid one of them
+		/// with `schedule_with_tip`. A caller that may submit the same task twice, e.g. when
+		/// retrying, can pass an `idempotency_key`: scheduling fails while a task of theirs
+		/// scheduled under the same key has yet to run for the last time, and the key is free
+		/// again once it has run, expired or been cancelled. Only `schedule` takes a key; a
+		/// caller retrying the other scheduling calls should check `tasks_by_sender` first.
+		#[weight = ScheduleWeight::<T>::default()]
+		fn schedule(
+			origin,
+			call: Box<<T as Trait>::Call>,
+			at: T::BlockNumber,
+			priority: u8,
+			valid_until: Option<T::BlockNumber>,
+			idempotency_key: Option<T::Hash>
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+			if let Some(key) = &idempotency_key {
+				ensure!(!<IdempotencyKeys<T>>::exists(&sender, key), Error::DuplicateKey);
+			}
+			let id = Self::task_id(&sender, &Self::next_nonce(&sender), &call);
+			let method = TaskCall::Value(*call);
+			let tip = Zero::zero();
+			Self::schedule_call(sender.clone(), method, at, None, priority, tip, valid_until)?;
+			if let Some(key) = idempotency_key {
+				<IdempotencyKeys<T>>::insert(&sender, &key, id);
+				<ScheduleTask<T>>::mutate(id, |task| {
+					if let Some(task) = task {
+						task.idempotency_key = Some(key);
+					}
+				});
+			}
+			Ok(())
+		}
+
+		/// Schedule `call` like `schedule` with priority `0`, `delay_in_blocks` blocks after the
+		/// one the transaction is included in, so that a transaction included later than
+		/// expected cannot make the task due in the past.
+		#[weight = DelayedScheduleWeight::<T>::default()]
+		fn schedule_after(
+			origin,
+			call: Box<<T as Trait>::Call>,
+			delay_in_blocks: T::BlockNumber
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+			let at = <system::Module<T>>::block_number().saturating_add(delay_in_blocks);
+			let method = TaskCall::Value(*call);
+			Self::schedule_call(sender, method, at, None, 0, Zero::zero(), None)
+		}
+
+		/// Schedule `call` like `schedule`, bidding `tip` for a place in block `at`. The tip is
+		/// reserved along with the deposit and paid to the author of the block the task runs
+		/// in. A block holding `MaxTasksPerBlock` tasks takes no more unless the new task tips
+		/// more than the lowest tip there: that task is then postponed to the next block.
+		#[weight = TippedScheduleWeight::<T>::default()]
+		fn schedule_with_tip(
+			origin,
+			call: Box<<T as Trait>::Call>,
+			at: T::BlockNumber,
+			priority: u8,
+			tip: BalanceOf<T>,
+			valid_until: Option<T::BlockNumber>
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+			Self::schedule_call(sender, TaskCall::Value(*call), at, None, priority, tip, valid_until)
+		}
+
+		/// Schedule the call with the hash `call_hash` like `schedule`, keeping only the hash,
+		/// so that a big call takes little room until it is due. The deposit covers the hash.
+		/// The call must be noted with `note_preimage` before the task runs, or the run fails,
+		/// and its weight is charged then. A task whose call is never noted is cheap to cancel.
+		#[weight = ScheduleHashWeight::<T>::default()]
+		fn schedule_hash(
+			origin,
+			call_hash: T::Hash,
+			at: T::BlockNumber,
+			priority: u8,
+			valid_until: Option<T::BlockNumber>
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+			let method = TaskCall::Hash(call_hash);
+			Self::schedule_call(sender, method, at, None, priority, Zero::zero(), valid_until)
+		}
+
+		/// Note `encoded_call` for the tasks scheduled with its hash, reserving `DepositPerByte`
+		/// for each of its bytes from the caller, who may be anyone. The preimage is dropped
+		/// and the deposit refunded once a task with its hash has run for the last time or is
+		/// dropped, or when the caller unnotes it.
+		#[weight = NotePreimageWeight::<T>::default()]
+		fn note_preimage(origin, encoded_call: Vec<u8>) -> Result {
+			let provider = ensure_signed(origin)?;
+			let hash = T::Hashing::hash(&encoded_call);
+			ensure!(!<Preimages<T>>::exists(&hash), "preimage already noted");
+			let call = <T as Trait>::Call::decode(&mut &encoded_call[..])
+				.map_err(|_| "preimage is not a call")?;
+			ensure!(T::ScheduleFilter::filter(&call), "call may not be scheduled");
+			let size = BalanceOf::<T>::from(encoded_call.len() as u32);
+			let deposit = T::DepositPerByte::get().saturating_mul(size);
+			T::Currency::reserve(&provider, deposit)
+				.map_err(|_| "not enough free balance for preimage deposit")?;
+			<Preimages<T>>::insert(hash, Preimage { data: encoded_call, provider, deposit });
+			Self::deposit_event(RawEvent::PreimageNoted(hash));
+			Ok(())
+		}
+
+		/// Drop the preimage with the hash `call_hash` the caller noted, refunding its deposit.
+		/// The tasks scheduled with the hash fail unless it is noted again before they run.
+		fn unnote_preimage(origin, call_hash: T::Hash) -> Result {
+			let provider = ensure_signed(origin)?;
+			let preimage = Self::preimage(&call_hash).ok_or("no such preimage")?;
+			ensure!(preimage.provider == provider, "only the provider may unnote a preimage");
+			Self::drop_preimage(&TaskCall::Hash(call_hash));
+			Ok(())
+		}
+
+		/// Dispatch `call` from the caller's account `count` times, every `period` blocks from
+		/// block `start`, at `priority` like `schedule`. Cancelling the task stops the
+		/// occurrences still to come, and so does `valid_until` passing.
+		#[weight = PeriodicScheduleWeight::<T>::default()]
+		fn schedule_periodic(
+			origin,
+			call: Box<<T as Trait>::Call>,
+			start: T::BlockNumber,
+			period: T::BlockNumber,
+			count: u32,
+			priority: u8,
+			valid_until: Option<T::BlockNumber>
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+			ensure!(!period.is_zero(), "period must be non-zero");
+			ensure!(count > 0, "repetition count must be non-zero");
+			let periodic = Some((period, count - 1));
+			let method = TaskCall::Value(*call);
+			Self::schedule_call(sender, method, start, periodic, priority, Zero::zero(), valid_until)
+		}
+
+		/// Dispatch `call` with `dispatch_as` at block `at`, at `priority` like `schedule`, so
+		/// that governance can enact changes at a future block. Only `ScheduleAsOrigin` may
+		/// do so. Such tasks reserve no deposit, pay no execution fee, are not subject to
+		/// `ScheduleFilter` and can be cancelled by root only.
+		#[weight = ScheduleAsWeight::<T>::default()]
+		fn schedule_as(
+			origin,
+			dispatch_as: DispatchAs<T::AccountId>,
+			call: Box<<T as Trait>::Call>,
+			at: T::BlockNumber,
+			priority: u8
+		) -> Result {
+			T::ScheduleAsOrigin::ensure_origin(origin)?;
+			Self::ensure_schedulable_at(at)?;
+			let id = Self::enqueue_dispatch_as(dispatch_as, *call, at, priority);
+			Self::deposit_event(RawEvent::TaskScheduled(id, at));
+			Ok(())
+		}
+
+		/// Schedule each of `tasks`, a call and the block to dispatch it at, at `priority` like
+		/// `schedule`. Either all of them are scheduled or, if any is invalid, none. They
+		/// share the caller's next nonce, and the deposit for all of them is reserved at once.
+		/// None of them may be due at a block that, with the tasks before it in the batch, holds
+		/// `MaxTasksPerBlock` tasks already: a batch carries no tip to outbid them.
+		#[weight = BatchScheduleWeight::<T>::default()]
+		fn schedule_batch(
+			origin,
+			tasks: Vec<(<T as Trait>::Call, T::BlockNumber)>,
+			priority: u8
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+			ensure!(!tasks.is_empty(), "batch must not be empty");
+			Self::ensure_pending_room(&sender, tasks.len() as u32)?;
+			let mut deposits = Vec::with_capacity(tasks.len());
+			for (index, (call, at)) in tasks.iter().enumerate() {
+				Self::ensure_schedulable_at(*at)?;
+				ensure!(T::ScheduleFilter::filter(call), "call may not be scheduled");
+				let batched = tasks[..index].iter().filter(|(_, other)| other == at).count();
+				ensure!(
+					Self::queued_at(*at).saturating_add(batched as u32) < T::MaxTasksPerBlock::get(),
+					"block is full"
+				);
+				deposits.push(Self::deposit_for(call, *at, None));
+			}
+			let total = deposits.iter().fold(Zero::zero(), |total: BalanceOf<T>, deposit| {
+				total.saturating_add(*deposit)
+			});
+			T::Currency::reserve(&sender, total)
+				.map_err(|_| "not enough free balance for scheduling deposit")?;
+
+			let nonce = Self::next_nonce(&sender);
+			Self::inc_account_nonce(&sender);
+			for (index, ((call, at), deposit)) in tasks.into_iter().zip(deposits).enumerate() {
+				let id = Self::batch_task_id(&sender, &nonce, index as u32, &call);
+				let task = Task {
+					method: TaskCall::Value(call),
+					sender: sender.clone(),
+					nonce,
+					block_number: at,
+					periodic: None,
+					priority,
+					deposit,
+					tip: Zero::zero(),
+					valid_until: None,
+					condition: None,
+					dispatch_as: None,
+					idempotency_key: None,
+				};
+				Self::add_pending(&sender, id);
+				Self::enqueue(id, task);
+				Self::deposit_event(RawEvent::TaskScheduled(id, at));
+			}
+			Ok(())
+		}
+
+		/// Schedule the task `payload` describes on behalf of its sender, who signed it as
+		/// `signature`. The caller only pays for the transaction: the task runs as the sender,
+		/// its deposit is reserved from them and it takes their nonce, which the payload must
+		/// carry. The payload is void after block `expiry`.
+		#[weight = DelegatedScheduleWeight::<T>::default()]
+		fn schedule_delegated(
+			origin,
+			payload: DelegatedTaskOf<T>,
+			signature: T::Signature
+		) -> Result {
+			ensure_signed(origin)?;
+			ensure!(
+				<system::Module<T>>::block_number() <= payload.expiry,
+				"delegation has expired"
+			);
+			ensure!(payload.nonce == Self::next_nonce(&payload.sender), "invalid nonce");
+			ensure!(
+				payload.using_encoded(|encoded| signature.verify(encoded, &payload.sender)),
+				"invalid delegation signature"
+			);
+			let DelegatedTask { sender, call, block_number, priority, .. } = payload;
+			let method = TaskCall::Value(call);
+			Self::schedule_call(sender, method, block_number, None, priority, Zero::zero(), None)
+		}
+
+		/// Dispatch `call` from the caller's account in the block after the task `after` has
+		/// run for the last time, if that run succeeds. If it fails, expires or is cancelled,
+		/// so is this task. Until then the task waits outside of any block's queue. `priority`
+		/// and `valid_until` are as for `schedule`, and the deposit covers the blocks until
+		/// `after` runs for the last time. The block after that must be within
+		/// `MaxScheduleHorizon`, but not `MinScheduleDelay`, from now. At most `MaxDependents`
+		/// tasks may wait for one task.
+		#[weight = ScheduleAfterTaskWeight::<T>::default()]
+		fn schedule_after_task(
+			origin,
+			call: Box<<T as Trait>::Call>,
+			after: T::Hash,
+			priority: u8,
+			valid_until: Option<T::BlockNumber>
+		) -> Result {
+			let sender = ensure_signed(origin)?;
+			let first = Self::task(&after).ok_or("no such task")?;
+			ensure!(
+				(Self::dependents(&after).len() as u32) < T::MaxDependents::get(),
+				Error::TooManyDependents
+			);
+			Self::ensure_pending_room(&sender, 1)?;
+			let call = *call;
+			ensure!(T::ScheduleFilter::filter(&call), "call may not be scheduled");
+			let at = Self::last_run(first.block_number, first.periodic) + One::one();
+			// Only the horizon applies: the task runs after `after`, which may be overdue by now.
+			Self::ensure_within_horizon(at)?;
+			ensure!(
+				valid_until.map_or(true, |until| at <= until),
+				"task must be scheduled before it expires"
+			);
+			let deposit = Self::deposit_for(&call, at, None);
+			T::Currency::reserve(&sender, deposit)
+				.map_err(|_| "not enough free balance for scheduling deposit")?;
+
+			let nonce = Self::next_nonce(&sender);
+			Self::inc_account_nonce(&sender);
+			let id = Self::task_id(&sender, &nonce, &call);
+			Self::add_pending(&sender, id);
+			let task = Task {
+				method: TaskCall::Value(call),
+				sender,
+				nonce,
+				block_number: at,
+				periodic: None,
+				priority,
+				deposit,
+				tip: Zero::zero(),
+				valid_until,
+				condition: None,
+				dispatch_as: None,
+				idempotency_key: None,
 			};
+			<ScheduleTask<T>>::insert(id, task);
+			<WaitingFor<T>>::insert(id, after);
+			<Dependents<T>>::mutate(after, |ids| ids.push(id));
+			Self::deposit_event(RawEvent::TaskWaiting(id, after));
+			Ok(())
+		}
 
-			tasks.into_iter().for_each(|t| {
-				let origin = T::Origin::from(system::RawOrigin::Signed(t.sender.clone()));
-				if let Ok(_) = t.method.clone().dispatch(origin) {
-					Self::deposit_event(RawEvent::TaskExecutedOk(block_number, t.sender, t.nonce, t.method));
-				} else {
-					Self::deposit_event(RawEvent::TaskExecutedErr(block_number, t.sender, t.nonce, t.method));
-				}
-			})
+		/// Make the caller's task `id` run only while `condition` holds, or always if it is
+		/// `None`. A task that is postponed while its condition does not hold should have a
+		/// deadline, or it may stay queued for good.
+		fn set_condition(origin, id: T::Hash, condition: Option<ConditionOf<T>>) -> Result {
+			let sender = ensure_signed(origin)?;
+			let mut task = Self::task(&id).ok_or("no such task")?;
+			ensure!(sender == task.sender, "only the sender may set a task's condition");
+			task.condition = condition;
+			<ScheduleTask<T>>::insert(id, task);
+			Self::deposit_event(RawEvent::TaskConditionSet(id));
+			Ok(())
+		}
+
+		/// Cancel the task `id`, and the tasks waiting for it, refunding their deposits. Only
+		/// its sender or root may cancel it.
+		fn cancel(origin, id: T::Hash) -> Result {
+			let task = Self::task(&id).ok_or("no such task")?;
+			if ensure_root(origin.clone()).is_err() {
+				let caller = ensure_signed(origin)?;
+				ensure!(caller == task.sender, "only the sender may cancel a task");
+			}
+			Self::cancel_task(id);
+			Ok(())
+		}
+
+		/// Cancel the task `id` of any sender, e.g. because it is malicious or stuck, slashing
+		/// `ForceCancelSlash` of its deposit. The tasks waiting for it are cancelled too, and
+		/// their deposits refunded. Only `ForceCancelOrigin` may force a task to be cancelled.
+		fn force_cancel(origin, id: T::Hash) -> Result {
+			T::ForceCancelOrigin::ensure_origin(origin)?;
+			let task = Self::take_task(&id).ok_or("no such task")?;
+			let slashed = Self::release_deposit(&task, T::ForceCancelSlash::get());
+			Self::deposit_event(RawEvent::TaskForceCancelled(id, slashed));
+			Self::cancel_dependents(&id);
+			Ok(())
+		}
+
+		/// Postpone the caller's task `id` to the later block `at`, at most
+		/// `MaxRescheduleDelay` blocks after the one it is due at and not past its deadline.
+		/// The task keeps its nonce and deposit.
+		fn reschedule(origin, id: T::Hash, at: T::BlockNumber) -> Result {
+			let sender = ensure_signed(origin)?;
+			let mut task = Self::task(&id).ok_or("no such task")?;
+			ensure!(sender == task.sender, "only the sender may reschedule a task");
+			ensure!(!<WaitingFor<T>>::exists(&id), "task is waiting for another task");
+			let old = task.block_number;
+			ensure!(at > old, "task may only be moved to a later block");
+			Self::ensure_schedulable_at(at)?;
+			ensure!(
+				at - old <= T::MaxRescheduleDelay::get(),
+				"task may not be moved that far"
+			);
+			ensure!(
+				task.valid_until.map_or(true, |until| at <= until),
+				"task must be scheduled before it expires"
+			);
+			let outbid = Self::outbid(at, task.tip)?;
+			if let Some(outbid) = outbid {
+				Self::displace(outbid, at);
+			}
+			Self::unlink(Self::queue_of(old), &id);
+			task.block_number = at;
+			Self::enqueue(id, task);
+			Self::deposit_event(RawEvent::TaskRescheduled(id, old, at));
+			Ok(())
+		}
+
+		/// Stop running tasks, e.g. while a kind of call that can be scheduled is found to be
+		/// exploitable. Tasks can still be scheduled, and the ones that fall due wait among
+		/// the pending tasks. Only `PauseOrigin` may pause the scheduler.
+		fn pause(origin) -> Result {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(!Self::paused(), "scheduler is already paused");
+			<SchedulerPaused>::put(true);
+			Self::deposit_event(RawEvent::SchedulerPaused);
+			Ok(())
+		}
+
+		/// Run tasks again, starting with those that fell due while the scheduler was paused.
+		/// Only `PauseOrigin` may resume the scheduler.
+		fn resume(origin) -> Result {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(Self::paused(), "scheduler is not paused");
+			<SchedulerPaused>::put(false);
+			Self::deposit_event(RawEvent::SchedulerResumed);
+			Ok(())
+		}
+
+		/// Run the tasks due at this block, before any extrinsic, so that every node executes
+		/// them the same way. Their weight was charged when they were scheduled, and at most
+		/// `MaxTasksPerBlock` of them, using at most `MaxScheduledWeight` of the block, run
+		/// per block. Results older than `ResultRetention` are dropped first.
+        fn on_initialize(block_number: T::BlockNumber) {
+			Self::prune_results(block_number);
+			Self::run_tasks(block_number);
         }
     }
 }
@@ -106,42 +924,843 @@ decl_module! {
 decl_event!(
     pub enum Event<T>
     where
-        AccountId = <T as system::Trait>::AccountId,
 		BlockNumber = <T as system::Trait>::BlockNumber,
-		Nonce = <T as system::Trait>::Index,
-		Method = <T as system::Trait>::Call,
+		Hash = <T as system::Trait>::Hash,
+		Balance = BalanceOf<T>,
     {
-		/// (block_number, who, nonce, method)
-		TaskExecutedOk(BlockNumber, AccountId, Nonce, Method),
-		TaskExecutedErr(BlockNumber, AccountId, Nonce, Method),
+		/// A task was scheduled to run at a block. (id, block_number)
+		TaskScheduled(Hash, BlockNumber),
+		/// (id)
+		TaskConditionSet(Hash),
+		/// A task was due while its condition did not hold, and did not run. (id, block_number)
+		TaskSkipped(Hash, BlockNumber),
+		/// A task waits for another one to run. (id, after)
+		TaskWaiting(Hash, Hash),
+		/// A task was moved to a later block. (id, old_block_number, new_block_number)
+		TaskRescheduled(Hash, BlockNumber, BlockNumber),
+		/// (id)
+		TaskCancelled(Hash),
+		/// A task was cancelled by `ForceCancelOrigin`. (id, slashed)
+		TaskForceCancelled(Hash, Balance),
+		/// A task's condition did not hold, or it did not fit into its block, and it was moved
+		/// to a later block. (id, block_number)
+		TaskPostponed(Hash, BlockNumber),
+		/// A task was displaced from a full block by one with a higher tip, and postponed.
+		/// (id, block_number)
+		TaskOutbid(Hash, BlockNumber),
+		/// Tasks that are due did not fit into the block and run in later ones. (count)
+		TasksOverdue(u32),
+		SchedulerPaused,
+		SchedulerResumed,
+		/// A task's last run failed and part of its deposit was slashed. (id, slashed)
+		DepositSlashed(Hash, Balance),
+		/// A task's sender could not pay the fee for running it, so it did not run. (id, fee)
+		ExecutionFeeUnpaid(Hash, Balance),
+		/// A task had not run by its deadline and was dropped. (id, fee)
+		TaskExpired(Hash, Balance),
+		/// A task ran, as the `index`th of its block. Its call is in `ScheduleTask` until its
+		/// last run. (id, block_number, index)
+		TaskExecutedOk(Hash, BlockNumber, u32),
+		/// A task's call failed. (id, block_number, index, error)
+		TaskExecutedErr(Hash, BlockNumber, u32, Vec<u8>),
+		/// The call with a hash was noted for the tasks scheduled with it. (call_hash)
+		PreimageNoted(Hash),
+		/// (call_hash)
+		PreimageDropped(Hash),
     }
 );
 
 impl<T: Trait> Module<T> {
+	/// The id of the task `sender` schedules under `nonce` to dispatch `call`.
+	pub fn task_id(sender: &T::AccountId, nonce: &T::Index, call: &<T as Trait>::Call) -> T::Hash {
+		T::Hashing::hash_of(&(sender, nonce, call))
+	}
+
+	/// The id of the `index`th task of the batch `sender` schedules under `nonce`, which
+	/// dispatches `call`.
+	pub fn batch_task_id(
+		sender: &T::AccountId,
+		nonce: &T::Index,
+		index: u32,
+		call: &<T as Trait>::Call,
+	) -> T::Hash {
+		T::Hashing::hash_of(&(sender, nonce, index, call))
+	}
+
 	/// Schedule a task.
 	pub fn schedule_task(task: TaskOf<T>) -> Result {
 		let expected_nonce = <NextNonce<T>>::get(&task.sender);
 		if task.nonce != expected_nonce {
 			return Err("invalid nonce");
 		}
+		if let TaskCall::Value(call) = &task.method {
+			ensure!(T::ScheduleFilter::filter(call), "call may not be scheduled");
+		}
+		Self::ensure_pending_room(&task.sender, 1)?;
 
 		Self::inc_account_nonce(&task.sender);
-
+		let id = match &task.method {
+			TaskCall::Value(call) => Self::task_id(&task.sender, &task.nonce, call),
+			TaskCall::Hash(hash) => T::Hashing::hash_of(&(&task.sender, &task.nonce, hash)),
+		};
+		Self::add_pending(&task.sender, id);
 		let block_number = task.block_number;
-		let tasks = if let Some(mut tasks) = <TasksByBlock<T>>::take(&task.block_number) {
-			tasks.push(task);
-			tasks
+		Self::enqueue(id, task);
+		Self::deposit_event(RawEvent::TaskScheduled(id, block_number));
+
+		Ok(())
+	}
+
+	/// Queue a task dispatching `call` with `dispatch_as` at block `at`, without a deposit,
+	/// returning its id.
+	fn enqueue_dispatch_as(
+		dispatch_as: DispatchAs<T::AccountId>,
+		call: <T as Trait>::Call,
+		at: T::BlockNumber,
+		priority: u8,
+	) -> T::Hash {
+		// Governance tasks belong to no account, so they take their nonces from the default
+		// one, which nobody can sign for.
+		let sender = T::AccountId::default();
+		let nonce = Self::next_nonce(&sender);
+		Self::inc_account_nonce(&sender);
+		let id = Self::task_id(&sender, &nonce, &call);
+		let task = Task {
+			method: TaskCall::Value(call),
+			sender,
+			nonce,
+			block_number: at,
+			periodic: None,
+			priority,
+			deposit: Zero::zero(),
+			tip: Zero::zero(),
+			valid_until: None,
+			condition: None,
+			dispatch_as: Some(dispatch_as),
+			idempotency_key: None,
+		};
+		Self::enqueue(id, task);
+		id
+	}
+
+	/// Store `task` under `id` and queue it for its block, after the tasks due then that have
+	/// the same or a higher priority. A task due by now joins the end of the pending tasks.
+	fn enqueue(id: T::Hash, task: TaskOf<T>) {
+		let queue = Self::queue_of(task.block_number);
+		let priority = task.priority;
+		<ScheduleTask<T>>::insert(id, task);
+		match queue {
+			Queue::Block(_) => Self::link(queue, id, priority),
+			Queue::Pending => Self::link(queue, id, u8::max_value()),
+		}
+	}
+
+	/// The queue a task due at `block_number` is in.
+	fn queue_of(block_number: T::BlockNumber) -> Queue<T::BlockNumber> {
+		if block_number > <system::Module<T>>::block_number() {
+			Queue::Block(block_number)
+		} else {
+			Queue::Pending
+		}
+	}
+
+	fn queue(queue: Queue<T::BlockNumber>) -> Option<LinkedTasks<T::Hash>> {
+		match queue {
+			Queue::Block(block_number) => Self::block_linked_task(block_number),
+			Queue::Pending => Self::pending_tasks(),
+		}
+	}
+
+	fn set_queue(queue: Queue<T::BlockNumber>, tasks: Option<LinkedTasks<T::Hash>>) {
+		match (queue, tasks) {
+			(Queue::Block(block_number), Some(tasks)) => {
+				<TaskLinkedByBlock<T>>::insert(block_number, tasks)
+			}
+			(Queue::Block(block_number), None) => <TaskLinkedByBlock<T>>::remove(block_number),
+			(Queue::Pending, Some(tasks)) => <PendingTasks<T>>::put(tasks),
+			(Queue::Pending, None) => <PendingTasks<T>>::kill(),
+		}
+	}
+
+	/// Link `id` into `queue` after the tasks with the same or a higher `priority`. Walks
+	/// back from the tail, so queueing behind tasks of the same priority takes constant time.
+	fn link(queue: Queue<T::BlockNumber>, id: T::Hash, priority: u8) {
+		let mut tasks = match Self::queue(queue) {
+			Some(tasks) => tasks,
+			None => {
+				<Links<T>>::insert(id, TaskLinks { prev: None, next: None });
+				Self::set_queue(queue, Some(LinkedTasks { head: id, tail: id, len: 1 }));
+				return;
+			}
+		};
+		let mut prev = Some(tasks.tail);
+		while let Some(other) = prev {
+			if Self::priority(&other) <= priority {
+				break;
+			}
+			prev = Self::task_links(&other).prev;
+		}
+		let next = match prev {
+			Some(prev) => <Links<T>>::mutate(prev, |links| links.next.replace(id)),
+			None => Some(tasks.head),
+		};
+		match next {
+			Some(next) => <Links<T>>::mutate(next, |links| links.prev = Some(id)),
+			None => tasks.tail = id,
+		}
+		if prev.is_none() {
+			tasks.head = id;
+		}
+		<Links<T>>::insert(id, TaskLinks { prev, next });
+		tasks.len += 1;
+		Self::set_queue(queue, Some(tasks));
+	}
+
+	/// Take `id` out of `queue`, if it is there.
+	fn unlink(queue: Queue<T::BlockNumber>, id: &T::Hash) {
+		let mut tasks = match Self::queue(queue) {
+			Some(tasks) => tasks,
+			None => return,
+		};
+		let links = Self::task_links(id);
+		if links.prev.is_none() && tasks.head != *id {
+			return;
+		}
+		<Links<T>>::remove(id);
+		if tasks.len <= 1 {
+			Self::set_queue(queue, None);
+			return;
+		}
+		match links.prev {
+			Some(prev) => <Links<T>>::mutate(prev, |prev| prev.next = links.next),
+			None => tasks.head = links.next.unwrap_or(tasks.head),
+		}
+		match links.next {
+			Some(next) => <Links<T>>::mutate(next, |next| next.prev = links.prev),
+			None => tasks.tail = links.prev.unwrap_or(tasks.tail),
+		}
+		tasks.len -= 1;
+		Self::set_queue(queue, Some(tasks));
+	}
+
+	/// Take the first of the pending tasks.
+	fn pop_pending() -> Option<T::Hash> {
+		let mut tasks = Self::pending_tasks()?;
+		let id = tasks.head;
+		match <Links<T>>::take(&id).next {
+			Some(next) => {
+				<Links<T>>::mutate(next, |links| links.prev = None);
+				tasks.head = next;
+				tasks.len -= 1;
+				<PendingTasks<T>>::put(tasks);
+			}
+			None => <PendingTasks<T>>::kill(),
+		}
+		Some(id)
+	}
+
+	/// Move the tasks due at `block_number` to the end of the pending tasks.
+	fn append_to_pending(block_number: T::BlockNumber) {
+		let due = match <TaskLinkedByBlock<T>>::take(block_number) {
+			Some(due) => due,
+			None => return,
+		};
+		let tasks = match Self::pending_tasks() {
+			Some(mut tasks) => {
+				<Links<T>>::mutate(tasks.tail, |links| links.next = Some(due.head));
+				<Links<T>>::mutate(due.head, |links| links.prev = Some(tasks.tail));
+				tasks.tail = due.tail;
+				tasks.len += due.len;
+				tasks
+			}
+			None => due,
+		};
+		<PendingTasks<T>>::put(tasks);
+	}
+
+	/// The ids of the tasks in `queue`, in the order they run.
+	fn queued(queue: Queue<T::BlockNumber>) -> Vec<T::Hash> {
+		let mut ids = Vec::new();
+		let mut next = Self::queue(queue).map(|tasks| tasks.head);
+		while let Some(id) = next {
+			ids.push(id);
+			next = Self::task_links(&id).next;
+		}
+		ids
+	}
+
+	/// How many tasks are queued for `block_number`.
+	pub fn queued_at(block_number: T::BlockNumber) -> u32 {
+		Self::block_linked_task(block_number).map_or(0, |tasks| tasks.len)
+	}
+
+	/// The priority of the task `id`, lowest if there is no such task.
+	fn priority(id: &T::Hash) -> u8 {
+		Self::task(id).map_or(u8::max_value(), |task| task.priority)
+	}
+
+	/// The block a task due at block `at`, repeating as `periodic` says, runs at for the last
+	/// time.
+	fn last_run(at: T::BlockNumber, periodic: Option<(T::BlockNumber, u32)>) -> T::BlockNumber {
+		periodic.map_or(at, |(period, remaining)| {
+			at.saturating_add(period.saturating_mul(T::BlockNumber::from(remaining)))
+		})
+	}
+
+	/// The deposit for scheduling `call`, or its hash, at block `at`, repeating as `periodic`
+	/// says.
+	fn deposit_for<C: Encode>(
+		call: &C,
+		at: T::BlockNumber,
+		periodic: Option<(T::BlockNumber, u32)>,
+	) -> BalanceOf<T> {
+		let last_run = Self::last_run(at, periodic);
+		let now = <system::Module<T>>::block_number();
+		let blocks: u32 = last_run.saturating_sub(now).unique_saturated_into();
+		let size = call.encode().len() as u32;
+		let per_byte = T::DepositPerByte::get().saturating_mul(BalanceOf::<T>::from(size));
+		let per_block = T::DepositPerBlock::get().saturating_mul(BalanceOf::<T>::from(blocks));
+		per_byte.saturating_add(per_block)
+	}
+
+	/// Schedule `call` for `sender` at block `at` under their next nonce, reserving its
+	/// deposit and `tip`. If the block is full, the task displaces the one with the lowest
+	/// tip to the next block, and is refused unless its own tip is higher.
+	fn schedule_call(
+		sender: T::AccountId,
+		method: TaskCallOf<T>,
+		at: T::BlockNumber,
+		periodic: Option<(T::BlockNumber, u32)>,
+		priority: u8,
+		tip: BalanceOf<T>,
+		valid_until: Option<T::BlockNumber>,
+	) -> Result {
+		Self::ensure_schedulable_at(at)?;
+		ensure!(
+			valid_until.map_or(true, |until| at <= until),
+			"task must be scheduled before it expires"
+		);
+		if let TaskCall::Value(call) = &method {
+			ensure!(T::ScheduleFilter::filter(call), "call may not be scheduled");
+		}
+		Self::ensure_pending_room(&sender, 1)?;
+		let outbid = Self::outbid(at, tip)?;
+		let deposit = match &method {
+			TaskCall::Value(call) => Self::deposit_for(call, at, periodic),
+			TaskCall::Hash(hash) => Self::deposit_for(hash, at, periodic),
+		};
+		T::Currency::reserve(&sender, deposit.saturating_add(tip))
+			.map_err(|_| "not enough free balance for scheduling deposit")?;
+		let nonce = Self::next_nonce(&sender);
+		let task = Task {
+			method,
+			sender,
+			nonce,
+			block_number: at,
+			periodic,
+			priority,
+			deposit,
+			tip,
+			valid_until,
+			condition: None,
+			dispatch_as: None,
+			idempotency_key: None,
+		};
+		if let Some(outbid) = outbid {
+			Self::displace(outbid, at);
+		}
+		Self::schedule_task(task)
+	}
+
+	/// Run the tasks scheduled for `block_number` in order of priority, after those due
+	/// earlier that have yet to run, up to `MaxTasksPerBlock` of them and as many as fit into
+	/// `MaxScheduledWeight`. The first one always runs, however heavy, so that no task is
+	/// postponed forever. The others stay pending for the next block, and expire when they
+	/// are reached past their deadline. Recurring tasks with occurrences left are scheduled
+	/// again one period later. While the scheduler is paused, the tasks only join the
+	/// pending ones.
+	pub fn run_tasks(block_number: T::BlockNumber) {
+		Self::append_to_pending(block_number);
+		if Self::paused() {
+			return;
+		}
+
+		let budget = T::MaxScheduledWeight::get() * <T as system::Trait>::MaximumBlockWeight::get();
+		let mut used: Weight = 0;
+		for index in 0..T::MaxTasksPerBlock::get() {
+			let id = match Self::pending_tasks() {
+				Some(tasks) => tasks.head,
+				None => break,
+			};
+			let call = Self::task(&id).and_then(|task| Self::call_of(&task.method));
+			let weight = call.map_or(0, |call| call.get_dispatch_info().weight);
+			if index > 0 && used.saturating_add(weight) > budget {
+				break;
+			}
+			used = used.saturating_add(weight);
+			Self::pop_pending();
+
+			let mut task = match <ScheduleTask<T>>::take(&id) {
+				Some(task) => task,
+				None => continue,
+			};
+			if Self::is_expired(&task, block_number) {
+				Self::expire(&id, &task);
+				continue;
+			}
+			let unmet = task.condition.as_ref().map_or(false, |condition| {
+				!T::ScheduleCondition::holds(condition, &task.sender)
+			});
+			if unmet {
+				if T::OnUnmetCondition::get() == UnmetCondition::Postpone {
+					let next = block_number + One::one();
+					task.block_number = next;
+					Self::enqueue(id, task);
+					Self::deposit_event(RawEvent::TaskPostponed(id, next));
+					continue;
+				}
+				Self::deposit_event(RawEvent::TaskSkipped(id, block_number));
+			}
+			let succeeded = !unmet && Self::execute(id, &mut task, block_number, index);
+			match task.periodic {
+				Some((period, remaining)) if remaining > 0 => {
+					let next = block_number + period;
+					task.block_number = next;
+					task.periodic = Some((period, remaining - 1));
+					Self::enqueue(id, task);
+					Self::deposit_event(RawEvent::TaskScheduled(id, next));
+				}
+				_ => {
+					// A skipped run forfeits nothing, but tasks waiting for it do not run.
+					Self::settle_deposit(&id, &task, succeeded || unmet);
+					Self::remove_pending(&task.sender, &id);
+					Self::drop_preimage(&task.method);
+					Self::release_key(&task);
+					Self::release_dependents(&id, block_number, succeeded);
+				}
+			}
+		}
+
+		if let Some(tasks) = Self::pending_tasks() {
+			Self::report_postponed(block_number, &tasks);
+			Self::deposit_event(RawEvent::TasksOverdue(tasks.len));
+		}
+	}
+
+	/// Emit `TaskPostponed` for the tasks due at `block_number` that did not fit into it.
+	/// They are the last of the pending `tasks`, so only they are walked; the tasks overdue
+	/// since earlier blocks were reported when they missed their own.
+	fn report_postponed(block_number: T::BlockNumber, tasks: &LinkedTasks<T::Hash>) {
+		let mut postponed = Vec::new();
+		let mut prev = Some(tasks.tail);
+		while let Some(id) = prev {
+			if Self::task(&id).map_or(true, |task| task.block_number != block_number) {
+				break;
+			}
+			postponed.push(id);
+			prev = Self::task_links(&id).prev;
+		}
+		let next = block_number + One::one();
+		for id in postponed.into_iter().rev() {
+			Self::deposit_event(RawEvent::TaskPostponed(id, next));
+		}
+	}
+
+	/// Dispatch the call of the task `id` at `block_number`, as the `index`th task of the
+	/// block, returning whether it succeeded. The tip is paid only if the call is dispatched.
+	fn execute(id: T::Hash, task: &mut TaskOf<T>, block_number: T::BlockNumber, index: u32) -> bool {
+		let call = Self::call_of(&task.method);
+		let weight = call.as_ref().map_or(0, |call| call.get_dispatch_info().weight);
+		let result = match (call, task.dispatch_as.clone()) {
+			(None, _) => Err("call preimage has not been noted"),
+			(Some(call), Some(dispatch_as)) => {
+				let origin = match dispatch_as {
+					DispatchAs::Root => system::RawOrigin::Root,
+					DispatchAs::Signed(who) => system::RawOrigin::Signed(who),
+				};
+				Self::pay_tip(task);
+				call.dispatch(origin.into())
+			}
+			(Some(call), None) => Self::dispatch_as_sender(id, task, call),
+		};
+		Self::record_result(id, weight, result, block_number);
+		T::OnTaskExecuted::on_task_executed(&id, &task.sender, result);
+		match result {
+			Ok(()) => Self::deposit_event(RawEvent::TaskExecutedOk(id, block_number, index)),
+			Err(error) => {
+				let error = error.as_bytes().to_vec();
+				Self::deposit_event(RawEvent::TaskExecutedErr(id, block_number, index, error));
+			}
+		}
+		result.is_ok()
+	}
+
+	/// Charge the sender of the task `id` the fee for its call's weight, pay its tip and
+	/// dispatch the call from their account. If they cannot pay, the call is not dispatched.
+	/// A call that may no longer be scheduled is not dispatched either, and not charged for.
+	fn dispatch_as_sender(id: T::Hash, task: &mut TaskOf<T>, call: <T as Trait>::Call) -> Result {
+		// The filter may have been tightened since the task was scheduled.
+		ensure!(T::ScheduleFilter::filter(&call), "call may not be scheduled");
+		let weight = call.get_dispatch_info().weight;
+		let fee = T::WeightToFee::convert(weight);
+		let paid = T::Currency::withdraw(
+			&task.sender,
+			fee,
+			WithdrawReason::Fee,
+			ExistenceRequirement::KeepAlive,
+		);
+		let imbalance = match paid {
+			Ok(imbalance) => imbalance,
+			Err(_) => {
+				Self::deposit_event(RawEvent::ExecutionFeeUnpaid(id, fee));
+				return Err("sender cannot pay the execution fee");
+			}
+		};
+		T::ExecutionFees::on_unbalanced(imbalance);
+		Self::pay_tip(task);
+		let origin = T::Origin::from(system::RawOrigin::Signed(task.sender.clone()));
+		call.dispatch(origin)
+	}
+
+	/// Keep the `result` and `weight` of the run of the task `id` at `block_number` for
+	/// `ResultRetention` blocks.
+	fn record_result(id: T::Hash, weight: Weight, result: Result, block_number: T::BlockNumber) {
+		let record = TaskResult {
+			error: result.err().map(|error| error.as_bytes().to_vec()),
+			weight,
+			executed_at: block_number,
+		};
+		<TaskResults<T>>::insert(id, record);
+		let expiry = block_number.saturating_add(T::ResultRetention::get());
+		<ResultsExpiringAt<T>>::mutate(expiry, |ids| ids.push(id));
+	}
+
+	/// Drop the results due to be dropped at `block_number`, unless the task has run again
+	/// since.
+	fn prune_results(block_number: T::BlockNumber) {
+		let retention = T::ResultRetention::get();
+		for id in <ResultsExpiringAt<T>>::take(block_number) {
+			let stale = Self::task_result(&id).map_or(false, |result| {
+				result.executed_at.saturating_add(retention) <= block_number
+			});
+			if stale {
+				<TaskResults<T>>::remove(&id);
+			}
+		}
+	}
+
+	/// Whether `task` may no longer run at `block_number`.
+	fn is_expired(task: &TaskOf<T>, block_number: T::BlockNumber) -> bool {
+		task.valid_until.map_or(false, |until| block_number > until)
+	}
+
+	/// Drop the task `id`, which is past its deadline, refunding its deposit less `ExpiryFee`.
+	fn expire(id: &T::Hash, task: &TaskOf<T>) {
+		let fee = Self::release_deposit(task, T::ExpiryFee::get());
+		Self::remove_pending(&task.sender, id);
+		Self::drop_preimage(&task.method);
+		Self::release_key(task);
+		Self::deposit_event(RawEvent::TaskExpired(*id, fee));
+		Self::release_dependents(id, task.block_number, false);
+	}
+
+	/// Queue the tasks waiting for the task `id`, which ran for the last time at
+	/// `block_number`, for the next block if it `succeeded`, and cancel them otherwise.
+	fn release_dependents(id: &T::Hash, block_number: T::BlockNumber, succeeded: bool) {
+		let next = block_number + One::one();
+		for dependent in Self::dependents(id) {
+			if !succeeded {
+				Self::cancel_task(dependent);
+			} else if let Some(mut task) = <ScheduleTask<T>>::take(&dependent) {
+				<WaitingFor<T>>::remove(&dependent);
+				task.block_number = next;
+				Self::enqueue(dependent, task);
+				Self::deposit_event(RawEvent::TaskScheduled(dependent, next));
+			}
+		}
+		<Dependents<T>>::remove(id);
+	}
+
+	/// Drop the task `id` and the tasks waiting for it, refunding their deposits.
+	fn cancel_task(id: T::Hash) {
+		let task = match Self::take_task(&id) {
+			Some(task) => task,
+			None => return,
+		};
+		T::Currency::unreserve(&task.sender, task.deposit.saturating_add(task.tip));
+		Self::deposit_event(RawEvent::TaskCancelled(id));
+		Self::cancel_dependents(&id);
+	}
+
+	/// Cancel the tasks waiting for the task `id`, refunding their deposits.
+	fn cancel_dependents(id: &T::Hash) {
+		for dependent in Self::dependents(id) {
+			Self::cancel_task(dependent);
+		}
+		<Dependents<T>>::remove(id);
+	}
+
+	/// Remove the task `id` from storage and from the queue or the task it waits in, leaving
+	/// its deposit reserved and the tasks waiting for it in place.
+	fn take_task(id: &T::Hash) -> Option<TaskOf<T>> {
+		let task = <ScheduleTask<T>>::take(id)?;
+		match <WaitingFor<T>>::take(id) {
+			Some(after) => <Dependents<T>>::mutate(after, |ids| ids.retain(|other| other != id)),
+			None => Self::unlink(Self::queue_of(task.block_number), id),
+		}
+		Self::remove_pending(&task.sender, id);
+		Self::drop_preimage(&task.method);
+		Self::release_key(&task);
+		Some(task)
+	}
+
+	/// Free the idempotency key `task` was scheduled under, if any.
+	fn release_key(task: &TaskOf<T>) {
+		if let Some(key) = &task.idempotency_key {
+			<IdempotencyKeys<T>>::remove(&task.sender, key);
+		}
+	}
+
+	/// The call `method` dispatches, unless it is a hash whose preimage has not been noted.
+	fn call_of(method: &TaskCallOf<T>) -> Option<<T as Trait>::Call> {
+		match method {
+			TaskCall::Value(call) => Some(call.clone()),
+			TaskCall::Hash(hash) => {
+				let preimage = Self::preimage(hash)?;
+				Decode::decode(&mut &preimage.data[..]).ok()
+			}
+		}
+	}
+
+	/// Drop the preimage of `method` if it is a hash, refunding the deposit of its provider.
+	fn drop_preimage(method: &TaskCallOf<T>) {
+		let hash = match method {
+			TaskCall::Value(_) => return,
+			TaskCall::Hash(hash) => hash,
+		};
+		if let Some(preimage) = <Preimages<T>>::take(hash) {
+			T::Currency::unreserve(&preimage.provider, preimage.deposit);
+			Self::deposit_event(RawEvent::PreimageDropped(*hash));
+		}
+	}
+
+	/// Refund the deposit of the task `id`, which ran for the last time, slashing
+	/// `FailureSlash` of it if that run failed.
+	fn settle_deposit(id: &T::Hash, task: &TaskOf<T>, succeeded: bool) {
+		let slash = if succeeded { Perbill::zero() } else { T::FailureSlash::get() };
+		let slashed = Self::release_deposit(task, slash);
+		if !slashed.is_zero() {
+			Self::deposit_event(RawEvent::DepositSlashed(*id, slashed));
+		}
+	}
+
+	/// Slash `slash` of the deposit of `task` and unreserve the rest along with its unpaid
+	/// tip, returning how much was slashed.
+	fn release_deposit(task: &TaskOf<T>, slash: Perbill) -> BalanceOf<T> {
+		let slashed = slash * task.deposit;
+		if !slashed.is_zero() {
+			let (imbalance, _) = T::Currency::slash_reserved(&task.sender, slashed);
+			T::Slashed::on_unbalanced(imbalance);
+		}
+		T::Currency::unreserve(&task.sender, (task.deposit - slashed).saturating_add(task.tip));
+		slashed
+	}
+
+	/// Pay the tip of `task`, whose call is dispatched now, to `Tips`. A run whose call is not
+	/// dispatched keeps its tip, which is refunded along with the deposit.
+	fn pay_tip(task: &mut TaskOf<T>) {
+		if task.tip.is_zero() {
+			return;
+		}
+		let (imbalance, _) = T::Currency::slash_reserved(&task.sender, task.tip);
+		T::Tips::on_unbalanced(imbalance);
+		task.tip = Zero::zero();
+	}
+
+	/// The task a new task with `tip` displaces from block `at`, if that block already holds
+	/// `MaxTasksPerBlock` tasks: the one with the lowest tip, the last of them if several
+	/// share it. Governance tasks are never displaced. Fails if `tip` is not higher.
+	fn outbid(
+		at: T::BlockNumber,
+		tip: BalanceOf<T>,
+	) -> rstd::result::Result<Option<T::Hash>, &'static str> {
+		if Self::queued_at(at) < T::MaxTasksPerBlock::get() {
+			return Ok(None);
+		}
+		let mut lowest: Option<(T::Hash, BalanceOf<T>)> = None;
+		for id in Self::queued(Queue::Block(at)) {
+			let task = match Self::task(&id) {
+				Some(task) => task,
+				None => continue,
+			};
+			if task.dispatch_as.is_some() {
+				continue;
+			}
+			if lowest.as_ref().map_or(true, |(_, min)| task.tip <= *min) {
+				lowest = Some((id, task.tip));
+			}
+		}
+		match lowest {
+			Some((id, min)) if tip > min => Ok(Some(id)),
+			_ => Err("block is full, a higher tip is needed"),
+		}
+	}
+
+	/// Postpone the task `id` from block `at` to the next one, for a task that outbid it.
+	fn displace(id: T::Hash, at: T::BlockNumber) {
+		let mut task = match Self::task(&id) {
+			Some(task) => task,
+			None => return,
+		};
+		Self::unlink(Queue::Block(at), &id);
+		let next = at + One::one();
+		task.block_number = next;
+		Self::enqueue(id, task);
+		Self::deposit_event(RawEvent::TaskOutbid(id, next));
+	}
+
+	/// The tasks due from block `from` to block `to`, in the order they would run, followed
+	/// by the tasks waiting for each. Only the tasks of `sender` if it is given. The tasks
+	/// that are overdue come first if the range starts by the current block. At most
+	/// `MAX_AGENDA_BLOCKS` blocks are looked at.
+	pub fn agenda(
+		from: T::BlockNumber,
+		to: T::BlockNumber,
+		sender: Option<T::AccountId>,
+	) -> Vec<AgendaEntry<T::AccountId, T::BlockNumber, T::Hash>> {
+		let mut entries = Vec::new();
+		if from <= <system::Module<T>>::block_number() {
+			for id in Self::queued(Queue::Pending) {
+				Self::push_agenda_entry(&mut entries, id, &sender);
+			}
+		}
+		let mut block_number = from;
+		for _ in 0..MAX_AGENDA_BLOCKS {
+			if block_number > to {
+				break;
+			}
+			for id in Self::queued(Queue::Block(block_number)) {
+				Self::push_agenda_entry(&mut entries, id, &sender);
+			}
+			block_number = block_number.saturating_add(One::one());
+		}
+		entries
+	}
+
+	/// When the task `id` is due, if it has yet to run. The position of an overdue task is
+	/// among the pending tasks.
+	pub fn next_execution(id: T::Hash) -> Option<NextExecution<T::BlockNumber>> {
+		let task = Self::task(&id)?;
+		let position = if <WaitingFor<T>>::exists(&id) {
+			None
 		} else {
-			vec!(task)
+			Self::queued(Self::queue_of(task.block_number))
+				.iter()
+				.position(|other| *other == id)
+				.map(|position| position as u32)
+		};
+		Some(NextExecution { block_number: task.block_number, position })
+	}
+
+	/// How many tasks are queued for each block from `from` to `to` that has any. Overdue
+	/// tasks are not included. At most `MAX_AGENDA_BLOCKS` blocks are looked at.
+	pub fn queue_depth(from: T::BlockNumber, to: T::BlockNumber) -> Vec<(T::BlockNumber, u32)> {
+		let mut depths = Vec::new();
+		let mut block_number = from;
+		for _ in 0..MAX_AGENDA_BLOCKS {
+			if block_number > to {
+				break;
+			}
+			let queued = Self::queued_at(block_number);
+			if queued > 0 {
+				depths.push((block_number, queued));
+			}
+			block_number = block_number.saturating_add(One::one());
+		}
+		depths
+	}
+
+	/// Add the task `id` and the tasks waiting for it to `entries`, if they are `sender`'s.
+	fn push_agenda_entry(
+		entries: &mut Vec<AgendaEntry<T::AccountId, T::BlockNumber, T::Hash>>,
+		id: T::Hash,
+		sender: &Option<T::AccountId>,
+	) {
+		let task = match Self::task(&id) {
+			Some(task) => task,
+			None => return,
 		};
+		if sender.as_ref().map_or(true, |sender| *sender == task.sender) {
+			let status = match Self::waiting_for(&id) {
+				Some(after) => TaskStatus::Waiting(after),
+				None => TaskStatus::Queued,
+			};
+			entries.push(AgendaEntry {
+				id,
+				sender: task.sender,
+				call: match task.method {
+					TaskCall::Value(call) => call.encode(),
+					TaskCall::Hash(hash) => Self::preimage(hash).map_or(Vec::new(), |p| p.data),
+				},
+				block_number: task.block_number,
+				status,
+			});
+		}
+		for dependent in Self::dependents(&id) {
+			Self::push_agenda_entry(entries, dependent, sender);
+		}
+	}
 
-		<TasksByBlock<T>>::insert(block_number, tasks);
+	/// Ensure a task may be scheduled for block `at`, at least `MinScheduleDelay` and at most
+	/// `MaxScheduleHorizon` blocks from now.
+	fn ensure_schedulable_at(at: T::BlockNumber) -> Result {
+		let now = <system::Module<T>>::block_number();
+		ensure!(at > now, Error::BlockInPast);
+		ensure!(at >= now.saturating_add(T::MinScheduleDelay::get()), Error::TooSoon);
+		Self::ensure_within_horizon(at)
+	}
+
+	/// Ensure a task due at block `at` is at most `MaxScheduleHorizon` blocks from now.
+	fn ensure_within_horizon(at: T::BlockNumber) -> Result {
+		let now = <system::Module<T>>::block_number();
+		ensure!(at <= now.saturating_add(T::MaxScheduleHorizon::get()), Error::TooFarAhead);
+		Ok(())
+	}
 
+	/// Ensure `sender` may have `count` more tasks that have yet to run.
+	fn ensure_pending_room(sender: &T::AccountId, count: u32) -> Result {
+		let pending = (Self::tasks_by_sender(sender).len() as u32).saturating_add(count);
+		ensure!(pending <= T::MaxPendingPerAccount::get(), Error::TooManyPendingTasks);
 		Ok(())
 	}
 
+	/// Record task `id` of `sender` as having yet to run.
+	fn add_pending(sender: &T::AccountId, id: T::Hash) {
+		<TasksBySender<T>>::mutate(sender, |ids| ids.push(id));
+	}
+
+	/// Drop task `id` from those of `sender` that have yet to run.
+	fn remove_pending(sender: &T::AccountId, id: &T::Hash) {
+		let mut ids = Self::tasks_by_sender(sender);
+		ids.retain(|pending| pending != id);
+		if ids.is_empty() {
+			<TasksBySender<T>>::remove(sender);
+		} else {
+			<TasksBySender<T>>::insert(sender, ids);
+		}
+	}
+
     /// Increment a particular account's nonce by 1.
     pub fn inc_account_nonce(who: &T::AccountId) {
         <NextNonce<T>>::insert(who, Self::next_nonce(who) + T::Index::one());
     }
 }
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(all(test, feature = "runtime-benchmarks"))]
+mod benchmarking;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,235 @@
+//! A mock runtime wiring balances into the scheduler, for its tests and benchmarks.
+
+use super::*;
+
+use primitives::{Blake2Hasher, H256};
+use sr_primitives::{
+    testing::{Header, TestSignature},
+    traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    Perbill,
+};
+use std::cell::RefCell;
+use support::{impl_outer_event, impl_outer_origin, parameter_types};
+
+impl_outer_origin! {
+    pub enum Origin for Test {}
+}
+
+mod scheduler {
+    pub use crate::Event;
+}
+impl_outer_event! {
+    pub enum TestEvent for Test {
+        balances<T>,
+        scheduler<T>,
+    }
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    // Large enough that the weight budget never postpones a benchmarked task.
+    pub const MaximumBlockWeight: u32 = u32::max_value();
+    pub const MaximumBlockLength: u32 = 2 * 1024;
+    pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl system::Trait for Test {
+    type Origin = Origin;
+    type Index = u64;
+    type Call = balances::Call<Test>;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type WeightMultiplierUpdate = ();
+    type Event = TestEvent;
+    type BlockHashCount = BlockHashCount;
+    type MaximumBlockWeight = MaximumBlockWeight;
+    type AvailableBlockRatio = AvailableBlockRatio;
+    type MaximumBlockLength = MaximumBlockLength;
+    type Version = ();
+}
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 0;
+    pub const TransferFee: u64 = 0;
+    pub const CreationFee: u64 = 0;
+    pub const TransactionBaseFee: u64 = 0;
+    pub const TransactionByteFee: u64 = 0;
+    pub const DepositPerByte: u64 = 0;
+    pub const FailureSlash: Perbill = Perbill::from_percent(10);
+    pub const ExpiryFee: Perbill = Perbill::from_percent(10);
+    pub const ForceCancelSlash: Perbill = Perbill::from_percent(50);
+    pub const ResultRetention: u64 = 100;
+    pub const MaxRescheduleDelay: u64 = 100;
+    pub const MinScheduleDelay: u64 = 2;
+    pub const MaxScheduleHorizon: u64 = 100;
+    pub const MaxDependents: u32 = 2;
+}
+impl balances::Trait for Test {
+    type Balance = u64;
+    type OnFreeBalanceZero = ();
+    type OnNewAccount = ();
+    type Event = TestEvent;
+    type TransactionPayment = ();
+    type DustRemoval = ();
+    type TransferPayment = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type TransferFee = TransferFee;
+    type CreationFee = CreationFee;
+    type TransactionBaseFee = TransactionBaseFee;
+    type TransactionByteFee = TransactionByteFee;
+    type WeightToFee = ConvertInto;
+}
+thread_local! {
+    pub static MAX_TASKS_PER_BLOCK: RefCell<u32> = RefCell::new(1_000);
+}
+/// High enough for the benchmarks unless a test lowers it.
+pub struct MaxTasksPerBlock;
+impl Get<u32> for MaxTasksPerBlock {
+    fn get() -> u32 {
+        MAX_TASKS_PER_BLOCK.with(|max| *max.borrow())
+    }
+}
+
+thread_local! {
+    pub static MAX_SCHEDULED_WEIGHT: RefCell<Perbill> = RefCell::new(Perbill::one());
+}
+/// The whole block unless a test lowers it.
+pub struct MaxScheduledWeight;
+impl Get<Perbill> for MaxScheduledWeight {
+    fn get() -> Perbill {
+        MAX_SCHEDULED_WEIGHT.with(|max| *max.borrow())
+    }
+}
+
+thread_local! {
+    pub static MAX_PENDING_PER_ACCOUNT: RefCell<u32> = RefCell::new(1_000);
+}
+/// High enough for the benchmarks unless a test lowers it.
+pub struct MaxPendingPerAccount;
+impl Get<u32> for MaxPendingPerAccount {
+    fn get() -> u32 {
+        MAX_PENDING_PER_ACCOUNT.with(|max| *max.borrow())
+    }
+}
+
+thread_local! {
+    pub static DEPOSIT_PER_BLOCK: RefCell<u64> = RefCell::new(0);
+}
+/// Off unless a test sets it, so that other tests see the balances they set up.
+pub struct DepositPerBlock;
+impl Get<u64> for DepositPerBlock {
+    fn get() -> u64 {
+        DEPOSIT_PER_BLOCK.with(|deposit| *deposit.borrow())
+    }
+}
+
+thread_local! {
+    pub static SCHEDULABLE: RefCell<bool> = RefCell::new(true);
+}
+/// Lets every call be scheduled, unless a test turns `SCHEDULABLE` off.
+pub struct AnyCall;
+impl Filter<balances::Call<Test>> for AnyCall {
+    fn filter(_: &balances::Call<Test>) -> bool {
+        SCHEDULABLE.with(|schedulable| *schedulable.borrow())
+    }
+}
+
+thread_local! {
+    pub static EXECUTION_FEE: RefCell<u64> = RefCell::new(0);
+}
+/// A flat fee for every run, off unless a test sets it.
+pub struct ExecutionFee;
+impl Convert<Weight, u64> for ExecutionFee {
+    fn convert(_: Weight) -> u64 {
+        EXECUTION_FEE.with(|fee| *fee.borrow())
+    }
+}
+
+/// Holds while the sender has at least the given free balance.
+pub struct MinBalance;
+impl ScheduleCondition<u64> for MinBalance {
+    type Condition = u64;
+
+    fn holds(min: &u64, sender: &u64) -> bool {
+        Balances::free_balance(sender) >= *min
+    }
+}
+
+thread_local! {
+    pub static ON_UNMET_CONDITION: RefCell<UnmetCondition> = RefCell::new(UnmetCondition::Skip);
+}
+/// Skips unless a test switches it to postponing.
+pub struct OnUnmetCondition;
+impl Get<UnmetCondition> for OnUnmetCondition {
+    fn get() -> UnmetCondition {
+        ON_UNMET_CONDITION.with(|unmet| *unmet.borrow())
+    }
+}
+
+thread_local! {
+    pub static EXECUTED: RefCell<Vec<(H256, u64, Result)>> = RefCell::new(Vec::new());
+}
+/// Records every run of a task in `EXECUTED`.
+pub struct RecordExecuted;
+impl OnTaskExecuted<H256, u64> for RecordExecuted {
+    fn on_task_executed(id: &H256, sender: &u64, result: Result) {
+        EXECUTED.with(|executed| executed.borrow_mut().push((*id, *sender, result)));
+    }
+}
+
+impl Trait for Test {
+    type Event = TestEvent;
+    type Call = balances::Call<Test>;
+    type ScheduleFilter = AnyCall;
+    type Signature = TestSignature;
+    type ScheduleAsOrigin = system::EnsureRoot<u64>;
+    type PauseOrigin = system::EnsureRoot<u64>;
+    type ForceCancelOrigin = system::EnsureRoot<u64>;
+    type ScheduleCondition = MinBalance;
+    type OnUnmetCondition = OnUnmetCondition;
+    type MaxTasksPerBlock = MaxTasksPerBlock;
+    type MaxScheduledWeight = MaxScheduledWeight;
+    type Currency = balances::Module<Test>;
+    type DepositPerByte = DepositPerByte;
+    type DepositPerBlock = DepositPerBlock;
+    type FailureSlash = FailureSlash;
+    type ExpiryFee = ExpiryFee;
+    type ForceCancelSlash = ForceCancelSlash;
+    type ResultRetention = ResultRetention;
+    type MaxRescheduleDelay = MaxRescheduleDelay;
+    type MinScheduleDelay = MinScheduleDelay;
+    type MaxScheduleHorizon = MaxScheduleHorizon;
+    type MaxPendingPerAccount = MaxPendingPerAccount;
+    type MaxDependents = MaxDependents;
+    type Slashed = ();
+    type WeightToFee = ExecutionFee;
+    type ExecutionFees = ();
+    type Tips = ();
+    type OnTaskExecuted = RecordExecuted;
+    type WeightInfo = ();
+}
+pub type System = system::Module<Test>;
+pub type Balances = balances::Module<Test>;
+pub type Scheduler = Module<Test>;
+
+pub const SENDER: u64 = 1;
+
+pub fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+    new_test_ext_with_tasks(vec![])
+}
+
+/// Like `new_test_ext`, with `tasks` scheduled at genesis.
+pub fn new_test_ext_with_tasks(
+    tasks: Vec<(DispatchAs<u64>, Vec<u8>, u64)>,
+) -> runtime_io::TestExternalities<Blake2Hasher> {
+    let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    balances::GenesisConfig::<Test> { balances: vec![(SENDER, 1_000_000)], vesting: vec![] }
+        .assimilate_storage(&mut t)
+        .unwrap();
+    GenesisConfig::<Test> { tasks }.assimilate_storage(&mut t).unwrap();
+    t.into()
+}
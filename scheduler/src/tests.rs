@@ -0,0 +1,1051 @@
+//! Unit tests for the scheduler, run against the mock runtime.
+
+use super::*;
+use crate::mock::*;
+
+use primitives::H256;
+use runtime_io::with_externalities;
+use sr_primitives::{testing::TestSignature, traits::BlakeTwo256};
+use support::{assert_noop, assert_ok};
+
+/// The block the tests schedule from.
+const NOW: u64 = 5;
+
+fn transfer() -> Box<balances::Call<Test>> {
+    Box::new(balances::Call::transfer(2, 1))
+}
+
+fn schedule_at(at: u64) -> Result {
+    Scheduler::schedule(Origin::signed(SENDER), transfer(), at, 0, None, None)
+}
+
+/// The events the scheduler deposited so far.
+fn events() -> Vec<Event<Test>> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            TestEvent::scheduler(event) => Some(event),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn schedule_rejects_current_and_past_blocks() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        assert_noop!(schedule_at(NOW - 1), Error::BlockInPast.as_str());
+        assert_noop!(schedule_at(NOW), Error::BlockInPast.as_str());
+    });
+}
+
+#[test]
+fn schedule_enforces_min_delay() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let earliest = NOW + MinScheduleDelay::get();
+        assert_noop!(schedule_at(earliest - 1), Error::TooSoon.as_str());
+        assert_ok!(schedule_at(earliest));
+        assert_eq!(Scheduler::queued_at(earliest), 1);
+    });
+}
+
+#[test]
+fn schedule_enforces_horizon() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let latest = NOW + MaxScheduleHorizon::get();
+        assert_noop!(schedule_at(latest + 1), Error::TooFarAhead.as_str());
+        assert_ok!(schedule_at(latest));
+        assert_eq!(Scheduler::queued_at(latest), 1);
+    });
+}
+
+#[test]
+fn schedule_after_checks_delay_against_bounds() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let signed = || Origin::signed(SENDER);
+        assert_noop!(
+            Scheduler::schedule_after(signed(), transfer(), 0),
+            Error::BlockInPast.as_str()
+        );
+        assert_noop!(
+            Scheduler::schedule_after(signed(), transfer(), 1),
+            Error::TooSoon.as_str()
+        );
+        let horizon = MaxScheduleHorizon::get();
+        assert_noop!(
+            Scheduler::schedule_after(signed(), transfer(), horizon + 1),
+            Error::TooFarAhead.as_str()
+        );
+        assert_ok!(Scheduler::schedule_after(signed(), transfer(), horizon));
+        assert_eq!(Scheduler::queued_at(NOW + horizon), 1);
+    });
+}
+
+#[test]
+fn batch_is_rejected_whole_if_one_block_is_out_of_bounds() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let earliest = NOW + MinScheduleDelay::get();
+        let latest = NOW + MaxScheduleHorizon::get();
+        let tasks = vec![(*transfer(), earliest), (*transfer(), latest + 1)];
+        assert_noop!(
+            Scheduler::schedule_batch(Origin::signed(SENDER), tasks, 0),
+            Error::TooFarAhead.as_str()
+        );
+    });
+}
+
+#[test]
+fn reschedule_may_not_pass_horizon() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let id = Scheduler::task_id(&SENDER, &0, &balances::Call::transfer(2, 1));
+        assert_ok!(schedule_at(NOW + 50));
+        let latest = NOW + MaxScheduleHorizon::get();
+        assert_noop!(
+            Scheduler::reschedule(Origin::signed(SENDER), id, latest + 1),
+            Error::TooFarAhead.as_str()
+        );
+        assert_ok!(Scheduler::reschedule(Origin::signed(SENDER), id, latest));
+        assert_eq!(Scheduler::queued_at(latest), 1);
+    });
+}
+
+#[test]
+fn runs_are_reported_to_on_task_executed() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let id = Scheduler::task_id(&SENDER, &0, &balances::Call::transfer(2, 1));
+        let overdraft = balances::Call::transfer(2, 10_000_000);
+        let failing = Scheduler::task_id(&SENDER, &1, &overdraft);
+        assert_ok!(schedule_at(at));
+        assert_ok!(Scheduler::schedule(
+            Origin::signed(SENDER),
+            Box::new(overdraft),
+            at,
+            1,
+            None,
+            None
+        ));
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        let executed = EXECUTED.with(|executed| executed.borrow().clone());
+        assert_eq!(executed.len(), 2);
+        assert_eq!(executed[0], (id, SENDER, Ok(())));
+        assert_eq!((executed[1].0, executed[1].1), (failing, SENDER));
+        assert!(executed[1].2.is_err());
+    });
+}
+
+#[test]
+fn genesis_tasks_are_queued_and_run_with_their_origin() {
+    let grant = balances::Call::<Test>::set_balance(3, 500, 0).encode();
+    let mut ext = new_test_ext_with_tasks(vec![(DispatchAs::Root, grant, 3)]);
+    with_externalities(&mut ext, || {
+        assert_eq!(Scheduler::queued_at(3), 1);
+        System::set_block_number(3);
+        Scheduler::run_tasks(3);
+        assert_eq!(Balances::free_balance(3), 500);
+    });
+}
+
+#[test]
+fn idempotency_key_is_refused_while_its_task_is_pending() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let key = Some(H256::repeat_byte(7));
+        let schedule =
+            |at| Scheduler::schedule(Origin::signed(SENDER), transfer(), at, 0, None, key);
+        assert_ok!(schedule(at));
+        assert_noop!(schedule(at + 1), Error::DuplicateKey.as_str());
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert_eq!(Scheduler::idempotent_task(&SENDER, &key.unwrap()), None);
+        assert_ok!(schedule(at + MinScheduleDelay::get()));
+    });
+}
+
+#[test]
+fn execution_fee_is_charged_only_for_dispatched_calls() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        EXECUTION_FEE.with(|fee| *fee.borrow_mut() = 10);
+        let at = NOW + MinScheduleDelay::get();
+        assert_ok!(schedule_at(at));
+        assert_ok!(schedule_at(at + 1));
+        let balance = Balances::free_balance(SENDER);
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert_eq!(Balances::free_balance(SENDER), balance - 1 - 10);
+
+        // A call filtered out since it was scheduled is neither dispatched nor charged for.
+        SCHEDULABLE.with(|schedulable| *schedulable.borrow_mut() = false);
+        System::set_block_number(at + 1);
+        Scheduler::run_tasks(at + 1);
+        assert_eq!(Balances::free_balance(SENDER), balance - 1 - 10);
+    });
+}
+
+#[test]
+fn schedule_after_task_may_not_pass_horizon() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let call = balances::Call::transfer(2, 1);
+        let latest = NOW + MaxScheduleHorizon::get();
+        assert_ok!(Scheduler::schedule_periodic(
+            Origin::signed(SENDER),
+            Box::new(call.clone()),
+            latest - 10,
+            5,
+            3,
+            0,
+            None
+        ));
+        let periodic = Scheduler::task_id(&SENDER, &0, &call);
+        assert_ok!(schedule_at(latest - 1));
+        let last = Scheduler::task_id(&SENDER, &1, &call);
+
+        let after =
+            |id| Scheduler::schedule_after_task(Origin::signed(SENDER), transfer(), id, 0, None);
+        assert_noop!(after(periodic), Error::TooFarAhead.as_str());
+        assert_ok!(after(last));
+        let dependent = Scheduler::task_id(&SENDER, &2, &call);
+        assert_eq!(Scheduler::task(&dependent).unwrap().block_number, latest);
+    });
+}
+
+#[test]
+fn batch_counts_its_own_tasks_towards_full_blocks() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        MAX_TASKS_PER_BLOCK.with(|max| *max.borrow_mut() = 2);
+        DEPOSIT_PER_BLOCK.with(|deposit| *deposit.borrow_mut() = 1);
+        let at = NOW + MinScheduleDelay::get();
+        assert_ok!(schedule_at(at));
+        let reserved = Balances::reserved_balance(SENDER);
+
+        let tasks = vec![(*transfer(), at + 1), (*transfer(), at), (*transfer(), at)];
+        assert_noop!(
+            Scheduler::schedule_batch(Origin::signed(SENDER), tasks, 0),
+            "block is full"
+        );
+        assert_eq!(Balances::reserved_balance(SENDER), reserved);
+        assert_eq!(Scheduler::next_nonce(SENDER), 1);
+        assert_eq!(Scheduler::queued_at(at + 1), 0);
+
+        let tasks = vec![(*transfer(), at + 1), (*transfer(), at)];
+        assert_ok!(Scheduler::schedule_batch(Origin::signed(SENDER), tasks, 0));
+        assert_eq!(Scheduler::queued_at(at), 2);
+        assert_eq!(Scheduler::queued_at(at + 1), 1);
+    });
+}
+
+#[test]
+fn tasks_over_max_per_block_are_postponed_one_by_one() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        let ids: Vec<_> = (0..3)
+            .map(|nonce| Scheduler::task_id(&SENDER, &nonce, &call))
+            .collect();
+        for _ in 0..3 {
+            assert_ok!(schedule_at(at));
+        }
+        MAX_TASKS_PER_BLOCK.with(|max| *max.borrow_mut() = 1);
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        let events = events();
+        assert!(events.contains(&RawEvent::TaskExecutedOk(ids[0], at, 0)));
+        assert!(events.contains(&RawEvent::TaskPostponed(ids[1], at + 1)));
+        assert!(events.contains(&RawEvent::TaskPostponed(ids[2], at + 1)));
+        assert_eq!(events.last(), Some(&RawEvent::TasksOverdue(2)));
+
+        System::set_block_number(at + 1);
+        Scheduler::run_tasks(at + 1);
+        assert_eq!(Scheduler::next_execution(ids[1]), None);
+        assert_eq!(Scheduler::next_execution(ids[2]).unwrap().position, Some(0));
+        // Still overdue, but already reported as postponed.
+        let postponed = events()
+            .into_iter()
+            .filter(|event| match event {
+                RawEvent::TaskPostponed(id, _) => *id == ids[2],
+                _ => false,
+            })
+            .count();
+        assert_eq!(postponed, 1);
+        assert_eq!(events().last(), Some(&RawEvent::TasksOverdue(1)));
+    });
+}
+
+#[test]
+fn higher_tip_outbids_the_lowest_from_a_full_block() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        MAX_TASKS_PER_BLOCK.with(|max| *max.borrow_mut() = 1);
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        let tipped = |tip| {
+            Scheduler::schedule_with_tip(Origin::signed(SENDER), transfer(), at, 0, tip, None)
+        };
+        assert_ok!(tipped(5));
+        let outbid = Scheduler::task_id(&SENDER, &0, &call);
+        assert_noop!(tipped(5), "block is full, a higher tip is needed");
+        assert_ok!(tipped(10));
+        let winner = Scheduler::task_id(&SENDER, &1, &call);
+        assert!(events().contains(&RawEvent::TaskOutbid(outbid, at + 1)));
+        assert_eq!(
+            Scheduler::next_execution(outbid).unwrap().block_number,
+            at + 1
+        );
+        assert_eq!(Balances::reserved_balance(SENDER), 15);
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert!(events().contains(&RawEvent::TaskExecutedOk(winner, at, 0)));
+        assert_eq!(Balances::reserved_balance(SENDER), 5);
+    });
+}
+
+#[test]
+fn tip_is_kept_when_the_run_is_skipped() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        let id = Scheduler::task_id(&SENDER, &0, &call);
+        assert_ok!(Scheduler::schedule_with_tip(
+            Origin::signed(SENDER),
+            transfer(),
+            at,
+            0,
+            10,
+            None
+        ));
+        let unreachable = Some(u64::max_value());
+        assert_ok!(Scheduler::set_condition(
+            Origin::signed(SENDER),
+            id,
+            unreachable
+        ));
+        let balance = Balances::free_balance(SENDER);
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert!(events().contains(&RawEvent::TaskSkipped(id, at)));
+        assert_eq!(Balances::reserved_balance(SENDER), 0);
+        assert_eq!(Balances::free_balance(SENDER), balance + 10);
+    });
+}
+
+#[test]
+fn tip_is_refunded_when_the_call_is_filtered_out() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        let id = Scheduler::task_id(&SENDER, &0, &call);
+        assert_ok!(Scheduler::schedule_with_tip(
+            Origin::signed(SENDER),
+            transfer(),
+            at,
+            0,
+            10,
+            None
+        ));
+        let balance = Balances::free_balance(SENDER);
+        SCHEDULABLE.with(|schedulable| *schedulable.borrow_mut() = false);
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        let error = b"call may not be scheduled".to_vec();
+        assert!(events().contains(&RawEvent::TaskExecutedErr(id, at, 0, error)));
+        assert_eq!(Balances::reserved_balance(SENDER), 0);
+        assert_eq!(Balances::free_balance(SENDER), balance + 10);
+    });
+}
+
+#[test]
+fn tip_is_refunded_when_the_execution_fee_is_unpaid() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        let id = Scheduler::task_id(&SENDER, &0, &call);
+        assert_ok!(Scheduler::schedule_with_tip(
+            Origin::signed(SENDER),
+            transfer(),
+            at,
+            0,
+            10,
+            None
+        ));
+        let balance = Balances::free_balance(SENDER);
+        EXECUTION_FEE.with(|fee| *fee.borrow_mut() = balance + 1);
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert!(events().contains(&RawEvent::ExecutionFeeUnpaid(id, balance + 1)));
+        assert_eq!(Balances::reserved_balance(SENDER), 0);
+        assert_eq!(Balances::free_balance(SENDER), balance + 10);
+    });
+}
+
+#[test]
+fn idempotency_key_is_free_again_once_its_task_is_cancelled() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let key = H256::repeat_byte(7);
+        let schedule =
+            || Scheduler::schedule(Origin::signed(SENDER), transfer(), at, 0, None, Some(key));
+        assert_ok!(schedule());
+        let id = Scheduler::idempotent_task(&SENDER, &key).unwrap();
+        assert_ok!(Scheduler::cancel(Origin::signed(SENDER), id));
+        assert_eq!(Scheduler::idempotent_task(&SENDER, &key), None);
+
+        assert_ok!(schedule());
+        let again = Scheduler::task_id(&SENDER, &1, &balances::Call::transfer(2, 1));
+        assert_eq!(Scheduler::idempotent_task(&SENDER, &key), Some(again));
+    });
+}
+
+#[test]
+fn scheduled_call_is_dispatched_from_the_sender_at_its_block() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        assert_noop!(
+            Scheduler::schedule(Origin::NONE, transfer(), at, 0, None, None),
+            "bad origin: expected to be a signed origin"
+        );
+        assert_ok!(schedule_at(at));
+        assert_eq!(Scheduler::next_nonce(SENDER), 1);
+        assert_eq!(Scheduler::tasks_by_sender(SENDER).len(), 1);
+
+        System::set_block_number(at - 1);
+        Scheduler::run_tasks(at - 1);
+        assert_eq!(Balances::free_balance(2), 0);
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert_eq!(Balances::free_balance(2), 1);
+        assert!(Scheduler::tasks_by_sender(SENDER).is_empty());
+    });
+}
+
+#[test]
+fn periodic_task_runs_count_times_every_period() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let periodic = |period, count| {
+            Scheduler::schedule_periodic(
+                Origin::signed(SENDER),
+                transfer(),
+                at,
+                period,
+                count,
+                0,
+                None,
+            )
+        };
+        assert_noop!(periodic(0, 2), "period must be non-zero");
+        assert_noop!(periodic(3, 0), "repetition count must be non-zero");
+        assert_ok!(periodic(3, 2));
+        let id = Scheduler::task_id(&SENDER, &0, &balances::Call::transfer(2, 1));
+
+        for block in at..at + 7 {
+            System::set_block_number(block);
+            Scheduler::run_tasks(block);
+            if block == at {
+                assert_eq!(Scheduler::next_execution(id).unwrap().block_number, at + 3);
+            }
+        }
+        assert_eq!(Balances::free_balance(2), 2);
+        assert_eq!(Scheduler::task(&id), None);
+    });
+}
+
+#[test]
+fn lower_priority_value_runs_first_within_a_block() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let late = balances::Call::transfer(2, 1);
+        let early = balances::Call::transfer(3, 1);
+        let schedule = |call: &balances::Call<Test>, priority| {
+            let call = Box::new(call.clone());
+            Scheduler::schedule(Origin::signed(SENDER), call, at, priority, None, None)
+        };
+        assert_ok!(schedule(&late, 2));
+        assert_ok!(schedule(&late, 2));
+        assert_ok!(schedule(&early, 1));
+        let ids = [
+            Scheduler::task_id(&SENDER, &2, &early),
+            Scheduler::task_id(&SENDER, &0, &late),
+            Scheduler::task_id(&SENDER, &1, &late),
+        ];
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        let executed = EXECUTED.with(|executed| executed.borrow().clone());
+        let order: Vec<_> = executed.into_iter().map(|(id, _, _)| id).collect();
+        assert_eq!(order, ids.to_vec());
+    });
+}
+
+#[test]
+fn deposit_is_refunded_on_success_and_slashed_on_failure() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        DEPOSIT_PER_BLOCK.with(|deposit| *deposit.borrow_mut() = 10);
+        let at = NOW + MinScheduleDelay::get();
+        let overdraft = balances::Call::transfer(2, 10_000_000);
+        let failing = Scheduler::task_id(&SENDER, &1, &overdraft);
+        assert_ok!(schedule_at(at));
+        assert_ok!(Scheduler::schedule(
+            Origin::signed(SENDER),
+            Box::new(overdraft),
+            at,
+            0,
+            None,
+            None
+        ));
+        assert_eq!(Balances::reserved_balance(SENDER), 2 * 20);
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert!(events().contains(&RawEvent::DepositSlashed(failing, 2)));
+        assert_eq!(Balances::reserved_balance(SENDER), 0);
+        assert_eq!(Balances::free_balance(SENDER), 1_000_000 - 1 - 2);
+    });
+}
+
+#[test]
+fn deposit_must_be_affordable() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        DEPOSIT_PER_BLOCK.with(|deposit| *deposit.borrow_mut() = 1_000_000);
+        assert_noop!(
+            schedule_at(NOW + MinScheduleDelay::get()),
+            "not enough free balance for scheduling deposit"
+        );
+    });
+}
+
+#[test]
+fn filtered_calls_cannot_be_scheduled() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        SCHEDULABLE.with(|schedulable| *schedulable.borrow_mut() = false);
+        let at = NOW + MinScheduleDelay::get();
+        assert_noop!(schedule_at(at), "call may not be scheduled");
+        assert_noop!(
+            Scheduler::schedule_batch(Origin::signed(SENDER), vec![(*transfer(), at)], 0),
+            "call may not be scheduled"
+        );
+        let encoded = transfer().encode();
+        assert_noop!(
+            Scheduler::note_preimage(Origin::signed(SENDER), encoded),
+            "call may not be scheduled"
+        );
+    });
+}
+
+#[test]
+fn tasks_over_the_weight_cap_carry_over() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        // Too little for any call but the first, which always runs.
+        MAX_SCHEDULED_WEIGHT.with(|max| *max.borrow_mut() = Perbill::from_parts(1));
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        let second = Scheduler::task_id(&SENDER, &1, &call);
+        assert_ok!(schedule_at(at));
+        assert_ok!(schedule_at(at));
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert_eq!(Balances::free_balance(2), 1);
+        assert_eq!(Scheduler::next_execution(second).unwrap().position, Some(0));
+
+        System::set_block_number(at + 1);
+        Scheduler::run_tasks(at + 1);
+        assert_eq!(Balances::free_balance(2), 2);
+        assert_eq!(Scheduler::pending_tasks(), None);
+    });
+}
+
+#[test]
+fn task_not_run_by_its_deadline_expires() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        DEPOSIT_PER_BLOCK.with(|deposit| *deposit.borrow_mut() = 10);
+        let at = NOW + MinScheduleDelay::get();
+        let until = |valid_until| {
+            Scheduler::schedule(
+                Origin::signed(SENDER),
+                transfer(),
+                at,
+                0,
+                Some(valid_until),
+                None,
+            )
+        };
+        assert_noop!(until(at - 1), "task must be scheduled before it expires");
+        assert_ok!(until(at));
+        assert_ok!(until(at));
+        let late = Scheduler::task_id(&SENDER, &1, &balances::Call::transfer(2, 1));
+
+        // Only one of them runs at their block, so the other is past its deadline next block.
+        MAX_TASKS_PER_BLOCK.with(|max| *max.borrow_mut() = 1);
+        for block in at..at + 2 {
+            System::set_block_number(block);
+            Scheduler::run_tasks(block);
+        }
+        assert!(events().contains(&RawEvent::TaskExpired(late, 2)));
+        assert_eq!(Scheduler::task(&late), None);
+        assert_eq!(Balances::free_balance(2), 1);
+        assert_eq!(Balances::reserved_balance(SENDER), 0);
+        assert_eq!(Balances::free_balance(SENDER), 1_000_000 - 1 - 2);
+    });
+}
+
+#[test]
+fn results_are_kept_for_the_retention_period() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let id = Scheduler::task_id(&SENDER, &0, &balances::Call::transfer(2, 1));
+        let overdraft = balances::Call::transfer(2, 10_000_000);
+        let failing = Scheduler::task_id(&SENDER, &1, &overdraft);
+        assert_ok!(schedule_at(at));
+        assert_ok!(Scheduler::schedule(
+            Origin::signed(SENDER),
+            Box::new(overdraft),
+            at,
+            0,
+            None,
+            None
+        ));
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        let result = Scheduler::task_result(&id).unwrap();
+        assert_eq!((result.error, result.executed_at), (None, at));
+        assert!(Scheduler::task_result(&failing).unwrap().error.is_some());
+
+        let expiry = at + ResultRetention::get();
+        Scheduler::prune_results(expiry - 1);
+        assert!(Scheduler::task_result(&id).is_some());
+        Scheduler::prune_results(expiry);
+        assert_eq!(Scheduler::task_result(&id), None);
+        assert_eq!(Scheduler::task_result(&failing), None);
+    });
+}
+
+#[test]
+fn rescheduled_task_runs_at_its_new_block_only() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let id = Scheduler::task_id(&SENDER, &0, &balances::Call::transfer(2, 1));
+        assert_ok!(schedule_at(at + 1));
+        assert_noop!(
+            Scheduler::reschedule(Origin::signed(2), id, at + 5),
+            "only the sender may reschedule a task"
+        );
+        assert_noop!(
+            Scheduler::reschedule(Origin::signed(SENDER), id, at),
+            "task may only be moved to a later block"
+        );
+        assert_ok!(Scheduler::reschedule(Origin::signed(SENDER), id, at + 5));
+        assert!(events().contains(&RawEvent::TaskRescheduled(id, at + 1, at + 5)));
+        assert_eq!(Scheduler::queued_at(at + 1), 0);
+
+        for block in at..at + 5 {
+            System::set_block_number(block);
+            Scheduler::run_tasks(block);
+        }
+        assert_eq!(Balances::free_balance(2), 0);
+        System::set_block_number(at + 5);
+        Scheduler::run_tasks(at + 5);
+        assert_eq!(Balances::free_balance(2), 1);
+    });
+}
+
+#[test]
+fn dependent_task_runs_after_a_success_and_is_cancelled_after_a_failure() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        let overdraft = balances::Call::transfer(2, 10_000_000);
+        let after =
+            |id| Scheduler::schedule_after_task(Origin::signed(SENDER), transfer(), id, 0, None);
+        assert_noop!(after(H256::repeat_byte(1)), "no such task");
+        assert_ok!(schedule_at(at));
+        assert_ok!(after(Scheduler::task_id(&SENDER, &0, &call)));
+        let dependent = Scheduler::task_id(&SENDER, &1, &call);
+        assert_ok!(Scheduler::schedule(
+            Origin::signed(SENDER),
+            Box::new(overdraft.clone()),
+            at,
+            0,
+            None,
+            None
+        ));
+        assert_ok!(after(Scheduler::task_id(&SENDER, &2, &overdraft)));
+        let orphan = Scheduler::task_id(&SENDER, &3, &call);
+        assert_eq!(Scheduler::queued_at(at + 1), 0);
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert!(events().contains(&RawEvent::TaskScheduled(dependent, at + 1)));
+        assert!(events().contains(&RawEvent::TaskCancelled(orphan)));
+        assert_eq!(Scheduler::task(&orphan), None);
+
+        System::set_block_number(at + 1);
+        Scheduler::run_tasks(at + 1);
+        assert_eq!(Balances::free_balance(2), 2);
+    });
+}
+
+#[test]
+fn task_takes_at_most_max_dependents() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        assert_ok!(schedule_at(at));
+        let first = Scheduler::task_id(&SENDER, &0, &call);
+        let after =
+            |who| Scheduler::schedule_after_task(Origin::signed(who), transfer(), first, 0, None);
+        assert_ok!(after(SENDER));
+        // Waiting for someone else's task counts towards its limit too.
+        assert_ok!(after(2));
+        assert_noop!(after(3), Error::TooManyDependents.as_str());
+        assert_eq!(
+            Scheduler::dependents(&first).len() as u32,
+            MaxDependents::get()
+        );
+    });
+}
+
+#[test]
+fn unmet_condition_skips_or_postpones_the_task() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        let skipped = Scheduler::task_id(&SENDER, &0, &call);
+        let postponed = Scheduler::task_id(&SENDER, &1, &call);
+        let unreachable = Some(u64::max_value());
+        assert_ok!(schedule_at(at));
+        assert_noop!(
+            Scheduler::set_condition(Origin::signed(2), skipped, unreachable),
+            "only the sender may set a task's condition"
+        );
+        assert_ok!(Scheduler::set_condition(
+            Origin::signed(SENDER),
+            skipped,
+            unreachable
+        ));
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert!(events().contains(&RawEvent::TaskSkipped(skipped, at)));
+        assert_eq!(Scheduler::task(&skipped), None);
+
+        ON_UNMET_CONDITION.with(|unmet| *unmet.borrow_mut() = UnmetCondition::Postpone);
+        assert_ok!(schedule_at(at + 2));
+        assert_ok!(Scheduler::set_condition(
+            Origin::signed(SENDER),
+            postponed,
+            unreachable
+        ));
+        System::set_block_number(at + 2);
+        Scheduler::run_tasks(at + 2);
+        assert!(events().contains(&RawEvent::TaskPostponed(postponed, at + 3)));
+        assert_eq!(Balances::free_balance(2), 0);
+
+        assert_ok!(Scheduler::set_condition(
+            Origin::signed(SENDER),
+            postponed,
+            None
+        ));
+        System::set_block_number(at + 3);
+        Scheduler::run_tasks(at + 3);
+        assert_eq!(Balances::free_balance(2), 1);
+    });
+}
+
+#[test]
+fn agenda_lists_upcoming_tasks_in_run_order() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        let first = Scheduler::task_id(&SENDER, &0, &call);
+        let dependent = Scheduler::task_id(&SENDER, &1, &call);
+        assert_ok!(schedule_at(at));
+        assert_ok!(Scheduler::schedule_after_task(
+            Origin::signed(SENDER),
+            transfer(),
+            first,
+            0,
+            None
+        ));
+        let grant = balances::Call::set_balance(3, 500, 0);
+        assert_ok!(Scheduler::schedule_as(
+            Origin::ROOT,
+            DispatchAs::Root,
+            Box::new(grant.clone()),
+            at + 1,
+            0
+        ));
+        let governance = Scheduler::task_id(&0, &0, &grant);
+
+        let agenda = Scheduler::agenda(NOW, at + 1, None);
+        let listed: Vec<_> = agenda
+            .iter()
+            .map(|entry| (entry.id, entry.status.clone()))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                (first, TaskStatus::Queued),
+                (dependent, TaskStatus::Waiting(first)),
+                (governance, TaskStatus::Queued),
+            ]
+        );
+        assert_eq!(agenda[0].call, call.encode());
+        assert_eq!(agenda[2].block_number, at + 1);
+        assert_eq!(Scheduler::agenda(NOW, at + 1, Some(SENDER)).len(), 2);
+        assert_eq!(Scheduler::agenda(at + 1, at + 1, None).len(), 1);
+    });
+}
+
+#[test]
+fn next_execution_and_queue_depth_follow_the_queues() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        let first = Scheduler::task_id(&SENDER, &0, &call);
+        let second = Scheduler::task_id(&SENDER, &1, &call);
+        let dependent = Scheduler::task_id(&SENDER, &3, &call);
+        assert_ok!(schedule_at(at));
+        assert_ok!(schedule_at(at));
+        assert_ok!(schedule_at(at + 2));
+        assert_ok!(Scheduler::schedule_after_task(
+            Origin::signed(SENDER),
+            transfer(),
+            first,
+            0,
+            None
+        ));
+
+        let position = |id| Scheduler::next_execution(id).map(|next| next.position);
+        assert_eq!(position(first), Some(Some(0)));
+        assert_eq!(position(second), Some(Some(1)));
+        assert_eq!(position(dependent), Some(None));
+        assert_eq!(position(H256::repeat_byte(1)), None);
+        assert_eq!(
+            Scheduler::queue_depth(NOW, at + 2),
+            vec![(at, 2), (at + 2, 1)]
+        );
+    });
+}
+
+#[test]
+fn pending_tasks_per_account_are_limited() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        MAX_PENDING_PER_ACCOUNT.with(|max| *max.borrow_mut() = 2);
+        let at = NOW + MinScheduleDelay::get();
+        assert_ok!(schedule_at(at));
+        let tasks = vec![(*transfer(), at), (*transfer(), at)];
+        assert_noop!(
+            Scheduler::schedule_batch(Origin::signed(SENDER), tasks, 0),
+            Error::TooManyPendingTasks.as_str()
+        );
+        assert_ok!(schedule_at(at));
+        assert_noop!(schedule_at(at), Error::TooManyPendingTasks.as_str());
+
+        // Tasks that have run no longer count.
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert_ok!(schedule_at(at + 2));
+    });
+}
+
+#[test]
+fn delegated_task_is_scheduled_for_its_signer_once() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let relayer = 3;
+        let payload = DelegatedTask {
+            sender: SENDER,
+            call: balances::Call::transfer(2, 1),
+            block_number: NOW + MinScheduleDelay::get(),
+            priority: 0,
+            nonce: 0,
+            expiry: NOW,
+        };
+        let sign =
+            |signer, payload: &DelegatedTaskOf<Test>| TestSignature(signer, payload.encode());
+        let delegate = |payload: &DelegatedTaskOf<Test>, signature| {
+            Scheduler::schedule_delegated(Origin::signed(relayer), payload.clone(), signature)
+        };
+        assert_noop!(
+            delegate(&payload, sign(relayer, &payload)),
+            "invalid delegation signature"
+        );
+        let expired = DelegatedTask {
+            expiry: NOW - 1,
+            ..payload.clone()
+        };
+        assert_noop!(
+            delegate(&expired, sign(SENDER, &expired)),
+            "delegation has expired"
+        );
+
+        assert_ok!(delegate(&payload, sign(SENDER, &payload)));
+        assert_eq!(Scheduler::tasks_by_sender(SENDER).len(), 1);
+        assert_eq!(Scheduler::next_nonce(SENDER), 1);
+        assert_noop!(delegate(&payload, sign(SENDER, &payload)), "invalid nonce");
+    });
+}
+
+#[test]
+fn governance_tasks_run_with_their_origin_free_of_charge() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        EXECUTION_FEE.with(|fee| *fee.borrow_mut() = 10);
+        DEPOSIT_PER_BLOCK.with(|deposit| *deposit.borrow_mut() = 10);
+        let at = NOW + MinScheduleDelay::get();
+        let signed = DispatchAs::Signed(SENDER);
+        let schedule_as = |origin, dispatch_as, call: balances::Call<Test>| {
+            Scheduler::schedule_as(origin, dispatch_as, Box::new(call), at, 0)
+        };
+        assert_noop!(
+            schedule_as(Origin::signed(SENDER), signed.clone(), *transfer()),
+            "Invalid origin"
+        );
+        assert_ok!(schedule_as(Origin::ROOT, signed, *transfer()));
+        let grant = balances::Call::set_balance(3, 500, 0);
+        assert_ok!(schedule_as(Origin::ROOT, DispatchAs::Root, grant.clone()));
+        let governance = Scheduler::task_id(&0, &1, &grant);
+        assert_eq!(Balances::reserved_balance(SENDER), 0);
+        assert_noop!(
+            Scheduler::cancel(Origin::signed(SENDER), governance),
+            "only the sender may cancel a task"
+        );
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert_eq!(Balances::free_balance(2), 1);
+        assert_eq!(Balances::free_balance(SENDER), 1_000_000 - 1);
+        assert_eq!(Balances::free_balance(3), 500);
+    });
+}
+
+#[test]
+fn paused_scheduler_queues_tasks_until_resumed() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        assert_ok!(schedule_at(at));
+        assert_noop!(Scheduler::pause(Origin::signed(SENDER)), "Invalid origin");
+        assert_noop!(Scheduler::resume(Origin::ROOT), "scheduler is not paused");
+        assert_ok!(Scheduler::pause(Origin::ROOT));
+        assert_noop!(
+            Scheduler::pause(Origin::ROOT),
+            "scheduler is already paused"
+        );
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert_eq!(Balances::free_balance(2), 0);
+        assert_eq!(Scheduler::pending_tasks().map(|tasks| tasks.len), Some(1));
+        assert_ok!(schedule_at(at + 2));
+
+        assert_ok!(Scheduler::resume(Origin::ROOT));
+        System::set_block_number(at + 1);
+        Scheduler::run_tasks(at + 1);
+        assert_eq!(Balances::free_balance(2), 1);
+    });
+}
+
+#[test]
+fn force_cancel_slashes_the_task_and_refunds_its_dependents() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        DEPOSIT_PER_BLOCK.with(|deposit| *deposit.borrow_mut() = 10);
+        let at = NOW + MinScheduleDelay::get();
+        let call = balances::Call::transfer(2, 1);
+        let id = Scheduler::task_id(&SENDER, &0, &call);
+        let dependent = Scheduler::task_id(&SENDER, &1, &call);
+        assert_ok!(schedule_at(at));
+        assert_ok!(Scheduler::schedule_after_task(
+            Origin::signed(SENDER),
+            transfer(),
+            id,
+            0,
+            None
+        ));
+        assert_eq!(Balances::reserved_balance(SENDER), 20 + 30);
+
+        assert_noop!(
+            Scheduler::force_cancel(Origin::signed(SENDER), id),
+            "Invalid origin"
+        );
+        assert_ok!(Scheduler::force_cancel(Origin::ROOT, id));
+        assert!(events().contains(&RawEvent::TaskForceCancelled(id, 10)));
+        assert!(events().contains(&RawEvent::TaskCancelled(dependent)));
+        assert_eq!(Scheduler::task(&dependent), None);
+        assert_eq!(Balances::reserved_balance(SENDER), 0);
+        assert_eq!(Balances::free_balance(SENDER), 1_000_000 - 10);
+        assert_noop!(Scheduler::force_cancel(Origin::ROOT, id), "no such task");
+    });
+}
+
+#[test]
+fn task_scheduled_by_hash_runs_the_noted_preimage() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(NOW);
+        let at = NOW + MinScheduleDelay::get();
+        let encoded = balances::Call::<Test>::transfer(2, 1).encode();
+        let hash = BlakeTwo256::hash(&encoded);
+        let missing = H256::repeat_byte(1);
+        let by_hash = |hash| Scheduler::schedule_hash(Origin::signed(SENDER), hash, at, 0, None);
+        assert_ok!(by_hash(hash));
+        assert_ok!(by_hash(missing));
+        let unnoted = BlakeTwo256::hash_of(&(&SENDER, &1u64, &missing));
+
+        let note = |data| Scheduler::note_preimage(Origin::signed(SENDER), data);
+        assert_noop!(note(vec![0xff; 3]), "preimage is not a call");
+        assert_ok!(note(encoded.clone()));
+        assert_noop!(note(encoded), "preimage already noted");
+        assert_noop!(
+            Scheduler::unnote_preimage(Origin::signed(2), hash),
+            "only the provider may unnote a preimage"
+        );
+
+        System::set_block_number(at);
+        Scheduler::run_tasks(at);
+        assert_eq!(Balances::free_balance(2), 1);
+        assert_eq!(Scheduler::preimage(&hash), None);
+        let error = b"call preimage has not been noted".to_vec();
+        assert!(events().contains(&RawEvent::TaskExecutedErr(unnoted, at, 1, error)));
+    });
+}
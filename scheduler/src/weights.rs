@@ -0,0 +1,327 @@
+//! Weights of the scheduler's operations.
+
+use codec::Decode;
+use rstd::{marker::PhantomData, prelude::*};
+use sr_primitives::traits::Saturating;
+use support::weights::{ClassifyDispatch, DispatchClass, GetDispatchInfo, WeighData, Weight};
+
+use crate::{BalanceOf, DelegatedTaskOf, DispatchAs, Module, Trait};
+
+/// Weights of scheduling and running tasks, in the units of `MaximumBlockWeight`. Runtimes
+/// implement this from the figures the module's benchmarks report, run with
+/// `cargo test -p scheduler --release --features runtime-benchmarks -- --nocapture bench`.
+/// `()` charges the default weight of a dispatchable per task.
+pub trait WeightInfo {
+    /// `schedule_task` for a block that already has `t` tasks.
+    fn schedule_task(t: u32) -> Weight;
+    /// `run_tasks` for a block with `t` tasks. The benchmark runs balance transfers, so
+    /// heavier tasks need their own weight on top.
+    fn run_tasks(t: u32) -> Weight;
+}
+
+impl WeightInfo for () {
+    fn schedule_task(_t: u32) -> Weight {
+        10_000
+    }
+
+    fn run_tasks(t: u32) -> Weight {
+        t.saturating_mul(10_000)
+    }
+}
+
+/// Weighs `schedule` by the tasks already queued for the target block, plus the weight of
+/// the call it schedules.
+pub struct ScheduleWeight<T>(PhantomData<T>);
+
+/// Weighs `schedule_after` like `ScheduleWeight`, for the block `delay_in_blocks` blocks
+/// from now.
+pub struct DelayedScheduleWeight<T>(PhantomData<T>);
+
+/// Weighs `schedule_with_tip` like `ScheduleWeight`. Looking for the task to displace from
+/// a full block walks its queue, which `schedule_task` weighs already.
+pub struct TippedScheduleWeight<T>(PhantomData<T>);
+
+/// Weighs `schedule_hash` like `ScheduleWeight`, without a call: its weight is charged when
+/// it is noted.
+pub struct ScheduleHashWeight<T>(PhantomData<T>);
+
+/// Weighs `note_preimage` like scheduling into an empty block, plus the weight of the call it
+/// notes, which runs without paying for its weight again.
+pub struct NotePreimageWeight<T>(PhantomData<T>);
+
+/// Weighs `schedule_periodic` like `ScheduleWeight`, with the call's weight once for every
+/// time it runs.
+pub struct PeriodicScheduleWeight<T>(PhantomData<T>);
+
+/// Weighs `schedule_after_task` like scheduling into an empty block, plus the weight of the
+/// call it schedules. The task is queued when the one it waits for has run, in
+/// `on_initialize`.
+pub struct ScheduleAfterTaskWeight<T>(PhantomData<T>);
+
+/// Weighs `schedule_batch` like `ScheduleWeight` for each of its tasks.
+pub struct BatchScheduleWeight<T>(PhantomData<T>);
+
+/// Weighs `schedule_delegated` like `ScheduleWeight`.
+pub struct DelegatedScheduleWeight<T>(PhantomData<T>);
+
+/// Weighs `schedule_as` like `ScheduleWeight`.
+pub struct ScheduleAsWeight<T>(PhantomData<T>);
+
+impl<T> Default for ScheduleWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for DelayedScheduleWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for TippedScheduleWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for ScheduleHashWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for NotePreimageWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for PeriodicScheduleWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for ScheduleAfterTaskWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for BatchScheduleWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for DelegatedScheduleWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for ScheduleAsWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// The arguments of `schedule`: call, block, priority, deadline and idempotency key.
+type ScheduleArgs<'a, T> = (
+    &'a Box<<T as Trait>::Call>,
+    &'a <T as system::Trait>::BlockNumber,
+    &'a u8,
+    &'a Option<<T as system::Trait>::BlockNumber>,
+    &'a Option<<T as system::Trait>::Hash>,
+);
+
+impl<T: Trait> WeighData<ScheduleArgs<'_, T>> for ScheduleWeight<T> {
+    fn weigh_data(&self, (call, at, _, _, _): ScheduleArgs<'_, T>) -> Weight {
+        let queued = Module::<T>::queued_at(*at);
+        T::WeightInfo::schedule_task(queued).saturating_add(call.get_dispatch_info().weight)
+    }
+}
+
+/// The arguments of `schedule_after`: call and delay.
+type DelayedArgs<'a, T> = (
+    &'a Box<<T as Trait>::Call>,
+    &'a <T as system::Trait>::BlockNumber,
+);
+
+impl<T: Trait> WeighData<DelayedArgs<'_, T>> for DelayedScheduleWeight<T> {
+    fn weigh_data(&self, (call, delay): DelayedArgs<'_, T>) -> Weight {
+        let at = <system::Module<T>>::block_number().saturating_add(*delay);
+        let queued = Module::<T>::queued_at(at);
+        T::WeightInfo::schedule_task(queued).saturating_add(call.get_dispatch_info().weight)
+    }
+}
+
+/// The arguments of `schedule_with_tip`: call, block, priority, tip and deadline.
+type TippedArgs<'a, T> = (
+    &'a Box<<T as Trait>::Call>,
+    &'a <T as system::Trait>::BlockNumber,
+    &'a u8,
+    &'a BalanceOf<T>,
+    &'a Option<<T as system::Trait>::BlockNumber>,
+);
+
+impl<T: Trait> WeighData<TippedArgs<'_, T>> for TippedScheduleWeight<T> {
+    fn weigh_data(&self, (call, at, _, _, _): TippedArgs<'_, T>) -> Weight {
+        let queued = Module::<T>::queued_at(*at);
+        T::WeightInfo::schedule_task(queued).saturating_add(call.get_dispatch_info().weight)
+    }
+}
+
+/// The arguments of `schedule_hash`: call hash, block, priority and deadline.
+type ScheduleHashArgs<'a, T> = (
+    &'a <T as system::Trait>::Hash,
+    &'a <T as system::Trait>::BlockNumber,
+    &'a u8,
+    &'a Option<<T as system::Trait>::BlockNumber>,
+);
+
+impl<T: Trait> WeighData<ScheduleHashArgs<'_, T>> for ScheduleHashWeight<T> {
+    fn weigh_data(&self, (_, at, _, _): ScheduleHashArgs<'_, T>) -> Weight {
+        T::WeightInfo::schedule_task(Module::<T>::queued_at(*at))
+    }
+}
+
+impl<T: Trait> WeighData<(&Vec<u8>,)> for NotePreimageWeight<T> {
+    fn weigh_data(&self, (encoded_call,): (&Vec<u8>,)) -> Weight {
+        let call = <T as Trait>::Call::decode(&mut &encoded_call[..]);
+        let weight = call.map_or(0, |call| call.get_dispatch_info().weight);
+        T::WeightInfo::schedule_task(0).saturating_add(weight)
+    }
+}
+
+/// The arguments of `schedule_periodic`: call, start, period, count, priority and deadline.
+type PeriodicArgs<'a, T> = (
+    &'a Box<<T as Trait>::Call>,
+    &'a <T as system::Trait>::BlockNumber,
+    &'a <T as system::Trait>::BlockNumber,
+    &'a u32,
+    &'a u8,
+    &'a Option<<T as system::Trait>::BlockNumber>,
+);
+
+impl<T: Trait> WeighData<PeriodicArgs<'_, T>> for PeriodicScheduleWeight<T> {
+    fn weigh_data(&self, (call, start, _, count, _, _): PeriodicArgs<'_, T>) -> Weight {
+        let queued = Module::<T>::queued_at(*start);
+        let runs = call.get_dispatch_info().weight.saturating_mul(*count);
+        T::WeightInfo::schedule_task(queued).saturating_add(runs)
+    }
+}
+
+/// The arguments of `schedule_after_task`: call, task to wait for, priority and deadline.
+type ScheduleAfterTaskArgs<'a, T> = (
+    &'a Box<<T as Trait>::Call>,
+    &'a <T as system::Trait>::Hash,
+    &'a u8,
+    &'a Option<<T as system::Trait>::BlockNumber>,
+);
+
+impl<T: Trait> WeighData<ScheduleAfterTaskArgs<'_, T>> for ScheduleAfterTaskWeight<T> {
+    fn weigh_data(&self, (call, _, _, _): ScheduleAfterTaskArgs<'_, T>) -> Weight {
+        T::WeightInfo::schedule_task(0).saturating_add(call.get_dispatch_info().weight)
+    }
+}
+
+/// The arguments of `schedule_batch`: calls with their blocks, and priority.
+type BatchArgs<'a, T> = (
+    &'a Vec<(<T as Trait>::Call, <T as system::Trait>::BlockNumber)>,
+    &'a u8,
+);
+
+impl<T: Trait> WeighData<BatchArgs<'_, T>> for BatchScheduleWeight<T> {
+    fn weigh_data(&self, (tasks, _): BatchArgs<'_, T>) -> Weight {
+        tasks.iter().fold(0, |weight: Weight, (call, at)| {
+            let queued = Module::<T>::queued_at(*at);
+            weight
+                .saturating_add(T::WeightInfo::schedule_task(queued))
+                .saturating_add(call.get_dispatch_info().weight)
+        })
+    }
+}
+
+impl<T: Trait> WeighData<(&DelegatedTaskOf<T>, &T::Signature)> for DelegatedScheduleWeight<T> {
+    fn weigh_data(&self, (payload, _): (&DelegatedTaskOf<T>, &T::Signature)) -> Weight {
+        let queued = Module::<T>::queued_at(payload.block_number);
+        T::WeightInfo::schedule_task(queued).saturating_add(payload.call.get_dispatch_info().weight)
+    }
+}
+
+/// The arguments of `schedule_as`: origin to dispatch with, call, block and priority.
+type ScheduleAsArgs<'a, T> = (
+    &'a DispatchAs<<T as system::Trait>::AccountId>,
+    &'a Box<<T as Trait>::Call>,
+    &'a <T as system::Trait>::BlockNumber,
+    &'a u8,
+);
+
+impl<T: Trait> WeighData<ScheduleAsArgs<'_, T>> for ScheduleAsWeight<T> {
+    fn weigh_data(&self, (_, call, at, _): ScheduleAsArgs<'_, T>) -> Weight {
+        let queued = Module::<T>::queued_at(*at);
+        T::WeightInfo::schedule_task(queued).saturating_add(call.get_dispatch_info().weight)
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for ScheduleWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for DelayedScheduleWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for TippedScheduleWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for ScheduleHashWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for NotePreimageWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for PeriodicScheduleWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for ScheduleAfterTaskWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for BatchScheduleWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for DelegatedScheduleWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for ScheduleAsWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
@@ -69,6 +69,13 @@ macro_rules! new_full_start {
 				tasks_to_spawn = Some(vec![Box::new(pruning_task)]);
 
 				Ok(import_queue)
+			})?
+			.with_rpc_extensions(|client, _pool| {
+				use scheduler_rpc::{Scheduler, SchedulerApi};
+
+				let mut io = jsonrpc_core::IoHandler::default();
+				io.extend_with(SchedulerApi::to_delegate(Scheduler::new(client)));
+				io
 			})?;
 
 		(builder, import_setup, inherent_data_providers, tasks_to_spawn)
@@ -0,0 +1,32 @@
+//! Runtime API definition for the trust-fund module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use rstd::prelude::*;
+use trust_fund::{AuditEntry, ClockInStats, TrustStatus};
+
+client::decl_runtime_apis! {
+    /// Read-only queries about trusts, for wallets and monitoring services.
+    pub trait TrustFundApi<AccountId, BlockNumber, Moment, Hash, AssetId, Balance> where
+        AccountId: Codec,
+        BlockNumber: Codec,
+        Moment: Codec,
+        Hash: Codec,
+        AssetId: Codec,
+        Balance: Codec,
+    {
+        /// Overview of `grantor`'s trust.
+        fn trust_status(grantor: AccountId) -> TrustStatus<AccountId, BlockNumber, Moment, Hash>;
+
+        /// Statistics over the recent clock-ins of `grantor`.
+        fn clock_in_stats(grantor: AccountId) -> ClockInStats<BlockNumber>;
+
+        /// What each beneficiary would receive if `grantor`'s `asset_id` holding were
+        /// distributed now.
+        fn quote_distribution(grantor: AccountId, asset_id: AssetId) -> Vec<(AccountId, Balance)>;
+
+        /// The most recent actions taken on `grantor`'s trust, oldest first.
+        fn audit_log(grantor: AccountId) -> Vec<AuditEntry<BlockNumber>>;
+    }
+}
@@ -0,0 +1,129 @@
+//! Benchmarks of the dispatchables `WeightInfo` covers, run against the mock runtime with
+//! `cargo test -p trust-fund --release --features runtime-benchmarks -- --nocapture bench`.
+//!
+//! Each benchmark prints the mean time of the call for growing counts, followed by the fit
+//! `base + slope * n` in nanoseconds. A runtime's `WeightInfo` scales these by how much
+//! weight it gives a nanosecond on its own hardware.
+
+use super::*;
+use crate::mock::*;
+
+use runtime_io::with_externalities;
+use std::time::Instant;
+use support::assert_ok;
+
+/// How often each call is timed, each time on fresh storage.
+const REPEATS: u32 = 20;
+
+/// An account for the `index`th of many beneficiaries, clear of the named mock accounts.
+fn account(index: u32) -> u64 {
+    1_000 + u64::from(index)
+}
+
+fn shares(count: u32) -> Vec<BeneficiaryShare<u64>> {
+    (0..count).map(|index| BeneficiaryShare { address: account(index), weight: 1 }).collect()
+}
+
+/// Mean time in nanoseconds of `call`, run after `setup` on fresh storage.
+fn measure(setup: impl Fn(), call: impl Fn()) -> u128 {
+    let mut total = 0;
+    for _ in 0..REPEATS {
+        with_externalities(&mut new_test_ext(), || {
+            setup();
+            let start = Instant::now();
+            call();
+            total += start.elapsed().as_nanos();
+        });
+    }
+    total / u128::from(REPEATS)
+}
+
+/// Time `call` for each of `counts` and print the linear fit between the smallest and the
+/// largest count.
+fn report(name: &str, counts: &[u32], time: impl Fn(u32) -> u128) {
+    let points: Vec<_> = counts.iter().map(|&count| (count, time(count))).collect();
+    for (count, nanos) in &points {
+        println!("{} n={}: {}ns", name, count, nanos);
+    }
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let slope = last.1.saturating_sub(first.1) / u128::from((last.0 - first.0).max(1));
+    let base = first.1.saturating_sub(slope * u128::from(first.0));
+    println!("{}: {} + {} * n ns", name, base, slope);
+}
+
+/// Issue `count` assets of 1000 to the grantor, numbered from `0`.
+fn issue_assets(count: u32) {
+    for _ in 0..count {
+        assert_ok!(assets::Call::<Test>::issue(1000).dispatch(Origin::signed(GRANTOR)));
+    }
+}
+
+#[test]
+fn bench_deposit() {
+    report("deposit", &[1, 10, 50], |assets| {
+        measure(
+            || {
+                issue_assets(assets);
+                for asset in 1..assets {
+                    assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), asset, 100));
+                }
+            },
+            || assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 100)),
+        )
+    });
+}
+
+#[test]
+fn bench_set_beneficiaries() {
+    report("set_beneficiaries", &[1, 10, 50, 100], |count| {
+        measure(
+            || {
+                issue_assets(1);
+                assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 1000));
+                assert_ok!(TrustFund::set_beneficiaries(Origin::signed(GRANTOR), shares(count)));
+            },
+            || {
+                let next: Vec<_> = shares(2 * count).split_off(count as usize);
+                assert_ok!(TrustFund::set_beneficiaries(Origin::signed(GRANTOR), next));
+            },
+        )
+    });
+}
+
+#[test]
+fn bench_clock_in() {
+    report("clock_in", &[1], |_| {
+        measure(
+            || {
+                issue_assets(1);
+                assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 1000));
+                assert_ok!(TrustFund::set_living_switch_condition(
+                    Origin::signed(GRANTOR),
+                    LivingSwitchCond::ClockInInterval(10)
+                ));
+                System::set_block_number(5);
+            },
+            || assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR))),
+        )
+    });
+}
+
+#[test]
+fn bench_withdraw() {
+    report("withdraw", &[1, 10, 50], |count| {
+        measure(
+            || {
+                let issue = assets::Call::<Test>::issue(1_000_000);
+                assert_ok!(issue.dispatch(Origin::signed(GRANTOR)));
+                assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 1_000_000));
+                assert_ok!(TrustFund::set_beneficiaries(Origin::signed(GRANTOR), shares(count)));
+                assert_ok!(TrustFund::set_living_switch_condition(
+                    Origin::signed(GRANTOR),
+                    LivingSwitchCond::BlockHeight(0)
+                ));
+                System::set_block_number(1);
+            },
+            || assert_ok!(TrustFund::withdraw(Origin::signed(account(0)), GRANTOR, 0)),
+        )
+    });
+}
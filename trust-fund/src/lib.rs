@@ -17,20 +17,59 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use app_crypto::{KeyTypeId, RuntimeAppPublic};
 use codec::{Compact, CompactAs, Decode, Encode};
 use rstd::{marker::PhantomData, prelude::*};
 use sr_primitives::{
-    traits::{Bounded, Convert, One, SignedExtension, Zero},
+    traits::{
+        AccountIdConversion, Bounded, CheckedAdd, Convert, Dispatchable, Hash, Member, One,
+        Saturating, SignedExtension, UniqueSaturatedInto, Verify, Zero,
+    },
     transaction_validity::{
         InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
     },
     weights::{DispatchInfo, SimpleDispatchInfo},
-    Perbill,
+    ModuleId, Perbill,
 };
 use support::{
-    decl_event, decl_module, decl_storage, dispatch::Result, ensure, StorageMap, StorageValue,
+    decl_event, decl_module, decl_storage,
+    dispatch::Result,
+    ensure,
+    traits::{Currency, Get, OnFreeBalanceZero, OnUnbalanced, ReservableCurrency},
+    unsigned::ValidateUnsigned,
+    Parameter, StorageDoubleMap, StorageMap, StorageValue,
 };
-use system::{ensure_root, ensure_signed};
+use system::{ensure_none, ensure_root, ensure_signed, offchain::SubmitUnsignedTransaction};
+
+/// Account holding the funds of every trust. `TrustBalances` records what belongs to whom.
+const MODULE_ID: ModuleId = ModuleId(*b"py/trust");
+
+/// Key type of the keys a grantor's node signs heartbeat clock-ins with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"trst");
+
+pub mod crypto {
+    pub mod sr25519 {
+        mod app_sr25519 {
+            use app_crypto::{app_crypto, sr25519};
+            app_crypto!(sr25519, crate::KEY_TYPE);
+        }
+
+        /// A heartbeat key using sr25519 as its crypto.
+        pub type HeartbeatId = app_sr25519::Public;
+
+        /// A heartbeat signature using sr25519 as its crypto.
+        pub type HeartbeatSignature = app_sr25519::Signature;
+
+        /// A heartbeat key pair using sr25519 as its crypto.
+        #[cfg(feature = "std")]
+        pub type HeartbeatPair = app_sr25519::Pair;
+    }
+}
+
+pub mod weights;
+
+pub use weights::WeightInfo;
+use weights::{SetBeneficiariesWeight, WithdrawWeight};
 
 /// Our module's configuration trait. All our types and consts go in here. If the
 /// module is dependent on specific other modules, then their configuration traits
@@ -44,6 +83,238 @@ pub trait Trait: balances::Trait + assets::Trait + timestamp::Trait + system::Tr
     //    type ConvertBalance: Convert<BalanceOf<Self>, u128> + Convert<u128, BalanceOf<Self>>;
     type BalanceToU128: From<BalanceOf<Self>> + Into<u128>;
     type U128ToBalance: From<u128> + Into<BalanceOf<Self>>;
+
+    /// What to do with trust funds while the living switch has not fired yet.
+    type OnIdleFunds: OnIdleFunds<Self::AccountId, Self::AssetId, BalanceOf<Self>>;
+
+    /// The smallest asset balance a payout may leave behind or create.
+    type AssetExistentialDeposit: Get<BalanceOf<Self>>;
+
+    /// Registry of unique items that grantors can bequeath to specific beneficiaries.
+    type UniqueAssets: UniqueAssets<Self::AccountId>;
+
+    /// The currency in which deposits are held.
+    type Currency: ReservableCurrency<Self::AccountId>;
+
+    /// Deposit required to trigger a trust under `TriggerPolicy::AnyoneWithDeposit`.
+    type TriggerDeposit: Get<NativeBalanceOf<Self>>;
+
+    /// Handler for slashed deposits.
+    type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+    /// How many clock-ins are kept in each grantor's history.
+    type ClockInHistoryLength: Get<u32>;
+
+    /// Maximum combined length in bytes of a trust's name and jurisdiction tag.
+    type MaxMetadataLength: Get<u32>;
+
+    /// How many queued automatic distributions are performed at the start of each block.
+    type AutoDistributionsPerBlock: Get<u32>;
+
+    /// Key a grantor's node signs heartbeat clock-ins with.
+    type HeartbeatId: Member + Parameter + RuntimeAppPublic + Default;
+
+    /// A dispatchable call type.
+    type Call: From<Call<Self>>;
+
+    /// A transaction submitter for heartbeat clock-ins.
+    type SubmitTransaction: SubmitUnsignedTransaction<Self, <Self as Trait>::Call>;
+
+    /// How many blocks a node waits after the last clock-in before sending a heartbeat.
+    type HeartbeatInterval: Get<Self::BlockNumber>;
+
+    /// Registry that tells whether a beneficiary's identity has been verified.
+    type IdentityVerifier: IdentityVerifier<Self::AccountId>;
+
+    /// How many blocks a share held for an unverified beneficiary waits before it may be
+    /// released to the trust's fallback account.
+    type UnclaimedTimeout: Get<Self::BlockNumber>;
+
+    /// The smallest holding of an asset a trust may be funded with.
+    type MinTrustDeposit: Get<BalanceOf<Self>>;
+
+    /// How many entries are kept in each trust's audit log.
+    type AuditLogLength: Get<u32>;
+
+    /// Signature of an account, for clocking in without a signed transaction.
+    type Signature: Member + Parameter + Verify<Signer = Self::AccountId>;
+
+    /// How many blocks a grantor must wait between two unsigned clock-ins.
+    type UnsignedClockInPeriod: Get<Self::BlockNumber>;
+
+    /// How many blocks a guardian stays a guardian without confirming they are still active.
+    type GuardianActivityPeriod: Get<Self::BlockNumber>;
+
+    /// Hook run on every payout, e.g. to levy estate tax.
+    type OnDistribution: OnDistribution<Self::AccountId, Self::AssetId, BalanceOf<Self>>;
+
+    /// Deposit a beneficiary reserves when disputing a triggered trust's split.
+    type DisputeDeposit: Get<NativeBalanceOf<Self>>;
+
+    /// How many blocks a dispute freezes distribution for at most.
+    type DisputeWindow: Get<Self::BlockNumber>;
+
+    /// How many blocks must pass between two changes to a trust's beneficiaries or conditions.
+    type ConfigChangeCooldown: Get<Self::BlockNumber>;
+
+    /// Exchange that swaps payouts into the asset a beneficiary prefers.
+    type AssetConverter: AssetConverter<Self::AccountId, Self::AssetId, BalanceOf<Self>>;
+
+    /// Deposit a guardian reserves when attesting. It is slashed if the grantor clocks in
+    /// before the trust triggers, and refunded otherwise.
+    type AttestationDeposit: Get<NativeBalanceOf<Self>>;
+
+    /// Deposit reserved from the grantor for every beneficiary entry their trust stores, in
+    /// its plain set, its tiers and its per-asset overrides. Refunded when the entry goes or
+    /// the trust is closed.
+    type BeneficiaryDeposit: Get<NativeBalanceOf<Self>>;
+
+    /// Deposit reserved from the grantor for every asset their trust holds. Refunded when the
+    /// trust runs out of the asset or is closed.
+    type AssetDeposit: Get<NativeBalanceOf<Self>>;
+
+    /// Weights of the dispatchables whose cost depends on the size of a trust.
+    type WeightInfo: WeightInfo;
+}
+
+/// Handler that can put the funds of a dormant trust to work, e.g. by bonding them with a
+/// staking system, until the living switch fires.
+pub trait OnIdleFunds<AccountId, AssetId, Balance> {
+    /// Bond `amount` of asset `asset_id` held in the trust of `grantor`.
+    fn bond(grantor: &AccountId, asset_id: AssetId, amount: Balance) -> Result;
+
+    /// Unbond everything bonded for asset `asset_id` of `grantor`'s trust. Any rewards earned
+    /// in the meantime must be paid back to the trust, so they accrue to the beneficiaries.
+    fn unbond(grantor: &AccountId, asset_id: AssetId) -> Result;
+}
+
+impl<AccountId, AssetId, Balance> OnIdleFunds<AccountId, AssetId, Balance> for () {
+    fn bond(_: &AccountId, _: AssetId, _: Balance) -> Result {
+        Err("idle funds bonding is not supported")
+    }
+
+    fn unbond(_: &AccountId, _: AssetId) -> Result {
+        Ok(())
+    }
+}
+
+/// Hook run on every payout a trust distributes, so that e.g. estate tax can be modelled
+/// without changing this module.
+pub trait OnDistribution<AccountId, AssetId, Balance> {
+    /// The part of the `amount` of asset `asset_id` that `grantor`'s trust is about to pay
+    /// `beneficiary` that goes elsewhere instead, and where it goes. Also used to quote
+    /// distributions, so it must not change any state.
+    fn on_distribution(
+        grantor: &AccountId,
+        beneficiary: &AccountId,
+        asset_id: AssetId,
+        amount: Balance,
+    ) -> Option<(AccountId, Balance)>;
+}
+
+impl<AccountId, AssetId, Balance> OnDistribution<AccountId, AssetId, Balance> for () {
+    fn on_distribution(
+        _: &AccountId,
+        _: &AccountId,
+        _: AssetId,
+        _: Balance,
+    ) -> Option<(AccountId, Balance)> {
+        None
+    }
+}
+
+/// `OnDistribution` that diverts `EstateTaxRate` of every payout to `EstateTaxAccount`, both
+/// set with `set_estate_tax`.
+pub struct EstateTax<T>(PhantomData<T>);
+
+impl<T: Trait> OnDistribution<T::AccountId, T::AssetId, BalanceOf<T>> for EstateTax<T> {
+    fn on_distribution(
+        _: &T::AccountId,
+        _: &T::AccountId,
+        _: T::AssetId,
+        amount: BalanceOf<T>,
+    ) -> Option<(T::AccountId, BalanceOf<T>)> {
+        let account = <Module<T>>::estate_tax_account()?;
+        Some((account, <Module<T>>::portion(<Module<T>>::estate_tax_rate(), amount)))
+    }
+}
+
+/// An exchange, e.g. a DEX, that can swap one asset for another.
+pub trait AssetConverter<AccountId, AssetId, Balance> {
+    /// How much of `to` `amount` of `from` would currently fetch, if they can be exchanged.
+    fn quote(from: AssetId, to: AssetId, amount: Balance) -> Option<Balance>;
+
+    /// Swap `amount` of `from` held by `who` for at least `min_out` of `to`, returning how
+    /// much of `to` `who` received. Must not change any state if it fails.
+    fn convert(
+        who: &AccountId,
+        from: AssetId,
+        to: AssetId,
+        amount: Balance,
+        min_out: Balance,
+    ) -> rstd::result::Result<Balance, &'static str>;
+}
+
+impl<AccountId, AssetId, Balance> AssetConverter<AccountId, AssetId, Balance> for () {
+    fn quote(_: AssetId, _: AssetId, _: Balance) -> Option<Balance> {
+        None
+    }
+
+    fn convert(
+        _: &AccountId,
+        _: AssetId,
+        _: AssetId,
+        _: Balance,
+        _: Balance,
+    ) -> rstd::result::Result<Balance, &'static str> {
+        Err("asset conversion is not supported")
+    }
+}
+
+/// A registry of unique (non-fungible) items, each identified by a `(class, instance)` pair.
+pub trait UniqueAssets<AccountId> {
+    /// Identifier of a class of items.
+    type ClassId: Parameter + Copy;
+    /// Identifier of an item within its class.
+    type InstanceId: Parameter + Copy;
+
+    /// The current owner of an item, if it exists.
+    fn owner(class: &Self::ClassId, instance: &Self::InstanceId) -> Option<AccountId>;
+
+    /// Check that `transfer` of an item to `dest` would go through.
+    fn can_transfer(class: &Self::ClassId, instance: &Self::InstanceId, dest: &AccountId) -> Result;
+
+    /// Move an item from its current owner to `dest`.
+    fn transfer(class: &Self::ClassId, instance: &Self::InstanceId, dest: &AccountId) -> Result;
+}
+
+impl<AccountId> UniqueAssets<AccountId> for () {
+    type ClassId = ();
+    type InstanceId = ();
+
+    fn owner(_: &(), _: &()) -> Option<AccountId> {
+        None
+    }
+
+    fn can_transfer(_: &(), _: &(), _: &AccountId) -> Result {
+        Err("unique assets are not supported")
+    }
+
+    fn transfer(_: &(), _: &(), _: &AccountId) -> Result {
+        Err("unique assets are not supported")
+    }
+}
+
+/// An identity registry, e.g. one recording the judgements of KYC registrars.
+pub trait IdentityVerifier<AccountId> {
+    /// Whether `who` has a verified identity.
+    fn is_verified(who: &AccountId) -> bool;
+}
+
+impl<AccountId> IdentityVerifier<AccountId> for () {
+    fn is_verified(_: &AccountId) -> bool {
+        false
+    }
 }
 
 #[derive(Encode, Decode, Clone, Eq, PartialEq)]
@@ -56,10 +327,17 @@ pub struct BeneficiaryShare<AccountId> {
 #[derive(Encode, Decode, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub enum LivingSwitchCond<BlockNumber, Moment> {
+    /// The switch never fires.
     None,
+    /// Fires once the chain has passed the given block height.
     BlockHeight(BlockNumber),
+    /// Fires once the chain time has passed the given moment.
     Timestamp(Moment),
+    /// Fires when the grantor has not clocked in for more than the given number of blocks.
     ClockInInterval(BlockNumber),
+    /// Fires when the grantor has not clocked in for more than the given number of
+    /// milliseconds.
+    Duration(Moment),
 }
 
 impl<BlockNumber, Moment> Default for LivingSwitchCond<BlockNumber, Moment> {
@@ -68,6 +346,286 @@ impl<BlockNumber, Moment> Default for LivingSwitchCond<BlockNumber, Moment> {
     }
 }
 
+/// An account that may pay bills from a trust while its grantor is incapacitated, without
+/// the trust being distributed.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct PowerOfAttorney<AccountId, Balance, BlockNumber, Moment> {
+    /// The account acting for the grantor.
+    pub attorney: AccountId,
+    /// The condition that must hold, or `None` to rely on attestations alone.
+    pub condition: LivingSwitchCond<BlockNumber, Moment>,
+    /// How many of the grantor's guardians must have attested.
+    pub required_attestations: u32,
+    /// The most the attorney may pay out of each asset per period.
+    pub cap: Balance,
+    /// Length of a period in blocks.
+    pub period: BlockNumber,
+}
+
+/// An account that may draw limited amounts of a minor beneficiary's share on their behalf,
+/// e.g. for school fees, until the beneficiary may claim it themself.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Custodianship<AccountId, Balance, BlockNumber> {
+    /// The account acting for the beneficiary.
+    pub custodian: AccountId,
+    /// Block from which the beneficiary claims their share themself.
+    pub eligible_at: BlockNumber,
+    /// The most the custodian may draw of each asset per period.
+    pub cap: Balance,
+    /// Length of a period in blocks.
+    pub period: BlockNumber,
+}
+
+/// A beneficiary's objection to how a triggered trust is about to be split.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Dispute<AccountId, Balance, BlockNumber> {
+    /// The beneficiary that raised the dispute.
+    pub disputer: AccountId,
+    /// The deposit they reserved.
+    pub deposit: Balance,
+    /// Block from which distribution resumes if the dispute has not been resolved.
+    pub expires_at: BlockNumber,
+}
+
+/// A common trust configuration that grantors can start from instead of configuring every
+/// part themselves.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct TrustTemplate<BlockNumber, Moment> {
+    /// Weight of each beneficiary, in the order their accounts are given. When empty, the
+    /// trust is split equally between however many accounts are given.
+    pub weights: Vec<u64>,
+    /// The condition the trust is created with.
+    pub condition: LivingSwitchCond<BlockNumber, Moment>,
+}
+
+/// A condition together with the beneficiaries it pays out to. A trust's tiers are evaluated
+/// in priority order and the first one that fires decides the distribution.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Tier<AccountId, BlockNumber, Moment> {
+    /// The condition that must hold, or `None` to rely on attestations alone.
+    pub condition: LivingSwitchCond<BlockNumber, Moment>,
+    /// How many of the grantor's guardians must have attested.
+    pub required_attestations: u32,
+    /// The beneficiaries and their weights.
+    pub beneficiaries: Vec<BeneficiaryShare<AccountId>>,
+}
+
+/// Lifecycle of a trust. Configuration can only be changed while the trust is `Active`.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TrustState {
+    /// The grantor is alive and may configure the trust.
+    Active,
+    /// The living switch has fired; beneficiaries and conditions are locked.
+    Triggered,
+    /// Payouts to the beneficiaries are in progress.
+    Distributing,
+    /// The trust has been wound up.
+    Closed,
+}
+
+impl Default for TrustState {
+    fn default() -> Self {
+        TrustState::Active
+    }
+}
+
+/// Who may trigger the distribution of a trust.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TriggerPolicy {
+    /// Only accounts listed as beneficiaries.
+    Beneficiaries,
+    /// Only the trustee of the trust.
+    Trustee,
+    /// Anyone putting down `TriggerDeposit`, which is refunded if the trigger is valid and
+    /// slashed otherwise.
+    AnyoneWithDeposit,
+}
+
+impl Default for TriggerPolicy {
+    fn default() -> Self {
+        TriggerPolicy::Beneficiaries
+    }
+}
+
+/// A clock-in sent by a grantor's node on their behalf.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Heartbeat<AccountId, BlockNumber> {
+    /// The grantor clocking in.
+    pub grantor: AccountId,
+    /// Must match the grantor's `HeartbeatNonce`, so a heartbeat cannot be replayed.
+    pub nonce: u64,
+    /// Block at which the heartbeat was produced.
+    pub block_number: BlockNumber,
+}
+
+/// Amount of a trust's holding the grantor may take back per period while the trust is
+/// active.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Allowance<Balance, BlockNumber> {
+    /// Amount available per period.
+    pub amount: Balance,
+    /// Length of a period in blocks.
+    pub period: BlockNumber,
+    /// Amount taken in the current period.
+    pub spent: Balance,
+    /// First block of the current period.
+    pub period_start: BlockNumber,
+}
+
+/// How long a trust may stay untriggered before its funds revert.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct TrustExpiry<AccountId, BlockNumber> {
+    /// Number of blocks the trust lives for after being set up or renewed.
+    pub duration: BlockNumber,
+    /// Block at which the trust expires.
+    pub expires_at: BlockNumber,
+    /// Account the funds revert to, or the grantor if `None`.
+    pub reversion: Option<AccountId>,
+}
+
+/// A deposit the module moves from a grantor's account into their trust at regular intervals.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct TopUp<Balance, BlockNumber> {
+    /// Amount deposited each time.
+    pub amount: Balance,
+    /// Number of blocks between deposits.
+    pub interval: BlockNumber,
+    /// Block of the next deposit.
+    pub next: BlockNumber,
+}
+
+/// How a triggered trust hands its funds to the beneficiaries.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum PayoutMode<Balance> {
+    /// Every beneficiary receives their full share at once.
+    LumpSum,
+    /// Every beneficiary accrues up to the given amount per block, which they collect with
+    /// `claim_share`.
+    Streamed(Balance),
+}
+
+impl<Balance> Default for PayoutMode<Balance> {
+    fn default() -> Self {
+        PayoutMode::LumpSum
+    }
+}
+
+/// A change to a trust's beneficiaries or conditions. While the grantor has a change delay,
+/// changes only take effect once the delay has passed.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TrustChange<AccountId, AssetId, BlockNumber, Moment> {
+    /// See `set_beneficiaries`.
+    SetBeneficiaries(Vec<BeneficiaryShare<AccountId>>),
+    /// See `set_beneficiaries_percent`.
+    SetBeneficiariesPercent(Vec<(AccountId, Perbill)>),
+    /// See `add_beneficiary`.
+    AddBeneficiary(AccountId, u64),
+    /// See `remove_beneficiary`.
+    RemoveBeneficiary(AccountId),
+    /// See `update_beneficiary_weight`.
+    UpdateBeneficiaryWeight(AccountId, u64),
+    /// See `clear_beneficiaries`.
+    ClearBeneficiaries,
+    /// See `set_living_switch_condition`.
+    SetLivingSwitchCondition(LivingSwitchCond<BlockNumber, Moment>),
+    /// See `reset_living_switch_condition`.
+    ResetLivingSwitchCondition,
+    /// See `set_tiers`.
+    SetTiers(Vec<Tier<AccountId, BlockNumber, Moment>>),
+    /// See `create_trust_from_template`.
+    FromTemplate(u32, Vec<AccountId>),
+    /// See `set_change_delay`.
+    SetChangeDelay(BlockNumber),
+    /// See `set_asset_beneficiaries`.
+    SetAssetBeneficiaries(AssetId, Option<Vec<BeneficiaryShare<AccountId>>>),
+    /// See `set_trust_expiry`.
+    SetExpiry(Option<BlockNumber>, Option<AccountId>),
+    /// See `set_guardian_reward`.
+    SetGuardianReward(Perbill),
+    /// See `clear_clock_in_history`.
+    ClearClockInHistory,
+}
+
+impl<AccountId, AssetId, BlockNumber, Moment> TrustChange<AccountId, AssetId, BlockNumber, Moment> {
+    /// Whether the change touches the beneficiaries or conditions, and so may only be made
+    /// once per `ConfigChangeCooldown`.
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            TrustChange::SetChangeDelay(_)
+            | TrustChange::SetExpiry(..)
+            | TrustChange::SetGuardianReward(_) => false,
+            _ => true,
+        }
+    }
+}
+
+/// Kind of action recorded in a trust's audit log.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum AuditAction {
+    /// The grantor or trustee changed the trust's configuration.
+    Configured,
+    /// Funds were deposited into the trust.
+    Deposited,
+    /// The grantor clocked in.
+    ClockedIn,
+    /// A guardian attested that the grantor's condition holds.
+    Attested,
+    /// The grantor took funds out of their allowance.
+    AllowanceSpent,
+    /// The trust was taken over by a new grantor account.
+    OwnershipTransferred,
+    /// The trust was triggered.
+    Triggered,
+    /// A holding of the trust was distributed.
+    Distributed,
+    /// A beneficiary collected a streamed or held share.
+    Claimed,
+    /// The trust's attorney paid a bill from it.
+    AttorneyPaid,
+    /// A beneficiary renounced their share.
+    Disclaimed,
+    /// The grantor or trustee changed where a beneficiary's share may be sent.
+    WhitelistChanged,
+    /// A custodian drew part of a beneficiary's share.
+    CustodianClaimed,
+}
+
+/// An action recorded in a trust's audit log.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct AuditEntry<BlockNumber> {
+    /// Block in which the action happened.
+    pub block: BlockNumber,
+    /// What happened.
+    pub action: AuditAction,
+}
+
+/// The part of a streamed share a beneficiary has not claimed yet.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Stream<Balance, BlockNumber> {
+    /// Amount still owed to the beneficiary.
+    pub remaining: Balance,
+    /// Amount that accrues per block.
+    pub rate: Balance,
+    /// Block from which the unclaimed amount accrues.
+    pub since: BlockNumber,
+}
+
 #[derive(Encode, Decode, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 struct SchedulePayment<AssetId, AccountId, Balance> {
@@ -76,13 +634,337 @@ struct SchedulePayment<AssetId, AccountId, Balance> {
     amount: Balance,
 }
 
+/// Descriptive information about a trust, for explorers and heir-facing applications.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct TrustMetadata<Hash> {
+    /// Display name of the trust.
+    pub name: Vec<u8>,
+    /// Tag of the jurisdiction the trust is set up under.
+    pub jurisdiction: Vec<u8>,
+    /// Hash of the grantor's contact information, which is shared off-chain.
+    pub contact_hash: Hash,
+}
+
+/// Overview of a trust, as reported by the runtime API.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct TrustStatus<AccountId, BlockNumber, Moment, Hash> {
+    /// Where the trust is in its lifecycle.
+    pub state: TrustState,
+    /// The condition that fires the living switch.
+    pub condition: LivingSwitchCond<BlockNumber, Moment>,
+    /// The beneficiaries and their weights.
+    pub beneficiaries: Vec<BeneficiaryShare<AccountId>>,
+    /// The trustee, if any.
+    pub trustee: Option<AccountId>,
+    /// Block of the grantor's last clock-in.
+    pub last_clock_in: BlockNumber,
+    /// Descriptive information set by the grantor.
+    pub metadata: Option<TrustMetadata<Hash>>,
+}
+
+/// Summary of a grantor's recent clock-ins, used to spot worrying patterns before the
+/// living switch fires.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ClockInStats<BlockNumber> {
+    /// Number of clock-ins in the history.
+    pub count: u32,
+    /// Average number of blocks between consecutive clock-ins.
+    pub average_interval: BlockNumber,
+    /// Longest gap in blocks between consecutive clock-ins, including the time since the
+    /// latest one.
+    pub longest_gap: BlockNumber,
+}
+
+/// Errors raised while distributing a trust.
+#[derive(Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Error {
+    /// The trust holds none of the asset.
+    NoBalance,
+    /// The trust has no beneficiaries.
+    NoBeneficiaries,
+    /// The beneficiaries' weights add up to zero.
+    ZeroWeight,
+    /// The distributable amount is below the existential deposit.
+    BelowExistentialDeposit,
+    /// A payout of zero was requested.
+    ZeroPayout,
+    /// The grantor's balance does not cover all payouts.
+    InsufficientBalance,
+    /// Moving funds to a beneficiary failed.
+    TransferFailed,
+}
+
+impl Error {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Error::NoBalance => "no balance",
+            Error::NoBeneficiaries => "no beneficiaries",
+            Error::ZeroWeight => "beneficiary weights add up to zero",
+            Error::BelowExistentialDeposit => "distributable amount is below existential deposit",
+            Error::ZeroPayout => "payout amount should be non-zero",
+            Error::InsufficientBalance => "trust balance does not cover the payouts",
+            Error::TransferFailed => "transfer to beneficiary failed",
+        }
+    }
+}
+
+impl From<Error> for &'static str {
+    fn from(err: Error) -> &'static str {
+        err.as_str()
+    }
+}
+
 type BalanceOf<T> = <T as assets::Trait>::Balance;
+type NativeBalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+type ClassIdOf<T> =
+    <<T as Trait>::UniqueAssets as UniqueAssets<<T as system::Trait>::AccountId>>::ClassId;
+type InstanceIdOf<T> =
+    <<T as Trait>::UniqueAssets as UniqueAssets<<T as system::Trait>::AccountId>>::InstanceId;
+type TrustChangeOf<T> = TrustChange<
+    <T as system::Trait>::AccountId,
+    <T as assets::Trait>::AssetId,
+    <T as system::Trait>::BlockNumber,
+    <T as timestamp::Trait>::Moment,
+>;
 
 decl_storage! {
     trait Store for Module<T: Trait> as TrustFund {
-        Beneficiaries get(beneficiaries): map T::AccountId => Vec<BeneficiaryShare<T::AccountId>>;
+        /// Position and weight of each beneficiary of each grantor's trust.
+        BeneficiaryShares get(beneficiary_share):
+            double_map T::AccountId, blake2_256(T::AccountId) => Option<(u32, u64)>;
+        /// Beneficiary at each position of each grantor's trust, from zero up to
+        /// `BeneficiaryCount`.
+        BeneficiaryAt get(beneficiary_at): double_map T::AccountId, blake2_256(u32) => Option<T::AccountId>;
+        /// How many beneficiaries each grantor's trust has.
+        BeneficiaryCount get(beneficiary_count): map T::AccountId => u32;
+        /// Sum of the weights of each grantor's beneficiaries.
+        TotalBeneficiaryWeight get(total_beneficiary_weight): map T::AccountId => u64;
+        /// Weight that stands for the whole trust, for shares set as percentages. Zero when
+        /// shares are plain weights.
+        ShareDenominator get(share_denominator): map T::AccountId => u64;
+        /// Conditional beneficiary sets of each grantor's trust, highest priority first. When
+        /// set, they take the place of the plain condition and beneficiaries.
+        Tiers get(tiers): map T::AccountId => Vec<Tier<T::AccountId, T::BlockNumber, T::Moment>>;
+        /// The tier that fired when each grantor's trust was triggered.
+        ActiveTier get(active_tier): map T::AccountId => Option<u32>;
+        /// Accounts that may attest to each grantor's death or incapacity.
+        Guardians get(guardians): map T::AccountId => Vec<T::AccountId>;
+        /// Accounts that must co-sign each grantor's beneficiaries and conditions before the
+        /// trust can be triggered.
+        Witnesses get(witnesses): map T::AccountId => Vec<T::AccountId>;
+        /// The configuration hash each witness last co-signed, and when.
+        WitnessConfirmations get(witness_confirmations):
+            map T::AccountId => Vec<(T::AccountId, T::Hash, T::BlockNumber)>;
+        /// Accounts, e.g. a lawyer or accountant, told when each grantor's trust triggers and
+        /// distributes, without a share of it.
+        Observers get(observers): map T::AccountId => Vec<T::AccountId>;
+        /// Accounts invited to become each grantor's guardians that have not accepted yet.
+        PendingGuardians get(pending_guardians): map T::AccountId => Vec<T::AccountId>;
+        /// The block at which each guardian last confirmed they are active, per grantor.
+        GuardianActivity get(guardian_activity):
+            double_map T::AccountId, blake2_256(T::AccountId) => T::BlockNumber;
+        /// Guardians that have attested since the grantor's last clock-in.
+        GuardianAttestations get(guardian_attestations): map T::AccountId => Vec<T::AccountId>;
+        /// The cut of each holding paid to every guardian whose attestation triggered the trust.
+        GuardianRewards get(guardian_reward): map T::AccountId => Perbill;
+        /// The deposit each attesting guardian has reserved, per grantor.
+        AttestationDeposits get(attestation_deposit):
+            double_map T::AccountId, blake2_256(T::AccountId) => NativeBalanceOf<T>;
+        /// The storage deposit each grantor has reserved for the beneficiaries and assets of
+        /// their trust.
+        StorageDeposits get(storage_deposit): map T::AccountId => NativeBalanceOf<T>;
+        /// The grantors whose trusts name a given account as beneficiary.
+        TrustsByBeneficiary get(trusts_by_beneficiary): map T::AccountId => Vec<T::AccountId>;
+        /// The asset each beneficiary would rather be paid in, with the most slippage they accept
+        /// against the quoted price.
+        PreferredAssets get(preferred_asset): map T::AccountId => Option<(T::AssetId, Perbill)>;
+        /// The custodian of each minor beneficiary, keyed by grantor and beneficiary.
+        Custodians get(custodianship): double_map T::AccountId, blake2_256(T::AccountId)
+            => Option<Custodianship<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+        /// What each custodian has drawn in the current period as (period start, amount), keyed
+        /// by (grantor, asset_id, beneficiary).
+        CustodianSpending get(custodian_spending):
+            map (T::AccountId, T::AssetId, T::AccountId) => (T::BlockNumber, BalanceOf<T>);
+        /// Beneficiaries whose shares are deposited into their own trust rather than paid to
+        /// them, keyed by grantor and beneficiary.
+        PaysIntoTrust get(pays_into_trust):
+            double_map T::AccountId, blake2_256(T::AccountId) => bool;
+        /// Beneficiaries whose account was reaped before the trust paid them, keyed by grantor
+        /// and beneficiary. Their shares go to the fallback account.
+        ReapedBeneficiaries get(is_reaped):
+            double_map T::AccountId, blake2_256(T::AccountId) => bool;
         LivingSwitchConds get(living_switch_cond): map T::AccountId => LivingSwitchCond<T::BlockNumber, T::Moment>;
         LastClockIn get(last_clock_in): map T::AccountId => T::BlockNumber;
+        /// The chain time of each grantor's last clock-in.
+        LastClockInMoment get(last_clock_in_moment): map T::AccountId => T::Moment;
+        /// The most recent clock-ins of each grantor as (block number, moment), oldest first.
+        ClockInHistory get(clock_in_history): map T::AccountId => Vec<(T::BlockNumber, T::Moment)>;
+        /// Descriptive information about each grantor's trust.
+        Metadata get(metadata): map T::AccountId => Option<TrustMetadata<T::Hash>>;
+        /// Whether payouts must leave the existential deposit in the trust.
+        KeepAlive get(keep_alive): map T::AccountId => bool;
+        /// Who may trigger each grantor's trust.
+        TriggerPolicies get(trigger_policy): map T::AccountId => TriggerPolicy;
+        /// The trustee administering each grantor's trust, if any.
+        Trustees get(trustee): map T::AccountId => Option<T::AccountId>;
+        /// Cut of every payout that goes to the fee destination.
+        DistributionFee get(distribution_fee) config(): Perbill;
+        /// Account receiving distribution fees. No fee is charged while unset.
+        FeeDestination get(fee_destination): Option<T::AccountId>;
+        /// Cut of every distribution a keeper performs that goes to the keeper.
+        KeeperBounty get(keeper_bounty) config(): Perbill;
+        /// Cut of each payout `EstateTax` diverts to `EstateTaxAccount`.
+        EstateTaxRate get(estate_tax_rate): Perbill;
+        /// Account `EstateTax` pays to. No tax is levied without one.
+        EstateTaxAccount get(estate_tax_account): Option<T::AccountId>;
+        /// Whether all distributions are halted, e.g. while a payout bug is investigated.
+        DistributionsPaused get(distributions_paused): bool;
+        /// The most recent actions taken on each grantor's trust, oldest first.
+        AuditLog get(audit_log): map T::AccountId => Vec<AuditEntry<T::BlockNumber>>;
+        /// Unique items each grantor has assigned, as (class, instance, beneficiary).
+        UniqueAssignments get(unique_assignments):
+            map T::AccountId => Vec<(ClassIdOf<T>, InstanceIdOf<T>, T::AccountId)>;
+        /// Where each grantor's trust is in its lifecycle.
+        TrustStates get(trust_state): map T::AccountId => TrustState;
+        /// Amount of each asset a grantor has bonded through `OnIdleFunds`.
+        BondedFunds get(bonded_funds): map (T::AccountId, T::AssetId) => BalanceOf<T>;
+        /// The most of each asset a single trust may hold. Unlimited when unset.
+        DepositCaps get(deposit_cap): map T::AssetId => Option<BalanceOf<T>>;
+        /// Configurations curated by governance that a trust can be created from.
+        TrustTemplates get(trust_template): map u32 => Option<TrustTemplate<T::BlockNumber, T::Moment>>;
+        /// Amount of each asset held in each grantor's trust.
+        TrustBalances get(trust_balance): double_map T::AccountId, blake2_256(T::AssetId) => BalanceOf<T>;
+        /// The assets each grantor's trust currently holds.
+        TrustAssets get(trust_assets): map T::AccountId => Vec<T::AssetId>;
+        /// How much each account has put into each grantor's trust of an asset, the grantor
+        /// included.
+        Contributions get(contribution):
+            double_map (T::AccountId, T::AssetId), blake2_256(T::AccountId) => BalanceOf<T>;
+        /// The accounts that have put each asset into each grantor's trust.
+        Contributors get(contributors): map (T::AccountId, T::AssetId) => Vec<T::AccountId>;
+        /// What each grantor may take back from their trust's holding of an asset.
+        Allowances get(allowance):
+            map (T::AccountId, T::AssetId) => Option<Allowance<BalanceOf<T>, T::BlockNumber>>;
+        /// Whether each grantor's beneficiaries need a verified identity to receive funds.
+        RequireVerifiedIdentity get(require_verified_identity): map T::AccountId => bool;
+        /// Where each grantor's unclaimed held shares go after `UnclaimedTimeout`.
+        FallbackAccount get(fallback_account): map T::AccountId => Option<T::AccountId>;
+        /// Shares held for unverified beneficiaries as (amount, block held from), keyed by
+        /// (grantor, asset_id, beneficiary). They no longer count towards the trust balance.
+        HeldShares get(held_share):
+            map (T::AccountId, T::AssetId, T::AccountId) => Option<(BalanceOf<T>, T::BlockNumber)>;
+        /// Hash whose preimage a beneficiary must present before their share is paid to them,
+        /// keyed by grantor and beneficiary.
+        ClaimLocks get(claim_lock):
+            double_map T::AccountId, blake2_256(T::AccountId) => Option<T::Hash>;
+        /// The only accounts each beneficiary's share may be sent to, keyed by grantor and
+        /// beneficiary. Empty means the share goes to the beneficiary.
+        PayoutWhitelists get(payout_whitelist):
+            double_map T::AccountId, blake2_256(T::AccountId) => Vec<T::AccountId>;
+        /// The whitelisted account each beneficiary chose to be paid to, keyed by grantor and
+        /// beneficiary.
+        PayoutDestinations get(payout_destination):
+            double_map T::AccountId, blake2_256(T::AccountId) => Option<T::AccountId>;
+        /// How each grantor's trust pays out once triggered.
+        PayoutModes get(payout_mode): map T::AccountId => PayoutMode<BalanceOf<T>>;
+        /// Unclaimed streamed shares, keyed by (grantor, asset_id, beneficiary).
+        Streams get(stream):
+            map (T::AccountId, T::AssetId, T::AccountId) => Option<Stream<BalanceOf<T>, T::BlockNumber>>;
+        /// Number of unfinished streams of each (grantor, asset_id) distribution.
+        OpenStreams get(open_streams): map (T::AccountId, T::AssetId) => u32;
+        /// Active trusts whose condition is checked at the end of every block: those with a
+        /// time-based condition or tiers.
+        ArmedTrusts get(armed_trusts): Vec<T::AccountId>;
+        /// Active trusts with a `BlockHeight` or `ClockInInterval` condition, by the block at
+        /// the end of which the condition first holds.
+        ExpiringAt get(expiring_at): map T::BlockNumber => Vec<T::AccountId>;
+        /// The block under which each trust is listed in `ExpiringAt`.
+        ExpiryBlock get(expiry_block): map T::AccountId => Option<T::BlockNumber>;
+        /// The block in which each grantor's trust was triggered.
+        TriggeredAtBlock get(triggered_at_block): map T::AccountId => Option<T::BlockNumber>;
+        /// The open dispute over each triggered trust's split, if any.
+        Disputes get(dispute):
+            map T::AccountId => Option<Dispute<T::AccountId, NativeBalanceOf<T>, T::BlockNumber>>;
+        /// What each triggered trust held of every asset when it was triggered. Distributions
+        /// pay out these amounts.
+        TriggerSnapshots get(trigger_snapshot): map T::AccountId => Vec<(T::AssetId, BalanceOf<T>)>;
+        /// Whether each grantor's trust distributes itself once triggered.
+        AutoDistribute get(auto_distribute): map T::AccountId => bool;
+        /// Distributions the module still has to perform on its own, as (grantor, asset_id).
+        DistributionQueue get(distribution_queue): Vec<(T::AccountId, T::AssetId)>;
+        /// Distributions of triggered trusts that are still to be performed, as
+        /// (grantor, asset_id). Any account may perform them for the keeper bounty.
+        ClaimableTrusts get(claimable_trusts): Vec<(T::AccountId, T::AssetId)>;
+        /// The key each grantor lets their node clock in with.
+        AuthorizedHeartbeatKey get(authorized_heartbeat_key): map T::AccountId => Option<T::HeartbeatId>;
+        /// The grantor that authorized each heartbeat key.
+        HeartbeatGrantor get(heartbeat_grantor): map T::HeartbeatId => Option<T::AccountId>;
+        /// The account whose only power is to clock in for each grantor, e.g. a phone's key.
+        LivenessKey get(liveness_key): map T::AccountId => Option<T::AccountId>;
+        /// The grantor each liveness key clocks in for.
+        LivenessGrantor get(liveness_grantor): map T::AccountId => Option<T::AccountId>;
+        /// The second key that must confirm each clock-in of a grantor, and within how many
+        /// blocks it must do so.
+        ConfirmationKeys get(confirmation_key):
+            map T::AccountId => Option<(T::AccountId, T::BlockNumber)>;
+        /// The block of each grantor's clock-in awaiting confirmation by their second key.
+        PendingClockIns get(pending_clock_in): map T::AccountId => Option<T::BlockNumber>;
+        /// Nonce the next heartbeat of each grantor must carry.
+        HeartbeatNonce get(heartbeat_nonce): map T::AccountId => u64;
+        /// Nonce the next unsigned clock-in of each grantor must carry.
+        ClockInNonce get(clock_in_nonce): map T::AccountId => u64;
+        /// Block of each grantor's last unsigned clock-in.
+        LastUnsignedClockIn get(last_unsigned_clock_in): map T::AccountId => Option<T::BlockNumber>;
+        /// The account each grantor offered their trust to, pending its confirmation.
+        PendingOwnershipTransfer get(pending_ownership_transfer): map T::AccountId => Option<T::AccountId>;
+        /// How many blocks each grantor's changes to beneficiaries and conditions wait before
+        /// they take effect.
+        ChangeDelays get(change_delay): map T::AccountId => T::BlockNumber;
+        /// The block in which each grantor last asked to change their beneficiaries or
+        /// conditions.
+        LastConfigChange get(last_config_change): map T::AccountId => Option<T::BlockNumber>;
+        /// Each grantor's changes that have not taken effect yet, with the block from which
+        /// they may be enacted.
+        TimelockedChanges get(timelocked_changes):
+            map T::AccountId => Vec<(T::BlockNumber, TrustChangeOf<T>)>;
+        /// When each grantor's trust reverts if it has not been triggered or renewed.
+        TrustExpiries get(trust_expiry):
+            map T::AccountId => Option<TrustExpiry<T::AccountId, T::BlockNumber>>;
+        /// The grantors whose trusts expire at each block.
+        ExpiriesDue get(expiries_due): map T::BlockNumber => Vec<T::AccountId>;
+        /// The recurring deposits each grantor has set up, per asset.
+        AutoTopUps get(auto_top_ups):
+            map T::AccountId => Vec<(T::AssetId, TopUp<BalanceOf<T>, T::BlockNumber>)>;
+        /// The recurring deposits due at each block, as (grantor, asset_id).
+        TopUpsDue get(top_ups_due): map T::BlockNumber => Vec<(T::AccountId, T::AssetId)>;
+        /// Beneficiaries that replace the plain set of each grantor's trust for specific
+        /// assets.
+        AssetBeneficiaries get(asset_beneficiaries):
+            map T::AccountId => Vec<(T::AssetId, Vec<BeneficiaryShare<T::AccountId>>)>;
+        /// Who may act for each grantor while they are incapacitated.
+        PowersOfAttorney get(power_of_attorney):
+            map T::AccountId => Option<PowerOfAttorney<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Moment>>;
+        /// First block of the current period and the amount the attorney paid out of each
+        /// trust's asset in it.
+        AttorneySpending get(attorney_spending): map (T::AccountId, T::AssetId) => (T::BlockNumber, BalanceOf<T>);
+        /// The grantors of each joint trust, who approve its configuration changes.
+        JointTrustOwners get(joint_trust_owners): map T::AccountId => Vec<T::AccountId>;
+        /// How many grantors of each joint trust must approve a change. Every grantor must if
+        /// unset.
+        ApprovalThresholds get(approval_threshold): map T::AccountId => Option<u32>;
+        /// The joint trusts each account is a grantor of.
+        JointTrustsOf get(joint_trusts_of): map T::AccountId => Vec<T::AccountId>;
+        /// How many joint trusts have been created, from which their accounts are derived.
+        JointTrustCount get(joint_trust_count): u32;
+        /// The change proposed for each joint trust, with the grantors that approved it.
+        PendingChanges get(pending_change): map T::AccountId => Option<(Call<T>, Vec<T::AccountId>)>;
     }
 }
 
@@ -95,11 +977,237 @@ decl_event!(
         AccountId = <T as system::Trait>::AccountId,
         BlockNumber = <T as system::Trait>::BlockNumber,
         Moment = <T as timestamp::Trait>::Moment,
+        AssetId = <T as assets::Trait>::AssetId,
+        Balance = BalanceOf<T>,
+        ClassId = ClassIdOf<T>,
+        InstanceId = InstanceIdOf<T>,
+        NativeBalance = NativeBalanceOf<T>,
+        HeartbeatId = <T as Trait>::HeartbeatId,
+        Hash = <T as system::Trait>::Hash,
     {
         // Just a normal `enum`, here's a dummy event to ensure it compiles.
         BeneficiariesSet(AccountId, Vec<BeneficiaryShare<AccountId>>),
         LivingSwitchCondSet(AccountId, LivingSwitchCond<BlockNumber, Moment>),
+        /// A beneficiary was added. (grantor, beneficiary, weight)
+        BeneficiaryAdded(AccountId, AccountId, u64),
+        /// A beneficiary was removed. (grantor, beneficiary)
+        BeneficiaryRemoved(AccountId, AccountId),
+        /// The weight of a beneficiary changed. (grantor, beneficiary, weight)
+        BeneficiaryWeightUpdated(AccountId, AccountId, u64),
         Withdraw(AccountId),
+        /// Funds were moved into a trust. (grantor, asset_id, amount)
+        Deposited(AccountId, AssetId, Balance),
+        /// A grantor limited how long their trust lives, or lifted the limit. (grantor, expiry)
+        TrustExpirySet(AccountId, Option<BlockNumber>),
+        /// A grantor renewed their trust. (grantor, new expiry)
+        TrustRenewed(AccountId, BlockNumber),
+        /// A trust expired and its funds reverted. (grantor, recipient)
+        TrustExpired(AccountId, AccountId),
+        /// A grantor set up or stopped a recurring deposit. (grantor, asset_id, amount, interval)
+        TopUpSet(AccountId, AssetId, Balance, BlockNumber),
+        /// A recurring deposit could not be made, e.g. for lack of free balance.
+        /// (grantor, asset_id, amount)
+        TopUpFailed(AccountId, AssetId, Balance),
+        /// Someone other than the grantor funded a trust. (grantor, contributor, asset_id, amount)
+        Contributed(AccountId, AccountId, AssetId, Balance),
+        /// A contribution was paid back from a revoked trust. (grantor, contributor, asset_id, amount)
+        ContributionRefunded(AccountId, AccountId, AssetId, Balance),
+        /// A grantor revoked their trust before it was triggered.
+        TrustRevoked(AccountId),
+        /// A grantor changed how long their changes wait before taking effect. (grantor, delay)
+        ChangeDelaySet(AccountId, BlockNumber),
+        /// A change was scheduled to take effect later. (grantor, due block)
+        ChangeScheduled(AccountId, BlockNumber),
+        /// A grantor set or removed the beneficiaries of one asset. (grantor, asset_id)
+        AssetBeneficiariesSet(AccountId, AssetId),
+        /// A scheduled change was enacted. (grantor, succeeded)
+        TimelockedChangeEnacted(AccountId, bool),
+        /// A grantor cancelled their scheduled changes.
+        TimelockedChangesCancelled(AccountId),
+        /// A grantor changed their allowance. (grantor, asset_id, amount, period)
+        AllowanceSet(AccountId, AssetId, Balance, BlockNumber),
+        /// A grantor took funds back under their allowance. (grantor, asset_id, amount)
+        AllowanceSpent(AccountId, AssetId, Balance),
+        /// A grantor removed their living switch condition.
+        LivingSwitchCondReset(AccountId),
+        /// A grantor removed all of their beneficiaries.
+        BeneficiariesCleared(AccountId),
+        /// A grantor wiped their clock-in history.
+        ClockInHistoryCleared(AccountId),
+        /// A grantor set the metadata of their trust.
+        MetadataSet(AccountId),
+        /// A grantor removed the metadata of their trust.
+        MetadataCleared(AccountId),
+        /// Idle trust funds were bonded. (grantor, asset_id, amount)
+        IdleFundsBonded(AccountId, AssetId, Balance),
+        /// Bonded trust funds were released. (grantor, asset_id, amount)
+        IdleFundsUnbonded(AccountId, AssetId, Balance),
+        /// A beneficiary was paid out. (grantor, beneficiary, asset_id, amount)
+        Payout(AccountId, AccountId, AssetId, Balance),
+        /// A unique item was assigned. (grantor, beneficiary, class, instance)
+        UniqueAssigned(AccountId, AccountId, ClassId, InstanceId),
+        /// A unique item assignment was removed. (grantor, class, instance)
+        UniqueUnassigned(AccountId, ClassId, InstanceId),
+        /// A unique item was handed to its beneficiary. (grantor, beneficiary, class, instance)
+        UniquePayout(AccountId, AccountId, ClassId, InstanceId),
+        /// A grantor changed who may trigger their trust. (grantor, policy)
+        TriggerPolicySet(AccountId, TriggerPolicy),
+        /// A grantor changed their trustee. (grantor, trustee)
+        TrusteeSet(AccountId, Option<AccountId>),
+        /// A trigger attempt was made before the condition held; the deposit was slashed.
+        /// (grantor, caller, deposit)
+        TriggerRejected(AccountId, AccountId, NativeBalance),
+        /// A grantor changed how their trust pays out. (grantor, mode)
+        PayoutModeSet(AccountId, PayoutMode<Balance>),
+        /// A share was held until the beneficiary's identity is verified.
+        /// (grantor, beneficiary, asset_id, amount)
+        ShareHeld(AccountId, AccountId, AssetId, Balance),
+        /// A held share was paid out. (grantor, beneficiary, asset_id, amount, recipient)
+        HeldShareReleased(AccountId, AccountId, AssetId, Balance, AccountId),
+        /// A beneficiary renounced their share of a trust. (grantor, beneficiary)
+        ShareDisclaimed(AccountId, AccountId),
+        /// A streamed share started to accrue. (grantor, beneficiary, asset_id, amount)
+        StreamOpened(AccountId, AccountId, AssetId, Balance),
+        /// The distribution fee was changed. (fee, destination)
+        DistributionFeeSet(Perbill, Option<AccountId>),
+        /// The deposit cap of an asset was changed. (asset_id, cap)
+        DepositCapSet(AssetId, Option<Balance>),
+        /// Distributions were halted or resumed. (paused)
+        DistributionsPausedSet(bool),
+        /// A trust template was added or replaced. (template_id)
+        TrustTemplateSet(u32),
+        /// A trust template was withdrawn. (template_id)
+        TrustTemplateRemoved(u32),
+        /// A distribution fee was paid. (grantor, asset_id, fee)
+        DistributionFeePaid(AccountId, AssetId, Balance),
+        /// Governance changed the estate tax. (rate, account)
+        EstateTaxSet(Perbill, Option<AccountId>),
+        /// Part of a payout was diverted by the distribution hook.
+        /// (grantor, beneficiary, asset_id, amount, recipient)
+        DistributionLevied(AccountId, AccountId, AssetId, Balance, AccountId),
+        /// A grantor changed the key their node clocks in with. (grantor, key)
+        HeartbeatKeySet(AccountId, Option<HeartbeatId>),
+        /// A grantor changed the key that may only clock in for them. (grantor, key)
+        LivenessKeySet(AccountId, Option<AccountId>),
+        /// A grantor set the second key that must confirm their clock-ins, or it released
+        /// itself. (grantor, key)
+        ConfirmationKeySet(AccountId, Option<AccountId>),
+        /// A clock-in awaits confirmation by the grantor's second key. (grantor)
+        ClockInPending(AccountId),
+        /// The grantor's second key confirmed their pending clock-in. (grantor)
+        ClockInConfirmed(AccountId),
+        /// Governance changed the keeper bounty.
+        KeeperBountySet(Perbill),
+        /// A finished trust was closed and its storage removed. (grantor)
+        TrustClosed(AccountId),
+        /// A keeper was paid for performing a distribution. (grantor, keeper, asset_id, bounty)
+        KeeperRewarded(AccountId, AccountId, AssetId, Balance),
+        /// A grantor's node clocked in on their behalf. (grantor, nonce)
+        HeartbeatReceived(AccountId, u64),
+        /// The living switch of a trust fired.
+        TrustTriggered(AccountId),
+        /// A beneficiary of a triggered trust can claim the listed amounts of each asset.
+        /// (grantor, beneficiary, [(asset_id, amount)])
+        InheritanceAvailable(AccountId, AccountId, Vec<(AssetId, Balance)>),
+        /// A grantor replaced the tiers of their trust. (grantor, tier count)
+        TiersSet(AccountId, u32),
+        /// A triggered trust pays out according to a tier. (grantor, tier index)
+        TierTriggered(AccountId, u32),
+        /// A grantor replaced their guardians. (grantor, guardians)
+        GuardiansSet(AccountId, Vec<AccountId>),
+        /// A beneficiary changed the asset they would rather be paid in.
+        /// (beneficiary, preference)
+        PreferredAssetSet(AccountId, Option<(AssetId, Perbill)>),
+        /// A payout was converted into the beneficiary's preferred asset.
+        /// (grantor, beneficiary, asset_id, amount, preferred asset_id, received)
+        PayoutConverted(AccountId, AccountId, AssetId, Balance, AssetId, Balance),
+        /// A payout could not be converted and was made in its original asset.
+        /// (grantor, beneficiary, asset_id, amount)
+        ConversionFailed(AccountId, AccountId, AssetId, Balance),
+        /// A grantor appointed or removed a beneficiary's custodian.
+        /// (grantor, beneficiary, custodian)
+        CustodianSet(AccountId, AccountId, Option<AccountId>),
+        /// A custodian drew part of a beneficiary's share.
+        /// (grantor, beneficiary, asset_id, amount, custodian)
+        CustodianClaimed(AccountId, AccountId, AssetId, Balance, AccountId),
+        /// A grantor chose whether a beneficiary's share goes into the beneficiary's own trust.
+        /// (grantor, beneficiary, into_trust)
+        PaysIntoTrustSet(AccountId, AccountId, bool),
+        /// A share was deposited into the beneficiary's trust.
+        /// (grantor, beneficiary, asset_id, amount)
+        PaidIntoTrust(AccountId, AccountId, AssetId, Balance),
+        /// A beneficiary's account was reaped before the trust paid them. (grantor, beneficiary)
+        BeneficiaryReaped(AccountId, AccountId),
+        /// The share of a reaped beneficiary was paid to the fallback account.
+        /// (grantor, beneficiary, asset_id, amount, fallback)
+        ShareRedirected(AccountId, AccountId, AssetId, Balance, AccountId),
+        /// A beneficiary disputed a triggered trust's split. (grantor, beneficiary, expires at)
+        DisputeRaised(AccountId, AccountId, BlockNumber),
+        /// A dispute was resolved, with the corrected split if the original was not upheld.
+        /// (grantor, corrected split)
+        DisputeResolved(AccountId, Option<Vec<BeneficiaryShare<AccountId>>>),
+        /// A dispute lapsed without being resolved. (grantor)
+        DisputeExpired(AccountId),
+        /// A grantor replaced their witnesses. (grantor, witnesses)
+        WitnessesSet(AccountId, Vec<AccountId>),
+        /// A witness co-signed a trust's configuration. (grantor, witness, config hash)
+        WitnessConfirmed(AccountId, AccountId, Hash),
+        /// A grantor replaced their observers. (grantor, observers)
+        ObserversSet(AccountId, Vec<AccountId>),
+        /// An observed trust was triggered. (grantor, observer)
+        ObservedTrigger(AccountId, AccountId),
+        /// An observed trust distributed a holding. (grantor, observer, asset_id)
+        ObservedDistribution(AccountId, AccountId, AssetId),
+        /// A grantor invited a guardian. (grantor, guardian)
+        GuardianInvited(AccountId, AccountId),
+        /// An invited guardian accepted. (grantor, guardian)
+        GuardianAccepted(AccountId, AccountId),
+        /// A grantor removed a guardian or withdrew an invitation. (grantor, guardian)
+        GuardianRemoved(AccountId, AccountId),
+        /// A guardian was dropped for not confirming their activity in time. (grantor, guardian)
+        GuardianExpired(AccountId, AccountId),
+        /// A guardian's attestation was disproved by the grantor clocking in; their deposit was
+        /// slashed. (grantor, guardian, deposit)
+        AttestationSlashed(AccountId, AccountId, NativeBalance),
+        /// A grantor changed what guardians are paid for a trigger. (grantor, reward)
+        GuardianRewardSet(AccountId, Perbill),
+        /// A guardian was paid for attesting to a trigger. (grantor, guardian, asset_id, amount)
+        GuardianRewarded(AccountId, AccountId, AssetId, Balance),
+        /// A grantor locked or unlocked a beneficiary's claim. (grantor, beneficiary, hash)
+        ClaimLockSet(AccountId, AccountId, Option<Hash>),
+        /// A beneficiary presented the preimage of their claim lock. (grantor, beneficiary)
+        ClaimUnlocked(AccountId, AccountId),
+        /// The accounts a beneficiary's share may be sent to changed.
+        /// (grantor, beneficiary, whitelist)
+        PayoutWhitelistSet(AccountId, AccountId, Vec<AccountId>),
+        /// A beneficiary chose where their share is sent. (grantor, beneficiary, destination)
+        PayoutDestinationSet(AccountId, AccountId, AccountId),
+        /// A guardian attested. (grantor, guardian)
+        GuardianAttested(AccountId, AccountId),
+        /// A grantor offered their trust to a new account. (grantor, new_account)
+        TrustOwnershipProposed(AccountId, AccountId),
+        /// A trust moved to a new grantor account. (old_account, new_account)
+        TrustOwnershipTransferred(AccountId, AccountId),
+        /// A grantor appointed or dismissed their attorney. (grantor, attorney)
+        AttorneySet(AccountId, Option<AccountId>),
+        /// An attorney paid a bill from a trust. (grantor, asset_id, recipient, amount)
+        AttorneyPaid(AccountId, AssetId, AccountId, Balance),
+        /// A trust controlled by several grantors was created. (trust, grantors)
+        JointTrustCreated(AccountId, Vec<AccountId>),
+        /// A grantor of a joint trust proposed a change. (trust, grantor)
+        ChangeProposed(AccountId, AccountId),
+        /// A grantor of a joint trust approved the pending change. (trust, grantor)
+        ChangeApproved(AccountId, AccountId),
+        /// The pending change of a joint trust was withdrawn. (trust)
+        ChangeCancelled(AccountId),
+        /// Enough grantors approved the pending change of a joint trust and it was applied.
+        /// (trust, succeeded)
+        ChangeExecuted(AccountId, bool),
+        /// A joint trust changed how many of its grantors must approve a change.
+        /// (trust, threshold)
+        ApprovalThresholdSet(AccountId, u32),
+        /// A trust moved to a new lifecycle state. (grantor, state)
+        TrustStateChanged(AccountId, TrustState),
     }
 );
 
@@ -112,17 +1220,194 @@ decl_module! {
         /// looks like: `fn deposit_event() = default;`.
         fn deposit_event() = default;
 
+        /// How many clock-ins are kept in each grantor's history.
+        const ClockInHistoryLength: u32 = T::ClockInHistoryLength::get();
+
+        /// The smallest asset balance a payout may leave behind or create.
+        const AssetExistentialDeposit: BalanceOf<T> = T::AssetExistentialDeposit::get();
+
+        /// How many queued automatic distributions are performed at the start of each block.
+        const AutoDistributionsPerBlock: u32 = T::AutoDistributionsPerBlock::get();
+
+        /// The smallest holding of an asset a trust may be funded with.
+        const MinTrustDeposit: BalanceOf<T> = T::MinTrustDeposit::get();
+
+        /// How many entries are kept in each trust's audit log.
+        const AuditLogLength: u32 = T::AuditLogLength::get();
+
+        /// How many blocks a node waits after the last clock-in before sending a heartbeat.
+        const HeartbeatInterval: T::BlockNumber = T::HeartbeatInterval::get();
+
+        /// How many blocks a grantor must wait between two unsigned clock-ins.
+        const UnsignedClockInPeriod: T::BlockNumber = T::UnsignedClockInPeriod::get();
+
+        /// How many blocks a guardian stays a guardian without confirming they are still active.
+        const GuardianActivityPeriod: T::BlockNumber = T::GuardianActivityPeriod::get();
+
+        /// Deposit a beneficiary reserves when disputing a triggered trust's split.
+        const DisputeDeposit: NativeBalanceOf<T> = T::DisputeDeposit::get();
+
+        /// How many blocks a dispute freezes distribution for at most.
+        const DisputeWindow: T::BlockNumber = T::DisputeWindow::get();
+
+        /// How many blocks must pass between two changes to a trust's beneficiaries or
+        /// conditions.
+        const ConfigChangeCooldown: T::BlockNumber = T::ConfigChangeCooldown::get();
+
+        /// Deposit a guardian reserves when attesting.
+        const AttestationDeposit: NativeBalanceOf<T> = T::AttestationDeposit::get();
+
+        /// Storage deposit reserved from the grantor for every beneficiary entry of a trust.
+        const BeneficiaryDeposit: NativeBalanceOf<T> = T::BeneficiaryDeposit::get();
+
+        /// Storage deposit reserved from the grantor for every asset a trust holds.
+        const AssetDeposit: NativeBalanceOf<T> = T::AssetDeposit::get();
+
+        /// Move `amount` of asset `asset_id` from the grantor's account into their trust.
+        #[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::deposit())]
         fn deposit(origin, asset_id: T::AssetId, amount: BalanceOf<T>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::credit_trust(&grantor, &grantor, asset_id, amount)?;
+            Self::record(&grantor, AuditAction::Deposited);
+            Self::deposit_event(RawEvent::Deposited(grantor, asset_id, amount));
             Ok(())
         }
 
-        fn set_beneficiaries(origin, option: Vec<BeneficiaryShare<T::AccountId>>) -> Result {
+        /// Let the trust expire `duration` blocks from now, or from its latest renewal, unless it
+        /// has been triggered by then. Its funds then revert to `reversion`, or the grantor if
+        /// `None`, and it closes. `None` for `duration` lets the trust live forever.
+        fn set_trust_expiry(
+            origin,
+            duration: Option<T::BlockNumber>,
+            reversion: Option<T::AccountId>
+        ) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::SetExpiry(duration, reversion))
+        }
+
+        /// Push the expiry of the trust back to a full duration from now.
+        fn renew_trust(origin) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            let mut expiry = Self::trust_expiry(&grantor).ok_or("trust has no expiry")?;
+            expiry.expires_at = <system::Module<T>>::block_number() + expiry.duration;
+            <ExpiriesDue<T>>::mutate(expiry.expires_at, |due| due.push(grantor.clone()));
+            let expires_at = expiry.expires_at;
+            <TrustExpiries<T>>::insert(&grantor, expiry);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::TrustRenewed(grantor, expires_at));
+            Ok(())
+        }
+
+        /// Deposit `amount` of asset `asset_id` into the trust every `interval` blocks while it is
+        /// active. A zero `amount` stops the deposits.
+        fn set_auto_topup(
+            origin,
+            asset_id: T::AssetId,
+            amount: BalanceOf<T>,
+            interval: T::BlockNumber
+        ) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            let mut top_ups = Self::auto_top_ups(&grantor);
+            top_ups.retain(|(asset, _)| *asset != asset_id);
+            if !amount.is_zero() {
+                ensure!(!interval.is_zero(), "top-up interval should be non-zero");
+                let next = <system::Module<T>>::block_number() + interval;
+                top_ups.push((asset_id, TopUp { amount, interval, next }));
+                <TopUpsDue<T>>::mutate(next, |due| due.push((grantor.clone(), asset_id)));
+            }
+            if top_ups.is_empty() {
+                <AutoTopUps<T>>::remove(&grantor);
+            } else {
+                <AutoTopUps<T>>::insert(&grantor, top_ups);
+            }
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::TopUpSet(grantor, asset_id, amount, interval));
+            Ok(())
+        }
+
+        /// Move `amount` of asset `asset_id` from the caller's account into `grantor`'s trust.
+        /// Contributions are paid back pro rata if the grantor revokes the trust.
+        fn contribute(origin, grantor: T::AccountId, asset_id: T::AssetId, amount: BalanceOf<T>) -> Result {
+            let contributor = ensure_signed(origin)?;
+            ensure!(Self::has_trust(&grantor), "no such trust");
+            ensure!(
+                amount >= T::AssetExistentialDeposit::get(),
+                "contribution below AssetExistentialDeposit"
+            );
+            Self::credit_trust(&grantor, &contributor, asset_id, amount)?;
+            Self::record(&grantor, AuditAction::Deposited);
+            Self::deposit_event(RawEvent::Contributed(grantor, contributor, asset_id, amount));
+            Ok(())
+        }
+
+        /// Close the trust before it is triggered and pay every holding back to whoever put
+        /// it in, in proportion to their contributions.
+        fn revoke_trust(origin) -> Result {
             let grantor = ensure_signed(origin)?;
-            <Beneficiaries<T>>::insert(&grantor, &option);
-            Self::deposit_event(RawEvent::BeneficiariesSet(grantor, option));
+            Self::ensure_active(&grantor)?;
+            let assets = Self::trust_assets(&grantor);
+            for asset_id in &assets {
+                ensure!(
+                    !<BondedFunds<T>>::exists((grantor.clone(), *asset_id)),
+                    "idle funds are still bonded"
+                );
+            }
+
+            for asset_id in assets {
+                Self::refund_contributions(&grantor, asset_id)?;
+            }
+            Self::disarm(&grantor);
+            Self::release_attestations(&grantor, false);
+            Self::set_trust_state(&grantor, TrustState::Closed);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::TrustRevoked(grantor));
             Ok(())
         }
 
+        #[weight = SetBeneficiariesWeight::<T>::default()]
+        fn set_beneficiaries(origin, option: Vec<BeneficiaryShare<T::AccountId>>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::SetBeneficiaries(option))
+        }
+
+        /// Set the beneficiaries with exact percentages of the trust. Shares may add up to
+        /// less than 100%, in which case the remainder stays in the trust.
+        fn set_beneficiaries_percent(origin, shares: Vec<(T::AccountId, Perbill)>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::SetBeneficiariesPercent(shares))
+        }
+
+        /// Add a single beneficiary to the trust.
+        fn add_beneficiary(origin, beneficiary: T::AccountId, weight: u64) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::AddBeneficiary(beneficiary, weight))
+        }
+
+        /// Pay the trust's `asset_id` holding out to `shares` instead of the plain
+        /// beneficiaries, or go back to the plain beneficiaries with `None`.
+        fn set_asset_beneficiaries(
+            origin,
+            asset_id: T::AssetId,
+            shares: Option<Vec<BeneficiaryShare<T::AccountId>>>
+        ) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::SetAssetBeneficiaries(asset_id, shares))
+        }
+
+        /// Remove a single beneficiary from the trust.
+        fn remove_beneficiary(origin, beneficiary: T::AccountId) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::RemoveBeneficiary(beneficiary))
+        }
+
+        /// Change the weight of a single beneficiary.
+        fn update_beneficiary_weight(origin, beneficiary: T::AccountId, weight: u64) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::UpdateBeneficiaryWeight(beneficiary, weight))
+        }
+
         fn set_schedule_payment(origin, asset_id: T::AssetId, beneficiary: T::AccountId, amount: BalanceOf<T>) -> Result {
             Ok(())
         }
@@ -131,69 +1416,2962 @@ decl_module! {
             Ok(())
         }
 
+        #[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::clock_in())]
         fn clock_in(origin) -> Result {
             let grantor = ensure_signed(origin)?;
-            let block_number = <system::Module<T>>::block_number();
-            <LastClockIn<T>>::insert(&grantor, &block_number);
+            Self::request_clock_in(&grantor);
             Ok(())
         }
 
-        fn set_living_switch_condition(origin, condition: LivingSwitchCond<T::BlockNumber, T::Moment>) -> Result {
+        /// Let the node holding `key` clock in on the grantor's behalf, or stop it with `None`.
+        fn set_heartbeat_key(origin, key: Option<T::HeartbeatId>) -> Result {
             let grantor = ensure_signed(origin)?;
-            <LivingSwitchConds<T>>::insert(&grantor, &condition);
-            Self::deposit_event(RawEvent::LivingSwitchCondSet(grantor, condition));
+            if let Some(key) = &key {
+                ensure!(
+                    !<HeartbeatGrantor<T>>::exists(key),
+                    "heartbeat key is already authorized"
+                );
+            }
+            if let Some(previous) = <AuthorizedHeartbeatKey<T>>::take(&grantor) {
+                <HeartbeatGrantor<T>>::remove(&previous);
+            }
+            if let Some(key) = &key {
+                <AuthorizedHeartbeatKey<T>>::insert(&grantor, key);
+                <HeartbeatGrantor<T>>::insert(key, &grantor);
+            }
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::HeartbeatKeySet(grantor, key));
             Ok(())
         }
 
-        fn withdraw(origin, grantor: T::AccountId, asset_id: T::AssetId) -> Result {
-            let living_cond = <LivingSwitchConds<T>>::get(&grantor);
-
-            let can_withdraw = Self::check_withdrawable(&grantor, &living_cond)?;
-            ensure!(can_withdraw, "not withdrawable yet");
-            let total_amount = <assets::Module<T>>::balance(asset_id.clone(), grantor.clone());
-            ensure!(total_amount > Zero::zero(), "no balance");
-            let beneficiaries = <Beneficiaries<T>>::get(&grantor);
-            ensure!(beneficiaries.len() > Zero::zero(), "no beneficiaries");
-            Self::calc_shares(&total_amount, &beneficiaries).iter().for_each(|share| match share {
-                    (account, amount) => {
-                        <assets::Module<T>>::make_transfer(grantor.clone(), asset_id.clone(), (*account).clone(), (*amount).clone());
-                    }
-                }
-            );
-            Self::deposit_event(RawEvent::Withdraw(grantor));
+        /// Let `key` clock in for the grantor with `liveness_clock_in`, and do nothing else, or
+        /// stop it with `None`. This keeps the key controlling the trust off everyday devices.
+        fn set_liveness_key(origin, key: Option<T::AccountId>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            if let Some(key) = &key {
+                ensure!(*key != grantor, "grantor cannot be their own liveness key");
+                ensure!(!<LivenessGrantor<T>>::exists(key), "liveness key is already in use");
+            }
+            if let Some(previous) = <LivenessKey<T>>::take(&grantor) {
+                <LivenessGrantor<T>>::remove(&previous);
+            }
+            if let Some(key) = &key {
+                <LivenessKey<T>>::insert(&grantor, key);
+                <LivenessGrantor<T>>::insert(key, &grantor);
+            }
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::LivenessKeySet(grantor, key));
             Ok(())
         }
 
-        // The signature could also look like: `fn on_initialize()`
-        fn on_initialize(_n: T::BlockNumber) {
-            // Anything that needs to be done at the start of the block.
-            // We don't do anything here.
+        /// Clock in, with a liveness key, for the grantor that registered it.
+        fn liveness_clock_in(origin) -> Result {
+            let key = ensure_signed(origin)?;
+            let grantor = Self::liveness_grantor(&key).ok_or("not a liveness key")?;
+            Self::request_clock_in(&grantor);
+            Ok(())
         }
 
-        // The signature could also look like: `fn on_finalize()`
-        fn on_finalize(_n: T::BlockNumber) {
-            // Anything that needs to be done at the end of the block.
-            // We just kill our dummy storage item.
-//            <Dummy<T>>::kill();
+        /// Clock in with a heartbeat signed by the grantor's authorized key.
+        fn heartbeat(
+            origin,
+            heartbeat: Heartbeat<T::AccountId, T::BlockNumber>,
+            signature: <T::HeartbeatId as RuntimeAppPublic>::Signature
+        ) -> Result {
+            ensure_none(origin)?;
+            Self::check_heartbeat(&heartbeat, &signature)?;
+            <HeartbeatNonce<T>>::insert(&heartbeat.grantor, heartbeat.nonce + 1);
+            Self::request_clock_in(&heartbeat.grantor);
+            Self::deposit_event(RawEvent::HeartbeatReceived(heartbeat.grantor, heartbeat.nonce));
+            Ok(())
         }
 
-        // A runtime code run after every block and have access to extended set of APIs.
-        //
-        // For instance you can generate extrinsics for the upcoming produced block.
-        fn offchain_worker(_n: T::BlockNumber) {
-            // We don't do anything here.
-            // but we could dispatch extrinsic (transaction/unsigned/inherent) using
-            // runtime_io::submit_extrinsic
+        /// Clock in without paying for a transaction. `signature` is the grantor's signature
+        /// over `(grantor, nonce)`, and `nonce` must equal their current clock-in nonce.
+        fn clock_in_unsigned(origin, grantor: T::AccountId, signature: T::Signature, nonce: u64) -> Result {
+            ensure_none(origin)?;
+            Self::check_unsigned_clock_in(&grantor, &signature, nonce)?;
+            <ClockInNonce<T>>::insert(&grantor, nonce + 1);
+            <LastUnsignedClockIn<T>>::insert(&grantor, <system::Module<T>>::block_number());
+            Self::request_clock_in(&grantor);
+            Ok(())
         }
-    }
+
+        /// Require every clock-in of the trust to be confirmed by `key`, e.g. a hardware
+        /// wallet, within `window` blocks, so that a single stolen device cannot keep the trust
+        /// from triggering. Once set, only `key` itself can lift the requirement, with
+        /// `release_confirmation_key`.
+        fn set_confirmation_key(origin, key: T::AccountId, window: T::BlockNumber) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            ensure!(key != grantor, "grantor cannot be their own confirmation key");
+            ensure!(!window.is_zero(), "confirmation window must be non-zero");
+            ensure!(
+                !<ConfirmationKeys<T>>::exists(&grantor),
+                "confirmation key already set"
+            );
+            <ConfirmationKeys<T>>::insert(&grantor, (key.clone(), window));
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::ConfirmationKeySet(grantor, Some(key)));
+            Ok(())
+        }
+
+        /// Stop confirming `grantor`'s clock-ins, as their confirmation key. A pending
+        /// clock-in is dropped.
+        fn release_confirmation_key(origin, grantor: T::AccountId) -> Result {
+            let key = ensure_signed(origin)?;
+            Self::ensure_confirmation_key(&grantor, &key)?;
+            <ConfirmationKeys<T>>::remove(&grantor);
+            <PendingClockIns<T>>::remove(&grantor);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::ConfirmationKeySet(grantor, None));
+            Ok(())
+        }
+
+        /// Confirm `grantor`'s pending clock-in, as their confirmation key. The clock-in counts
+        /// from this block.
+        fn confirm_clock_in(origin, grantor: T::AccountId) -> Result {
+            let key = ensure_signed(origin)?;
+            let window = Self::ensure_confirmation_key(&grantor, &key)?;
+            let requested = Self::pending_clock_in(&grantor).ok_or("no clock-in to confirm")?;
+            ensure!(
+                <system::Module<T>>::block_number() <= requested + window,
+                "confirmation window has passed"
+            );
+            <PendingClockIns<T>>::remove(&grantor);
+            Self::do_clock_in(&grantor);
+            Self::deposit_event(RawEvent::ClockInConfirmed(grantor));
+            Ok(())
+        }
+
+        /// Keep the existential deposit in the trust when it pays out.
+        fn set_keep_alive(origin, keep_alive: bool) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            <KeepAlive<T>>::insert(&grantor, keep_alive);
+            Self::record(&grantor, AuditAction::Configured);
+            Ok(())
+        }
+
+        /// Let the trust distribute every asset it holds by itself once triggered, without
+        /// waiting for a `withdraw` call.
+        fn set_auto_distribute(origin, auto_distribute: bool) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            <AutoDistribute<T>>::insert(&grantor, auto_distribute);
+            Self::record(&grantor, AuditAction::Configured);
+            Ok(())
+        }
+
+        /// Require beneficiaries to have a verified identity before they receive their share.
+        fn set_require_verified_identity(origin, required: bool) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            <RequireVerifiedIdentity<T>>::insert(&grantor, required);
+            Self::record(&grantor, AuditAction::Configured);
+            Ok(())
+        }
+
+        /// Set the account that receives shares unverified beneficiaries leave unclaimed.
+        fn set_fallback_account(origin, fallback: Option<T::AccountId>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            match fallback {
+                Some(fallback) => <FallbackAccount<T>>::insert(&grantor, fallback),
+                None => <FallbackAccount<T>>::remove(&grantor),
+            }
+            Self::record(&grantor, AuditAction::Configured);
+            Ok(())
+        }
+
+        /// Choose between lump-sum and streamed payouts.
+        fn set_payout_mode(origin, mode: PayoutMode<BalanceOf<T>>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            if let PayoutMode::Streamed(rate) = mode {
+                ensure!(!rate.is_zero(), "stream rate should be non-zero");
+            }
+            <PayoutModes<T>>::insert(&grantor, mode);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::PayoutModeSet(grantor, mode));
+            Ok(())
+        }
+
+        fn set_living_switch_condition(origin, condition: LivingSwitchCond<T::BlockNumber, T::Moment>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::SetLivingSwitchCondition(condition))
+        }
+
+        /// Make changes to beneficiaries and conditions wait `delay` blocks before they take
+        /// effect. Shortening the delay is itself subject to the current delay.
+        fn set_change_delay(origin, delay: T::BlockNumber) -> Result {
+            let grantor = ensure_signed(origin)?;
+            if delay >= Self::change_delay(&grantor) {
+                Self::do_set_change_delay(grantor, delay)
+            } else {
+                Self::change_or_schedule(grantor, TrustChange::SetChangeDelay(delay))
+            }
+        }
+
+        /// Apply the scheduled changes of `grantor` whose delay has passed, in the order they
+        /// were made.
+        fn enact_changes(origin, grantor: T::AccountId) -> Result {
+            ensure_signed(origin)?;
+            let now = <system::Module<T>>::block_number();
+            let (due, pending): (Vec<_>, Vec<_>) =
+                Self::timelocked_changes(&grantor).into_iter().partition(|(at, _)| *at <= now);
+            ensure!(!due.is_empty(), "no change is due");
+
+            if pending.is_empty() {
+                <TimelockedChanges<T>>::remove(&grantor);
+            } else {
+                <TimelockedChanges<T>>::insert(&grantor, pending);
+            }
+            for (_, change) in due {
+                let result = Self::apply_change(grantor.clone(), change);
+                Self::deposit_event(RawEvent::TimelockedChangeEnacted(grantor.clone(), result.is_ok()));
+            }
+            Ok(())
+        }
+
+        /// Drop every scheduled change of the trust.
+        fn cancel_timelocked_changes(origin) -> Result {
+            let grantor = ensure_signed(origin)?;
+            ensure!(<TimelockedChanges<T>>::exists(&grantor), "no scheduled changes");
+            <TimelockedChanges<T>>::remove(&grantor);
+            Self::deposit_event(RawEvent::TimelockedChangesCancelled(grantor));
+            Ok(())
+        }
+
+        /// Describe the trust for explorers and heir-facing applications.
+        fn set_metadata(origin, metadata: TrustMetadata<T::Hash>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            let length = metadata.name.len().saturating_add(metadata.jurisdiction.len());
+            ensure!(length <= T::MaxMetadataLength::get() as usize, "metadata too long");
+            <Metadata<T>>::insert(&grantor, metadata);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::MetadataSet(grantor));
+            Ok(())
+        }
+
+        /// Remove the description of the trust.
+        fn clear_metadata(origin) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            ensure!(<Metadata<T>>::exists(&grantor), "no metadata set");
+            <Metadata<T>>::remove(&grantor);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::MetadataCleared(grantor));
+            Ok(())
+        }
+
+        /// Remove the living switch condition, so the trust can no longer fire.
+        fn reset_living_switch_condition(origin) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::ResetLivingSwitchCondition)
+        }
+
+        /// Remove every beneficiary of the trust.
+        fn clear_beneficiaries(origin) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::ClearBeneficiaries)
+        }
+
+        /// Forget every clock-in of the grantor. Clock-in based conditions then count from
+        /// genesis again.
+        fn clear_clock_in_history(origin) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::ClearClockInHistory)
+        }
+
+        /// Replace the tiers of the trust. The first tier whose condition holds and that has
+        /// enough guardian attestations decides who is paid out.
+        fn set_tiers(origin, tiers: Vec<Tier<T::AccountId, T::BlockNumber, T::Moment>>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::SetTiers(tiers))
+        }
+
+        /// Replace the guardians of the trust. Existing attestations are dropped, and accounts
+        /// that are not guardians yet are invited and must accept with `accept_guardianship`.
+        fn set_guardians(origin, guardians: Vec<T::AccountId>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            let (kept, invited): (Vec<_>, Vec<_>) = guardians
+                .iter()
+                .cloned()
+                .partition(|guardian| Self::guardians(&grantor).contains(guardian));
+            for guardian in Self::guardians(&grantor) {
+                if !kept.contains(&guardian) {
+                    <GuardianActivity<T>>::remove(&grantor, &guardian);
+                }
+            }
+            Self::release_attestations(&grantor, false);
+            <Guardians<T>>::insert(&grantor, kept);
+            <PendingGuardians<T>>::insert(&grantor, invited);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::GuardiansSet(grantor, guardians));
+            Ok(())
+        }
+
+        /// Ask to be paid by any trust in `preference`'s asset, converting payouts of other
+        /// assets unless the price slips by more than its `Perbill` from the quote. Payouts that
+        /// cannot be converted are made in their own asset. `None` turns conversion off.
+        fn set_preferred_asset(origin, preference: Option<(T::AssetId, Perbill)>) -> Result {
+            let beneficiary = ensure_signed(origin)?;
+            match preference {
+                Some(preference) => <PreferredAssets<T>>::insert(&beneficiary, preference),
+                None => <PreferredAssets<T>>::remove(&beneficiary),
+            }
+            Self::deposit_event(RawEvent::PreferredAssetSet(beneficiary, preference));
+            Ok(())
+        }
+
+        /// Appoint or remove the custodian of `beneficiary`. Until the beneficiary is eligible,
+        /// their share is held and only the custodian may draw on it, within the cap.
+        fn set_custodian(
+            origin,
+            beneficiary: T::AccountId,
+            custodianship: Option<Custodianship<T::AccountId, BalanceOf<T>, T::BlockNumber>>
+        ) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            let custodian = match custodianship {
+                Some(custodianship) => {
+                    ensure!(!custodianship.period.is_zero(), "custodian period should be non-zero");
+                    let custodian = custodianship.custodian.clone();
+                    <Custodians<T>>::insert(&grantor, &beneficiary, custodianship);
+                    Some(custodian)
+                }
+                None => {
+                    <Custodians<T>>::remove(&grantor, &beneficiary);
+                    None
+                }
+            };
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::CustodianSet(grantor, beneficiary, custodian));
+            Ok(())
+        }
+
+        /// Draw `amount` of the share of `grantor`'s trust held for `beneficiary`, as their
+        /// custodian.
+        fn custodian_claim(
+            origin,
+            grantor: T::AccountId,
+            asset_id: T::AssetId,
+            beneficiary: T::AccountId,
+            amount: BalanceOf<T>
+        ) -> Result {
+            let custodian = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), "amount should be non-zero");
+            Self::ensure_not_paused()?;
+            let custodianship =
+                Self::custodianship(&grantor, &beneficiary).ok_or("beneficiary has no custodian")?;
+            ensure!(custodianship.custodian == custodian, "not the beneficiary's custodian");
+            let now = <system::Module<T>>::block_number();
+            ensure!(now < custodianship.eligible_at, "custodianship has ended");
+
+            let key = (grantor.clone(), asset_id, beneficiary.clone());
+            let (held, held_from) = Self::held_share(&key).ok_or("no held share")?;
+            ensure!(amount <= held, "held share too low");
+            let spending = Self::spend_in_period(
+                now,
+                Self::custodian_spending(&key),
+                amount,
+                custodianship.cap,
+                custodianship.period,
+            )
+            .ok_or("custodian spending cap exceeded")?;
+
+            let pot = Self::account_id();
+            <assets::Module<T>>::make_transfer(pot, asset_id, custodian.clone(), amount)?;
+            if amount == held {
+                <HeldShares<T>>::remove(&key);
+            } else {
+                <HeldShares<T>>::insert(&key, (held - amount, held_from));
+            }
+            <CustodianSpending<T>>::insert(&key, spending);
+            Self::record(&grantor, AuditAction::CustodianClaimed);
+            Self::deposit_event(RawEvent::CustodianClaimed(
+                grantor,
+                beneficiary,
+                asset_id,
+                amount,
+                custodian,
+            ));
+            Ok(())
+        }
+
+        /// Deposit `beneficiary`'s share into the trust `beneficiary` holds, e.g. a spouse's
+        /// trust that passes it on to the children, rather than paying it to them. Shares the
+        /// beneficiary's trust cannot take, e.g. because it has been triggered, are paid as
+        /// usual.
+        fn set_pays_into_trust(origin, beneficiary: T::AccountId, into_trust: bool) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            if into_trust {
+                ensure!(beneficiary != grantor, "a trust cannot pay into itself");
+                ensure!(Self::has_trust(&beneficiary), "beneficiary has no trust");
+                <PaysIntoTrust<T>>::insert(&grantor, &beneficiary, true);
+            } else {
+                <PaysIntoTrust<T>>::remove(&grantor, &beneficiary);
+            }
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::PaysIntoTrustSet(grantor, beneficiary, into_trust));
+            Ok(())
+        }
+
+        /// Require `witnesses` to co-sign the beneficiaries and conditions of the trust with
+        /// `witness_confirm` before it can be triggered. Any later change to them needs to be
+        /// co-signed again. An empty list drops the requirement.
+        fn set_witnesses(origin, witnesses: Vec<T::AccountId>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            for (index, witness) in witnesses.iter().enumerate() {
+                ensure!(!witnesses[..index].contains(witness), "duplicate witness");
+            }
+            if witnesses.is_empty() {
+                <Witnesses<T>>::remove(&grantor);
+            } else {
+                <Witnesses<T>>::insert(&grantor, &witnesses);
+            }
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::WitnessesSet(grantor, witnesses));
+            Ok(())
+        }
+
+        /// Co-sign, as one of `grantor`'s witnesses, the trust configuration whose hash is
+        /// `config_hash`. It must be the trust's current configuration.
+        fn witness_confirm(origin, grantor: T::AccountId, config_hash: T::Hash) -> Result {
+            let witness = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            ensure!(Self::witnesses(&grantor).contains(&witness), "not a witness");
+            ensure!(config_hash == Self::config_hash(&grantor), "configuration hash mismatch");
+            let now = <system::Module<T>>::block_number();
+            <WitnessConfirmations<T>>::mutate(&grantor, |confirmations| {
+                confirmations.retain(|(who, _, _)| *who != witness);
+                confirmations.push((witness.clone(), config_hash, now));
+            });
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::WitnessConfirmed(grantor, witness, config_hash));
+            Ok(())
+        }
+
+        /// Replace the observers of the trust. Observers are told when it triggers and
+        /// distributes but receive nothing from it.
+        fn set_observers(origin, observers: Vec<T::AccountId>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            for (index, observer) in observers.iter().enumerate() {
+                ensure!(!observers[..index].contains(observer), "duplicate observer");
+            }
+            <Observers<T>>::insert(&grantor, &observers);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::ObserversSet(grantor, observers));
+            Ok(())
+        }
+
+        /// Invite `guardian` to become a guardian of the trust.
+        fn add_guardian(origin, guardian: T::AccountId) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            ensure!(!Self::guardians(&grantor).contains(&guardian), "already a guardian");
+            let mut pending = Self::pending_guardians(&grantor);
+            ensure!(!pending.contains(&guardian), "guardian already invited");
+            pending.push(guardian.clone());
+            <PendingGuardians<T>>::insert(&grantor, pending);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::GuardianInvited(grantor, guardian));
+            Ok(())
+        }
+
+        /// Remove `guardian` from the trust, together with their attestation, or withdraw
+        /// their invitation.
+        fn remove_guardian(origin, guardian: T::AccountId) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            let mut pending = Self::pending_guardians(&grantor);
+            if let Some(index) = pending.iter().position(|g| *g == guardian) {
+                pending.remove(index);
+                <PendingGuardians<T>>::insert(&grantor, pending);
+            } else {
+                ensure!(Self::guardians(&grantor).contains(&guardian), "not a guardian");
+                Self::drop_guardian(&grantor, &guardian);
+            }
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::GuardianRemoved(grantor, guardian));
+            Ok(())
+        }
+
+        /// Accept an invitation to become one of `grantor`'s guardians.
+        fn accept_guardianship(origin, grantor: T::AccountId) -> Result {
+            let guardian = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            let mut pending = Self::pending_guardians(&grantor);
+            let index = pending.iter().position(|g| *g == guardian).ok_or("not invited")?;
+            pending.remove(index);
+            <PendingGuardians<T>>::insert(&grantor, pending);
+            <Guardians<T>>::mutate(&grantor, |guardians| guardians.push(guardian.clone()));
+            <GuardianActivity<T>>::insert(&grantor, &guardian, <system::Module<T>>::block_number());
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::GuardianAccepted(grantor, guardian));
+            Ok(())
+        }
+
+        /// Confirm, as one of `grantor`'s guardians, that you are still watching over the
+        /// trust. Guardians that do not within `GuardianActivityPeriod` blocks stop counting
+        /// and may be dropped with `expire_guardians`.
+        fn confirm_guardian_activity(origin, grantor: T::AccountId) -> Result {
+            let guardian = ensure_signed(origin)?;
+            ensure!(Self::is_active_guardian(&grantor, &guardian), "not a guardian");
+            <GuardianActivity<T>>::insert(&grantor, &guardian, <system::Module<T>>::block_number());
+            Ok(())
+        }
+
+        /// Drop the guardians of `grantor`'s trust that have not confirmed their activity in
+        /// time. Anyone may call this.
+        fn expire_guardians(origin, grantor: T::AccountId) -> Result {
+            ensure_signed(origin)?;
+            let expired: Vec<_> = Self::guardians(&grantor)
+                .into_iter()
+                .filter(|guardian| !Self::is_active_guardian(&grantor, guardian))
+                .collect();
+            ensure!(!expired.is_empty(), "no guardian has expired");
+            for guardian in expired {
+                Self::drop_guardian(&grantor, &guardian);
+                Self::deposit_event(RawEvent::GuardianExpired(grantor.clone(), guardian));
+            }
+            Ok(())
+        }
+
+        /// Attest, as one of `grantor`'s guardians, that the grantor can no longer look after
+        /// the trust, reserving `AttestationDeposit`. If the grantor clocks in before the trust
+        /// triggers, the attestation is dropped and the deposit slashed.
+        fn attest(origin, grantor: T::AccountId) -> Result {
+            let guardian = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            ensure!(Self::is_active_guardian(&grantor, &guardian), "not a guardian");
+            let mut attestations = Self::guardian_attestations(&grantor);
+            ensure!(!attestations.contains(&guardian), "already attested");
+            let deposit = T::AttestationDeposit::get();
+            T::Currency::reserve(&guardian, deposit)
+                .map_err(|_| "not enough free balance for attestation deposit")?;
+            <AttestationDeposits<T>>::insert(&grantor, &guardian, deposit);
+            attestations.push(guardian.clone());
+            <GuardianAttestations<T>>::insert(&grantor, attestations);
+            <GuardianActivity<T>>::insert(&grantor, &guardian, <system::Module<T>>::block_number());
+            Self::record(&grantor, AuditAction::Attested);
+            Self::deposit_event(RawEvent::GuardianAttested(grantor, guardian));
+            Ok(())
+        }
+
+        /// Pay every guardian whose attestation counts when the trust triggers `reward` of each
+        /// of its holdings. Zero pays nothing.
+        fn set_guardian_reward(origin, reward: Perbill) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::SetGuardianReward(reward))
+        }
+
+        /// Offer the trust to `new_account`, which takes it over once it confirms with
+        /// `accept_trust_ownership`.
+        fn transfer_trust_ownership(origin, new_account: T::AccountId) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            ensure!(new_account != grantor, "trust already belongs to this account");
+            ensure!(!Self::has_trust(&new_account), "new account already has a trust");
+            <PendingOwnershipTransfer<T>>::insert(&grantor, &new_account);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::TrustOwnershipProposed(grantor, new_account));
+            Ok(())
+        }
+
+        /// Take over the trust `grantor` offered to the caller, together with its funds,
+        /// beneficiaries, conditions and clock-in state. Unique items must be handed over to
+        /// the new account separately.
+        fn accept_trust_ownership(origin, grantor: T::AccountId) -> Result {
+            let new_account = ensure_signed(origin)?;
+            ensure!(
+                Self::pending_ownership_transfer(&grantor).as_ref() == Some(&new_account),
+                "trust was not offered to this account"
+            );
+            Self::ensure_active(&grantor)?;
+            ensure!(!Self::has_trust(&new_account), "new account already has a trust");
+            ensure!(
+                Self::trust_assets(&grantor)
+                    .iter()
+                    .all(|asset_id| !<BondedFunds<T>>::exists((grantor.clone(), *asset_id))),
+                "idle funds must be unbonded first"
+            );
+            let deposit = Self::ensure_storage_deposit(
+                &new_account,
+                Self::all_beneficiaries(&grantor).len(),
+                Self::trust_assets(&grantor).len(),
+            )?;
+
+            Self::move_trust(&grantor, &new_account);
+            Self::set_storage_deposit(&grantor, Zero::zero());
+            Self::set_storage_deposit(&new_account, deposit);
+            Self::deposit_event(RawEvent::TrustOwnershipTransferred(grantor, new_account));
+            Ok(())
+        }
+
+        /// Create a trust that the caller controls together with `co_grantors`. The trust has
+        /// its own account, which is configured through `propose_change`, funded through
+        /// `contribute` and kept alive by any of its grantors clocking in.
+        fn create_joint_trust(origin, co_grantors: Vec<T::AccountId>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            ensure!(!co_grantors.is_empty(), "a joint trust needs co-grantors");
+            let mut owners = vec![grantor];
+            for co_grantor in co_grantors {
+                ensure!(!owners.contains(&co_grantor), "duplicate grantor");
+                owners.push(co_grantor);
+            }
+            let index = Self::joint_trust_count();
+            let next_index = index.checked_add(1).ok_or("joint trust count overflow")?;
+
+            let trust = Self::joint_trust_account(index);
+            for owner in &owners {
+                <JointTrustsOf<T>>::mutate(owner, |trusts| trusts.push(trust.clone()));
+            }
+            <JointTrustOwners<T>>::insert(&trust, &owners);
+            <JointTrustCount>::put(next_index);
+            Self::record(&trust, AuditAction::Configured);
+            Self::deposit_event(RawEvent::JointTrustCreated(trust, owners));
+            Ok(())
+        }
+
+        /// Let a change to the joint trust take effect once `threshold` of its grantors
+        /// approved it, so that a lost key does not lock the trust. Dispatched as the trust
+        /// through `propose_change`.
+        fn set_approval_threshold(origin, threshold: u32) -> Result {
+            let trust = ensure_signed(origin)?;
+            let owners = Self::joint_trust_owners(&trust);
+            ensure!(!owners.is_empty(), "not a joint trust");
+            ensure!(
+                threshold > 0 && threshold as usize <= owners.len(),
+                "threshold must be between one and the number of grantors"
+            );
+            <ApprovalThresholds<T>>::insert(&trust, threshold);
+            Self::record(&trust, AuditAction::Configured);
+            Self::deposit_event(RawEvent::ApprovalThresholdSet(trust, threshold));
+            Ok(())
+        }
+
+        /// Propose `change` to the joint trust `trust`, replacing any pending change. It is
+        /// dispatched as the trust once enough grantors approved it.
+        fn propose_change(origin, trust: T::AccountId, change: Box<Call<T>>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_joint_owner(&trust, &grantor)?;
+            Self::deposit_event(RawEvent::ChangeProposed(trust.clone(), grantor.clone()));
+            Self::approve_or_execute(trust, *change, vec![grantor]);
+            Ok(())
+        }
+
+        /// Approve the pending change of the joint trust `trust`.
+        fn approve_change(origin, trust: T::AccountId) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_joint_owner(&trust, &grantor)?;
+            let (change, mut approvals) = Self::pending_change(&trust).ok_or("no pending change")?;
+            ensure!(!approvals.contains(&grantor), "change already approved");
+
+            approvals.push(grantor.clone());
+            Self::deposit_event(RawEvent::ChangeApproved(trust.clone(), grantor));
+            Self::approve_or_execute(trust, change, approvals);
+            Ok(())
+        }
+
+        /// Withdraw the pending change of the joint trust `trust`.
+        fn cancel_change(origin, trust: T::AccountId) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_joint_owner(&trust, &grantor)?;
+            ensure!(<PendingChanges<T>>::exists(&trust), "no pending change");
+            <PendingChanges<T>>::remove(&trust);
+            Self::deposit_event(RawEvent::ChangeCancelled(trust));
+            Ok(())
+        }
+
+        /// Appoint an attorney who may pay bills from the trust while `power.condition` holds,
+        /// or dismiss them with `None`.
+        fn set_power_of_attorney(
+            origin,
+            power: Option<PowerOfAttorney<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Moment>>
+        ) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            let attorney = match power {
+                Some(power) => {
+                    ensure!(
+                        power.condition != LivingSwitchCond::None || power.required_attestations > 0,
+                        "attorney could act immediately"
+                    );
+                    ensure!(!power.period.is_zero(), "attorney period should be non-zero");
+                    let attorney = power.attorney.clone();
+                    <PowersOfAttorney<T>>::insert(&grantor, power);
+                    Some(attorney)
+                }
+                None => {
+                    <PowersOfAttorney<T>>::remove(&grantor);
+                    None
+                }
+            };
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::AttorneySet(grantor, attorney));
+            Ok(())
+        }
+
+        /// Pay `amount` of `grantor`'s `asset_id` holding to `recipient` as their attorney.
+        fn attorney_pay(
+            origin,
+            grantor: T::AccountId,
+            asset_id: T::AssetId,
+            recipient: T::AccountId,
+            amount: BalanceOf<T>
+        ) -> Result {
+            let attorney = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), "amount should be non-zero");
+            Self::ensure_not_paused()?;
+            let power = Self::power_of_attorney(&grantor).ok_or("trust has no attorney")?;
+            ensure!(power.attorney == attorney, "not the trust's attorney");
+            Self::ensure_active(&grantor)?;
+            let condition_holds = power.condition == LivingSwitchCond::None
+                || Self::check_withdrawable(&grantor, &power.condition)?;
+            let attestations = Self::attestation_count(&grantor);
+            ensure!(
+                condition_holds && attestations >= power.required_attestations,
+                "grantor is not incapacitated"
+            );
+
+            let key = (grantor.clone(), asset_id);
+            let now = <system::Module<T>>::block_number();
+            let spent = Self::attorney_spending(&key);
+            let spending = Self::spend_in_period(now, spent, amount, power.cap, power.period)
+                .ok_or("attorney spending cap exceeded")?;
+            let free = Self::trust_balance(&grantor, &asset_id)
+                .saturating_sub(Self::bonded_funds(&key));
+            ensure!(amount <= free, "trust balance too low");
+
+            Self::transfer_out(&grantor, asset_id, recipient.clone(), amount)?;
+            <AttorneySpending<T>>::insert(&key, spending);
+            Self::record(&grantor, AuditAction::AttorneyPaid);
+            Self::deposit_event(RawEvent::AttorneyPaid(grantor, asset_id, recipient, amount));
+            Ok(())
+        }
+
+        /// Let the grantor take back up to `amount` of the trust's `asset_id` holding every
+        /// `period` blocks. A zero amount removes the allowance.
+        fn set_allowance(
+            origin,
+            asset_id: T::AssetId,
+            amount: BalanceOf<T>,
+            period: T::BlockNumber
+        ) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            if amount.is_zero() {
+                <Allowances<T>>::remove((grantor.clone(), asset_id));
+            } else {
+                ensure!(!period.is_zero(), "allowance period should be non-zero");
+                let allowance = Allowance {
+                    amount,
+                    period,
+                    spent: Zero::zero(),
+                    period_start: <system::Module<T>>::block_number(),
+                };
+                <Allowances<T>>::insert((grantor.clone(), asset_id), allowance);
+            }
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::AllowanceSet(grantor, asset_id, amount, period));
+            Ok(())
+        }
+
+        /// Take `amount` of the trust's `asset_id` holding back under the allowance.
+        fn spend_allowance(origin, asset_id: T::AssetId, amount: BalanceOf<T>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), "amount should be non-zero");
+            Self::ensure_active(&grantor)?;
+            let key = (grantor.clone(), asset_id);
+            let mut allowance = Self::allowance(&key).ok_or("no allowance")?;
+
+            let now = <system::Module<T>>::block_number();
+            let elapsed = now.saturating_sub(allowance.period_start);
+            if elapsed >= allowance.period {
+                allowance.period_start = now - elapsed % allowance.period;
+                allowance.spent = Zero::zero();
+            }
+            let spent = allowance
+                .spent
+                .checked_add(&amount)
+                .filter(|spent| *spent <= allowance.amount)
+                .ok_or("allowance exceeded")?;
+            let free = Self::trust_balance(&grantor, &asset_id)
+                .saturating_sub(Self::bonded_funds(&key));
+            ensure!(amount <= free, "trust balance too low");
+
+            Self::transfer_out(&grantor, asset_id, grantor.clone(), amount)?;
+            allowance.spent = spent;
+            <Allowances<T>>::insert(&key, allowance);
+            Self::record(&grantor, AuditAction::AllowanceSpent);
+            Self::deposit_event(RawEvent::AllowanceSpent(grantor, asset_id, amount));
+            Ok(())
+        }
+
+        /// Bond `amount` of the trust's `asset_id` holding while the trust is dormant.
+        fn bond_idle_funds(origin, asset_id: T::AssetId, amount: BalanceOf<T>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), "bond amount should be non-zero");
+            Self::ensure_active(&grantor)?;
+            ensure!(!Self::condition_holds(&grantor)?, "living switch already fired");
+            let bonded = Self::bonded_funds((grantor.clone(), asset_id))
+                .checked_add(&amount)
+                .ok_or("bonded amount overflow")?;
+            ensure!(
+                bonded <= Self::trust_balance(&grantor, &asset_id),
+                "bond amount exceeds trust balance"
+            );
+
+            T::OnIdleFunds::bond(&grantor, asset_id, amount)?;
+            <BondedFunds<T>>::insert((grantor.clone(), asset_id), bonded);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::IdleFundsBonded(grantor, asset_id, amount));
+            Ok(())
+        }
+
+        /// Release everything bonded for the trust's `asset_id` holding.
+        fn unbond_idle_funds(origin, asset_id: T::AssetId) -> Result {
+            let grantor = ensure_signed(origin)?;
+            ensure!(<BondedFunds<T>>::exists((grantor.clone(), asset_id)), "no bonded funds");
+            Self::release_idle_funds(&grantor, asset_id)
+        }
+
+        /// Choose who may trigger the trust.
+        fn set_trigger_policy(origin, policy: TriggerPolicy) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            <TriggerPolicies<T>>::insert(&grantor, policy);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::TriggerPolicySet(grantor, policy));
+            Ok(())
+        }
+
+        /// Appoint or remove the trustee of the trust.
+        fn set_trustee(origin, trustee: Option<T::AccountId>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            match trustee {
+                Some(ref trustee) => <Trustees<T>>::insert(&grantor, trustee),
+                None => <Trustees<T>>::remove(&grantor),
+            }
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::TrusteeSet(grantor, trustee));
+            Ok(())
+        }
+
+        /// Set the cut of each payout that goes to `destination`. Zero disables the fee.
+        fn set_distribution_fee(origin, fee: Perbill, destination: Option<T::AccountId>) -> Result {
+            ensure_root(origin)?;
+            <DistributionFee>::put(fee);
+            match destination {
+                Some(ref destination) => <FeeDestination<T>>::put(destination),
+                None => <FeeDestination<T>>::kill(),
+            }
+            Self::deposit_event(RawEvent::DistributionFeeSet(fee, destination));
+            Ok(())
+        }
+
+        /// Set the cut of each payout `EstateTax` sends to `account`. `None` stops the tax.
+        fn set_estate_tax(origin, rate: Perbill, account: Option<T::AccountId>) -> Result {
+            ensure_root(origin)?;
+            <EstateTaxRate>::put(rate);
+            match account {
+                Some(ref account) => <EstateTaxAccount<T>>::put(account),
+                None => <EstateTaxAccount<T>>::kill(),
+            }
+            Self::deposit_event(RawEvent::EstateTaxSet(rate, account));
+            Ok(())
+        }
+
+        /// Limit how much of `asset_id` a single trust may hold. `None` lifts the limit.
+        fn set_deposit_cap(origin, asset_id: T::AssetId, cap: Option<BalanceOf<T>>) -> Result {
+            ensure_root(origin)?;
+            match cap {
+                Some(cap) => <DepositCaps<T>>::insert(asset_id, cap),
+                None => <DepositCaps<T>>::remove(asset_id),
+            }
+            Self::deposit_event(RawEvent::DepositCapSet(asset_id, cap));
+            Ok(())
+        }
+
+        /// Halt or resume every withdrawal and claim. Clock-ins and configuration keep working.
+        fn set_distributions_paused(origin, paused: bool) -> Result {
+            ensure_root(origin)?;
+            <DistributionsPaused>::put(paused);
+            Self::deposit_event(RawEvent::DistributionsPausedSet(paused));
+            Ok(())
+        }
+
+        /// Offer `template` as a starting point for new trusts, or withdraw it with `None`.
+        fn set_trust_template(
+            origin,
+            template_id: u32,
+            template: Option<TrustTemplate<T::BlockNumber, T::Moment>>
+        ) -> Result {
+            ensure_root(origin)?;
+            match template {
+                Some(template) => {
+                    <TrustTemplates<T>>::insert(template_id, template);
+                    Self::deposit_event(RawEvent::TrustTemplateSet(template_id));
+                }
+                None => {
+                    <TrustTemplates<T>>::remove(template_id);
+                    Self::deposit_event(RawEvent::TrustTemplateRemoved(template_id));
+                }
+            }
+            Ok(())
+        }
+
+        /// Configure the trust from a template, naming `accounts` as its beneficiaries.
+        fn create_trust_from_template(origin, template_id: u32, accounts: Vec<T::AccountId>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::change_or_schedule(grantor, TrustChange::FromTemplate(template_id, accounts))
+        }
+
+        /// Bequeath the unique item `(class, instance)` to `beneficiary`.
+        fn assign_unique(
+            origin,
+            class: ClassIdOf<T>,
+            instance: InstanceIdOf<T>,
+            beneficiary: T::AccountId
+        ) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            ensure!(
+                T::UniqueAssets::owner(&class, &instance).as_ref() == Some(&grantor),
+                "grantor does not own the item"
+            );
+            let mut assignments = <UniqueAssignments<T>>::get(&grantor);
+            ensure!(
+                !assignments.iter().any(|(c, i, _)| *c == class && *i == instance),
+                "item already assigned"
+            );
+            assignments.push((class, instance, beneficiary.clone()));
+            <UniqueAssignments<T>>::insert(&grantor, assignments);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::UniqueAssigned(grantor, beneficiary, class, instance));
+            Ok(())
+        }
+
+        /// Remove the assignment of the unique item `(class, instance)`.
+        fn unassign_unique(origin, class: ClassIdOf<T>, instance: InstanceIdOf<T>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            let mut assignments = <UniqueAssignments<T>>::get(&grantor);
+            let before = assignments.len();
+            assignments.retain(|(c, i, _)| !(*c == class && *i == instance));
+            ensure!(assignments.len() < before, "item is not assigned");
+            <UniqueAssignments<T>>::insert(&grantor, assignments);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::UniqueUnassigned(grantor, class, instance));
+            Ok(())
+        }
+
+        /// Hand every unique item of `grantor`'s trust to its beneficiary. Items the grantor
+        /// no longer owns are dropped from the trust. Nothing is handed over unless every item
+        /// can be.
+        fn withdraw_unique(origin, grantor: T::AccountId) -> Result {
+            let caller = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+            if !Self::trigger_as(&caller, &grantor)? {
+                return Ok(());
+            }
+            Self::ensure_not_disputed(&grantor)?;
+            let assignments = Self::unique_assignments(&grantor);
+            ensure!(!assignments.is_empty(), "no unique items assigned");
+            let owned: Vec<_> = assignments
+                .into_iter()
+                .filter(|(class, instance, _)| {
+                    T::UniqueAssets::owner(class, instance).as_ref() == Some(&grantor)
+                })
+                .collect();
+            // Storage is not rolled back if a transfer fails, so check them all first.
+            for (class, instance, beneficiary) in &owned {
+                T::UniqueAssets::can_transfer(class, instance, beneficiary)?;
+            }
+            <UniqueAssignments<T>>::remove(&grantor);
+            Self::set_trust_state(&grantor, TrustState::Distributing);
+            for (class, instance, beneficiary) in owned {
+                T::UniqueAssets::transfer(&class, &instance, &beneficiary)?;
+                Self::deposit_event(RawEvent::UniquePayout(grantor.clone(), beneficiary, class, instance));
+            }
+            Ok(())
+        }
+
+        #[weight = WithdrawWeight::<T>::default()]
+        fn withdraw(origin, grantor: T::AccountId, asset_id: T::AssetId) -> Result {
+            let caller = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_not_streaming(&grantor, asset_id)?;
+            if !Self::trigger_as(&caller, &grantor)? {
+                return Ok(());
+            }
+            Self::distribute(&grantor, asset_id)
+        }
+
+        /// Perform a distribution listed in `ClaimableTrusts` as a keeper, for the keeper
+        /// bounty.
+        fn distribute_claimable(origin, grantor: T::AccountId, asset_id: T::AssetId) -> Result {
+            let keeper = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+            ensure!(
+                Self::claimable_trusts().contains(&(grantor.clone(), asset_id)),
+                "distribution is not claimable"
+            );
+            Self::distribute_for(&grantor, asset_id, Some(&keeper))
+        }
+
+        /// Set the cut of each distribution a keeper performs that goes to the keeper.
+        fn set_keeper_bounty(origin, bounty: Perbill) -> Result {
+            ensure_root(origin)?;
+            <KeeperBounty>::put(bounty);
+            Self::deposit_event(RawEvent::KeeperBountySet(bounty));
+            Ok(())
+        }
+
+        /// Remove every storage entry of `grantor`'s trust once it has paid out everything,
+        /// or was revoked or expired, refunding the deposits it holds. Anyone may close a
+        /// finished trust. The grantor's account can then set up a new trust.
+        fn close_trust(origin, grantor: T::AccountId) -> Result {
+            let _ = ensure_signed(origin)?;
+            Self::ensure_finished(&grantor)?;
+            Self::clear_trust(&grantor);
+            Self::deposit_event(RawEvent::TrustClosed(grantor));
+            Ok(())
+        }
+
+        /// Dispute, as one of its beneficiaries, how `grantor`'s triggered trust is to be split,
+        /// reserving `DisputeDeposit`. Distribution is frozen until the trustee or governance
+        /// resolves the dispute, or for `DisputeWindow` blocks at most.
+        fn raise_dispute(origin, grantor: T::AccountId) -> Result {
+            let beneficiary = ensure_signed(origin)?;
+            ensure!(
+                Self::trust_state(&grantor) == TrustState::Triggered,
+                "only undistributed triggered trusts can be disputed"
+            );
+            ensure!(Self::is_beneficiary(&grantor, &beneficiary), "not a beneficiary");
+            Self::ensure_not_disputed(&grantor)?;
+            let deposit = T::DisputeDeposit::get();
+            T::Currency::reserve(&beneficiary, deposit)
+                .map_err(|_| "not enough free balance for dispute deposit")?;
+            let expires_at = <system::Module<T>>::block_number() + T::DisputeWindow::get();
+            let dispute = Dispute { disputer: beneficiary.clone(), deposit, expires_at };
+            <Disputes<T>>::insert(&grantor, dispute);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::DisputeRaised(grantor, beneficiary, expires_at));
+            Ok(())
+        }
+
+        /// Resolve the dispute over `grantor`'s trust, as its trustee or governance. `None`
+        /// upholds the original split and slashes the disputer's deposit. A corrected split
+        /// replaces the beneficiaries of every asset and refunds the deposit.
+        fn resolve_dispute(
+            origin,
+            grantor: T::AccountId,
+            corrected: Option<Vec<BeneficiaryShare<T::AccountId>>>
+        ) -> Result {
+            if ensure_root(origin.clone()).is_err() {
+                let caller = ensure_signed(origin)?;
+                ensure!(
+                    Self::trustee(&grantor).as_ref() == Some(&caller),
+                    "only the trustee may resolve disputes"
+                );
+            }
+            let dispute = Self::dispute(&grantor).ok_or("trust is not disputed")?;
+            match corrected {
+                Some(ref shares) => {
+                    ensure!(Self::total_weight(shares) > 0, "corrected split has no weight");
+                    Self::ensure_distinct(shares)?;
+                    let previous = Self::all_beneficiaries(&grantor);
+                    Self::put_beneficiaries(&grantor, shares);
+                    <ShareDenominator<T>>::remove(&grantor);
+                    <ActiveTier<T>>::remove(&grantor);
+                    <AssetBeneficiaries<T>>::remove(&grantor);
+                    let current = Self::all_beneficiaries(&grantor);
+                    Self::update_beneficiary_index(&grantor, &previous, &current);
+                    Self::refund_storage_deposit(&grantor);
+                    T::Currency::unreserve(&dispute.disputer, dispute.deposit);
+                }
+                None => {
+                    let (imbalance, _) =
+                        T::Currency::slash_reserved(&dispute.disputer, dispute.deposit);
+                    T::Slashed::on_unbalanced(imbalance);
+                }
+            }
+            <Disputes<T>>::remove(&grantor);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::DisputeResolved(grantor, corrected));
+            Ok(())
+        }
+
+        /// Collect the part of a streamed share that has accrued so far.
+        fn claim_share(origin, grantor: T::AccountId, asset_id: T::AssetId) -> Result {
+            let beneficiary = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_unlocked(&grantor, &beneficiary)?;
+            Self::ensure_verified(&grantor, &beneficiary)?;
+            let key = (grantor.clone(), asset_id, beneficiary.clone());
+            let mut stream = Self::stream(&key).ok_or("no streamed share")?;
+            let now = <system::Module<T>>::block_number();
+            let amount = Self::accrued(&stream, now);
+            ensure!(!amount.is_zero(), "nothing to claim yet");
+            let destination = Self::destination_of(&grantor, &beneficiary)?;
+
+            Self::transfer_out(&grantor, asset_id, destination, amount)?;
+            stream.remaining -= amount;
+            if stream.remaining.is_zero() {
+                <Streams<T>>::remove(&key);
+                let open = Self::open_streams((grantor.clone(), asset_id)).saturating_sub(1);
+                if open == 0 {
+                    <OpenStreams<T>>::remove((grantor.clone(), asset_id));
+                } else {
+                    <OpenStreams<T>>::insert((grantor.clone(), asset_id), open);
+                }
+            } else {
+                stream.since = now;
+                <Streams<T>>::insert(&key, stream);
+            }
+            Self::record(&grantor, AuditAction::Claimed);
+            Self::deposit_event(RawEvent::Payout(grantor, beneficiary, asset_id, amount));
+            Ok(())
+        }
+
+        /// Renounce the caller's share of `grantor`'s trust before it is distributed. Their
+        /// weight goes to the remaining beneficiaries, or to the fallback account if none
+        /// remain.
+        fn disclaim_share(origin, grantor: T::AccountId) -> Result {
+            let beneficiary = ensure_signed(origin)?;
+            ensure!(Self::is_beneficiary(&grantor, &beneficiary), "not a beneficiary");
+            match Self::trust_state(&grantor) {
+                TrustState::Active | TrustState::Triggered => (),
+                _ => return Err("trust is already distributing"),
+            }
+
+            let previous = Self::all_beneficiaries(&grantor);
+            let fallback = Self::fallback_account(&grantor).filter(|f| *f != beneficiary);
+            if let Some((_, weight)) = Self::beneficiary_share(&grantor, &beneficiary) {
+                Self::take_beneficiary(&grantor, &beneficiary);
+                let remaining = Self::beneficiaries(&grantor);
+                if remaining.is_empty() {
+                    if let Some(fallback) = fallback.clone() {
+                        Self::push_beneficiary(&grantor, fallback, weight);
+                    }
+                } else if Self::share_denominator(&grantor) != 0 {
+                    let total = Self::total_weight(&remaining).saturating_add(weight);
+                    Self::put_beneficiaries(&grantor, &Self::rescale(remaining, total));
+                }
+            }
+            if <Tiers<T>>::exists(&grantor) {
+                <Tiers<T>>::mutate(&grantor, |tiers| {
+                    for tier in tiers.iter_mut() {
+                        Self::drop_share(&mut tier.beneficiaries, &beneficiary, &fallback);
+                    }
+                });
+            }
+            if <AssetBeneficiaries<T>>::exists(&grantor) {
+                <AssetBeneficiaries<T>>::mutate(&grantor, |overrides| {
+                    for (_, shares) in overrides.iter_mut() {
+                        Self::drop_share(shares, &beneficiary, &fallback);
+                    }
+                });
+            }
+            Self::update_beneficiary_index(&grantor, &previous, &Self::all_beneficiaries(&grantor));
+            Self::refund_storage_deposit(&grantor);
+            Self::record(&grantor, AuditAction::Disclaimed);
+            Self::deposit_event(RawEvent::ShareDisclaimed(grantor, beneficiary));
+            Ok(())
+        }
+
+        /// Require `beneficiary` to present the preimage of `hash` before their share is paid
+        /// to them, or lift the requirement with `None`. The grantor hands the preimage over
+        /// off-chain, e.g. in a sealed letter.
+        fn set_claim_lock(origin, beneficiary: T::AccountId, hash: Option<T::Hash>) -> Result {
+            let grantor = ensure_signed(origin)?;
+            Self::ensure_active(&grantor)?;
+            match hash {
+                Some(ref hash) => <ClaimLocks<T>>::insert(&grantor, &beneficiary, hash),
+                None => <ClaimLocks<T>>::remove(&grantor, &beneficiary),
+            }
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::ClaimLockSet(grantor, beneficiary, hash));
+            Ok(())
+        }
+
+        /// Lift the claim lock `grantor` put on the caller's share by presenting its preimage.
+        /// Shares held because of the lock can then be collected with `claim_held_share`.
+        fn unlock_claim(origin, grantor: T::AccountId, preimage: Vec<u8>) -> Result {
+            let beneficiary = ensure_signed(origin)?;
+            let hash = Self::claim_lock(&grantor, &beneficiary).ok_or("claim is not locked")?;
+            ensure!(T::Hashing::hash(&preimage) == hash, "wrong preimage");
+            <ClaimLocks<T>>::remove(&grantor, &beneficiary);
+            Self::record(&grantor, AuditAction::Claimed);
+            Self::deposit_event(RawEvent::ClaimUnlocked(grantor, beneficiary));
+            Ok(())
+        }
+
+        /// Restrict `beneficiary`'s share of `grantor`'s trust to the accounts in `whitelist`,
+        /// or lift the restriction with an empty list. The grantor may do so while the trust
+        /// is active, and the trustee once it has been triggered.
+        fn set_payout_whitelist(
+            origin,
+            grantor: T::AccountId,
+            beneficiary: T::AccountId,
+            whitelist: Vec<T::AccountId>
+        ) -> Result {
+            let caller = ensure_signed(origin)?;
+            match Self::trust_state(&grantor) {
+                TrustState::Active => {
+                    ensure!(caller == grantor, "only the grantor may change the whitelist")
+                }
+                TrustState::Closed => return Err("trust is closed"),
+                _ => ensure!(
+                    Self::trustee(&grantor).as_ref() == Some(&caller),
+                    "only the trustee may change the whitelist"
+                ),
+            }
+            if whitelist.is_empty() {
+                <PayoutWhitelists<T>>::remove(&grantor, &beneficiary);
+            } else {
+                <PayoutWhitelists<T>>::insert(&grantor, &beneficiary, &whitelist);
+            }
+            Self::record(&grantor, AuditAction::WhitelistChanged);
+            Self::deposit_event(RawEvent::PayoutWhitelistSet(grantor, beneficiary, whitelist));
+            Ok(())
+        }
+
+        /// Have the caller's share of `grantor`'s trust sent to `destination`, which must be on
+        /// their whitelist.
+        fn set_payout_destination(
+            origin,
+            grantor: T::AccountId,
+            destination: T::AccountId
+        ) -> Result {
+            let beneficiary = ensure_signed(origin)?;
+            ensure!(
+                Self::payout_whitelist(&grantor, &beneficiary).contains(&destination),
+                "destination not whitelisted"
+            );
+            <PayoutDestinations<T>>::insert(&grantor, &beneficiary, &destination);
+            Self::deposit_event(RawEvent::PayoutDestinationSet(grantor, beneficiary, destination));
+            Ok(())
+        }
+
+        /// Collect a share that was held until the caller's identity is verified, their
+        /// claim is unlocked or they chose a whitelisted destination.
+        fn claim_held_share(origin, grantor: T::AccountId, asset_id: T::AssetId) -> Result {
+            let beneficiary = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_unlocked(&grantor, &beneficiary)?;
+            Self::ensure_eligible(&grantor, &beneficiary)?;
+            Self::ensure_verified(&grantor, &beneficiary)?;
+            let destination = Self::destination_of(&grantor, &beneficiary)?;
+            Self::release_held_share(grantor, asset_id, beneficiary, destination)
+        }
+
+        /// Pay a share that has been held for longer than `UnclaimedTimeout` to the trust's
+        /// fallback account.
+        fn release_unclaimed_share(
+            origin,
+            grantor: T::AccountId,
+            asset_id: T::AssetId,
+            beneficiary: T::AccountId
+        ) -> Result {
+            ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+            let key = (grantor.clone(), asset_id, beneficiary.clone());
+            let (_, held_from) = Self::held_share(&key).ok_or("no held share")?;
+            let now = <system::Module<T>>::block_number();
+            ensure!(
+                now.saturating_sub(held_from) >= T::UnclaimedTimeout::get(),
+                "share is not unclaimed yet"
+            );
+            let fallback = Self::fallback_account(&grantor).ok_or("no fallback account")?;
+            Self::release_held_share(grantor, asset_id, beneficiary, fallback)
+        }
+
+        // The signature could also look like: `fn on_initialize()`
+        fn on_initialize(n: T::BlockNumber) {
+            Self::expire_trusts(n);
+            Self::make_top_ups(n);
+            Self::process_distribution_queue();
+        }
+
+        // The signature could also look like: `fn on_finalize()`
+        fn on_finalize(n: T::BlockNumber) {
+            // Trigger every armed trust whose condition has become true.
+            let (mut fired, mut armed): (Vec<_>, Vec<_>) = Self::armed_trusts()
+                .into_iter()
+                .partition(|grantor| Self::condition_holds(grantor).unwrap_or(false));
+            // Trusts expiring now whose configuration is not witnessed yet are checked every
+            // block from now on.
+            let expiring = <ExpiringAt<T>>::take(n);
+            for grantor in &expiring {
+                <ExpiryBlock<T>>::remove(grantor);
+            }
+            let (due, unwitnessed): (Vec<_>, Vec<_>) = expiring
+                .into_iter()
+                .partition(|grantor| Self::condition_holds(grantor).unwrap_or(false));
+            if !fired.is_empty() || !unwitnessed.is_empty() {
+                armed.extend(unwitnessed);
+                <ArmedTrusts<T>>::put(armed);
+            }
+            fired.extend(due);
+            for grantor in fired {
+                Self::trigger(&grantor);
+            }
+        }
+
+        // A runtime code run after every block and have access to extended set of APIs.
+        //
+        // For instance you can generate extrinsics for the upcoming produced block.
+        fn offchain_worker(now: T::BlockNumber) {
+            // Clock in for every grantor that authorized one of our local heartbeat keys.
+            Self::send_heartbeats(now);
+        }
+    }
 }
 
-// The main implementation block for the module. Functions here fall into three broad
-// categories:
-// - Public interface. These are functions that are `pub` and generally fall into inspector
-// functions that do not write to storage and operation functions that do.
-// - Private functions. These are your usual private utilities unavailable to other modules.
-impl<T: Trait> Module<T> {
+// The main implementation block for the module. Functions here fall into three broad
+// categories:
+// - Public interface. These are functions that are `pub` and generally fall into inspector
+// functions that do not write to storage and operation functions that do.
+// - Private functions. These are your usual private utilities unavailable to other modules.
+impl<T: Trait> Module<T> {
+    /// The account holding the funds of all trusts.
+    pub fn account_id() -> T::AccountId {
+        MODULE_ID.into_account()
+    }
+
+    /// The account of the `index`th joint trust. Derived by hashing rather than as a sub
+    /// account of `MODULE_ID`, which for index 0 would be the pot itself.
+    pub fn joint_trust_account(index: u32) -> T::AccountId {
+        let seed = T::Hashing::hash_of(&(MODULE_ID.0, b"joint", index));
+        T::AccountId::decode(&mut seed.as_ref()).unwrap_or_default()
+    }
+
+    /// Overview of `grantor`'s trust.
+    pub fn trust_status(
+        grantor: T::AccountId,
+    ) -> TrustStatus<T::AccountId, T::BlockNumber, T::Moment, T::Hash> {
+        TrustStatus {
+            state: Self::trust_state(&grantor),
+            condition: Self::living_switch_cond(&grantor),
+            beneficiaries: Self::beneficiaries(&grantor),
+            trustee: Self::trustee(&grantor),
+            last_clock_in: Self::last_clock_in(&grantor),
+            metadata: Self::metadata(&grantor),
+        }
+    }
+
+    /// What each beneficiary would receive, net of the distribution fee, if `grantor`'s
+    /// `asset_id` holding were distributed now. Empty if nothing could be paid out.
+    pub fn quote_distribution(
+        grantor: T::AccountId,
+        asset_id: T::AssetId,
+    ) -> Vec<(T::AccountId, BalanceOf<T>)> {
+        if <OpenStreams<T>>::exists((grantor.clone(), asset_id)) {
+            return Vec::new();
+        }
+        let tier = match Self::trust_state(&grantor) {
+            TrustState::Active => Self::firing_tier(&grantor).unwrap_or(None),
+            _ => Self::active_tier(&grantor),
+        };
+        let (beneficiaries, denominator) = Self::payout_plan(&grantor, asset_id, tier);
+        let total = Self::distributable(&grantor, asset_id);
+        Self::calc_payouts(&grantor, total, &beneficiaries, denominator)
+            .map(|payouts| Self::deduct_distribution_fee(payouts).0)
+            .map(|payouts| Self::levy_distribution(&grantor, asset_id, payouts).0)
+            .unwrap_or_default()
+    }
+
+    /// Statistics over the recent clock-ins of `grantor`.
+    pub fn clock_in_stats(grantor: T::AccountId) -> ClockInStats<T::BlockNumber> {
+        let history = Self::clock_in_history(&grantor);
+        let now = <system::Module<T>>::block_number();
+        let mut stats = ClockInStats::default();
+        stats.count = history.len() as u32;
+
+        let mut total: T::BlockNumber = Zero::zero();
+        for pair in history.windows(2) {
+            let gap = pair[1].0.saturating_sub(pair[0].0);
+            total = total.saturating_add(gap);
+            stats.longest_gap = stats.longest_gap.max(gap);
+        }
+        if stats.count > 1 {
+            stats.average_interval = total / T::BlockNumber::from(stats.count - 1);
+        }
+        if let Some((last, _)) = history.last() {
+            stats.longest_gap = stats.longest_gap.max(now.saturating_sub(*last));
+        }
+        stats
+    }
+
+    /// Apply `change` now, or schedule it if the grantor has a change delay. Scheduled changes
+    /// are only checked when they are enacted.
+    fn change_or_schedule(
+        grantor: T::AccountId,
+        change: TrustChangeOf<T>,
+    ) -> Result {
+        let now = <system::Module<T>>::block_number();
+        let rate_limited = change.is_rate_limited();
+        if rate_limited {
+            if let Some(last) = Self::last_config_change(&grantor) {
+                ensure!(
+                    now >= last + T::ConfigChangeCooldown::get(),
+                    "configuration changed too recently"
+                );
+            }
+        }
+        let delay = Self::change_delay(&grantor);
+        if delay.is_zero() {
+            Self::apply_change(grantor.clone(), change)?;
+        } else {
+            Self::ensure_active(&grantor)?;
+            let due = now + delay;
+            <TimelockedChanges<T>>::mutate(&grantor, |changes| changes.push((due, change)));
+            Self::deposit_event(RawEvent::ChangeScheduled(grantor.clone(), due));
+        }
+        if rate_limited {
+            <LastConfigChange<T>>::insert(&grantor, now);
+        }
+        Ok(())
+    }
+
+    fn apply_change(
+        grantor: T::AccountId,
+        change: TrustChangeOf<T>,
+    ) -> Result {
+        match change {
+            TrustChange::SetBeneficiaries(option) => Self::do_set_beneficiaries(grantor, option),
+            TrustChange::SetBeneficiariesPercent(shares) => {
+                Self::do_set_beneficiaries_percent(grantor, shares)
+            }
+            TrustChange::AddBeneficiary(beneficiary, weight) => {
+                Self::do_add_beneficiary(grantor, beneficiary, weight)
+            }
+            TrustChange::RemoveBeneficiary(beneficiary) => {
+                Self::do_remove_beneficiary(grantor, beneficiary)
+            }
+            TrustChange::UpdateBeneficiaryWeight(beneficiary, weight) => {
+                Self::do_update_beneficiary_weight(grantor, beneficiary, weight)
+            }
+            TrustChange::ClearBeneficiaries => Self::do_clear_beneficiaries(grantor),
+            TrustChange::SetLivingSwitchCondition(condition) => {
+                Self::do_set_living_switch_condition(grantor, condition)
+            }
+            TrustChange::ResetLivingSwitchCondition => {
+                Self::do_reset_living_switch_condition(grantor)
+            }
+            TrustChange::ClearClockInHistory => Self::do_clear_clock_in_history(grantor),
+            TrustChange::SetTiers(tiers) => Self::do_set_tiers(grantor, tiers),
+            TrustChange::FromTemplate(template_id, accounts) => {
+                Self::do_create_trust_from_template(grantor, template_id, accounts)
+            }
+            TrustChange::SetChangeDelay(delay) => Self::do_set_change_delay(grantor, delay),
+            TrustChange::SetAssetBeneficiaries(asset_id, shares) => {
+                Self::do_set_asset_beneficiaries(grantor, asset_id, shares)
+            }
+            TrustChange::SetExpiry(duration, reversion) => {
+                Self::do_set_expiry(grantor, duration, reversion)
+            }
+            TrustChange::SetGuardianReward(reward) => Self::do_set_guardian_reward(grantor, reward),
+        }
+    }
+
+    fn do_set_beneficiaries(
+        grantor: T::AccountId,
+        option: Vec<BeneficiaryShare<T::AccountId>>,
+    ) -> Result {
+        Self::ensure_active(&grantor)?;
+        Self::ensure_distinct(&option)?;
+        let previous = Self::all_beneficiaries(&grantor);
+        let plain = Self::beneficiary_count(&grantor) as usize;
+        let entries = previous.len() - plain + option.len();
+        let deposit = Self::ensure_beneficiary_deposit(&grantor, entries)?;
+        Self::put_beneficiaries(&grantor, &option);
+        Self::set_storage_deposit(&grantor, deposit);
+        <ShareDenominator<T>>::remove(&grantor);
+        Self::update_beneficiary_index(&grantor, &previous, &Self::all_beneficiaries(&grantor));
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::BeneficiariesSet(grantor, option));
+        Ok(())
+    }
+
+    fn do_set_beneficiaries_percent(
+        grantor: T::AccountId,
+        shares: Vec<(T::AccountId, Perbill)>,
+    ) -> Result {
+        Self::ensure_active(&grantor)?;
+        let option: Vec<_> = shares
+            .into_iter()
+            .map(|(address, share)| BeneficiaryShare { address, weight: share.deconstruct().into() })
+            .collect();
+        let denominator: u64 = Perbill::one().deconstruct().into();
+        ensure!(Self::total_weight(&option) <= denominator, "shares exceed 100%");
+        Self::ensure_distinct(&option)?;
+        let previous = Self::all_beneficiaries(&grantor);
+        let plain = Self::beneficiary_count(&grantor) as usize;
+        let entries = previous.len() - plain + option.len();
+        let deposit = Self::ensure_beneficiary_deposit(&grantor, entries)?;
+
+        Self::put_beneficiaries(&grantor, &option);
+        Self::set_storage_deposit(&grantor, deposit);
+        <ShareDenominator<T>>::insert(&grantor, denominator);
+        Self::update_beneficiary_index(&grantor, &previous, &Self::all_beneficiaries(&grantor));
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::BeneficiariesSet(grantor, option));
+        Ok(())
+    }
+
+    fn do_add_beneficiary(grantor: T::AccountId, beneficiary: T::AccountId, weight: u64) -> Result {
+        Self::ensure_active(&grantor)?;
+        ensure!(
+            !<BeneficiaryShares<T>>::exists(&grantor, &beneficiary),
+            "already a beneficiary"
+        );
+        let total = Self::total_beneficiary_weight(&grantor).saturating_add(weight);
+        Self::ensure_within_denominator(&grantor, total)?;
+        let entries = Self::all_beneficiaries(&grantor).len() + 1;
+        let deposit = Self::ensure_beneficiary_deposit(&grantor, entries)?;
+        <TrustsByBeneficiary<T>>::mutate(&beneficiary, |grantors| {
+            if !grantors.contains(&grantor) {
+                grantors.push(grantor.clone());
+            }
+        });
+        Self::push_beneficiary(&grantor, beneficiary.clone(), weight);
+        Self::set_storage_deposit(&grantor, deposit);
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::BeneficiaryAdded(grantor, beneficiary, weight));
+        Ok(())
+    }
+
+    fn do_remove_beneficiary(grantor: T::AccountId, beneficiary: T::AccountId) -> Result {
+        Self::ensure_active(&grantor)?;
+        ensure!(
+            <BeneficiaryShares<T>>::exists(&grantor, &beneficiary),
+            "not a beneficiary"
+        );
+        let previous = Self::all_beneficiaries(&grantor);
+        Self::take_beneficiary(&grantor, &beneficiary);
+        Self::update_beneficiary_index(&grantor, &previous, &Self::all_beneficiaries(&grantor));
+        Self::refund_storage_deposit(&grantor);
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::BeneficiaryRemoved(grantor, beneficiary));
+        Ok(())
+    }
+
+    fn do_update_beneficiary_weight(
+        grantor: T::AccountId,
+        beneficiary: T::AccountId,
+        weight: u64,
+    ) -> Result {
+        Self::ensure_active(&grantor)?;
+        let (index, old_weight) =
+            Self::beneficiary_share(&grantor, &beneficiary).ok_or("not a beneficiary")?;
+        let total = Self::total_beneficiary_weight(&grantor)
+            .saturating_sub(old_weight)
+            .saturating_add(weight);
+        Self::ensure_within_denominator(&grantor, total)?;
+        <BeneficiaryShares<T>>::insert(&grantor, &beneficiary, (index, weight));
+        <TotalBeneficiaryWeight<T>>::insert(&grantor, total);
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::BeneficiaryWeightUpdated(grantor, beneficiary, weight));
+        Ok(())
+    }
+
+    fn do_clear_beneficiaries(grantor: T::AccountId) -> Result {
+        Self::ensure_active(&grantor)?;
+        let previous = Self::all_beneficiaries(&grantor);
+        Self::clear_beneficiary_shares(&grantor);
+        <ShareDenominator<T>>::remove(&grantor);
+        Self::update_beneficiary_index(&grantor, &previous, &Self::all_beneficiaries(&grantor));
+        Self::refund_storage_deposit(&grantor);
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::BeneficiariesCleared(grantor));
+        Ok(())
+    }
+
+    fn do_set_living_switch_condition(
+        grantor: T::AccountId,
+        condition: LivingSwitchCond<T::BlockNumber, T::Moment>,
+    ) -> Result {
+        Self::ensure_active(&grantor)?;
+        <LivingSwitchConds<T>>::insert(&grantor, &condition);
+        Self::update_armed(&grantor);
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::LivingSwitchCondSet(grantor, condition));
+        Ok(())
+    }
+
+    fn do_reset_living_switch_condition(grantor: T::AccountId) -> Result {
+        Self::ensure_active(&grantor)?;
+        <LivingSwitchConds<T>>::remove(&grantor);
+        Self::update_armed(&grantor);
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::LivingSwitchCondReset(grantor));
+        Ok(())
+    }
+
+    fn do_clear_clock_in_history(grantor: T::AccountId) -> Result {
+        Self::ensure_active(&grantor)?;
+        <LastClockIn<T>>::remove(&grantor);
+        <LastClockInMoment<T>>::remove(&grantor);
+        <ClockInHistory<T>>::remove(&grantor);
+        if <ExpiryBlock<T>>::exists(&grantor) {
+            Self::update_armed(&grantor);
+        }
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::ClockInHistoryCleared(grantor));
+        Ok(())
+    }
+
+    fn do_set_tiers(
+        grantor: T::AccountId,
+        tiers: Vec<Tier<T::AccountId, T::BlockNumber, T::Moment>>,
+    ) -> Result {
+        Self::ensure_active(&grantor)?;
+        for tier in &tiers {
+            ensure!(!tier.beneficiaries.is_empty(), "tier has no beneficiaries");
+            ensure!(
+                tier.condition != LivingSwitchCond::None || tier.required_attestations > 0,
+                "tier would fire immediately"
+            );
+        }
+        let count = tiers.len() as u32;
+        let previous = Self::all_beneficiaries(&grantor);
+        let entries = |tiers: &[Tier<T::AccountId, T::BlockNumber, T::Moment>]| {
+            tiers.iter().map(|tier| tier.beneficiaries.len()).sum::<usize>()
+        };
+        let stored = previous.len() - entries(&Self::tiers(&grantor)) + entries(&tiers);
+        let deposit = Self::ensure_beneficiary_deposit(&grantor, stored)?;
+        if tiers.is_empty() {
+            <Tiers<T>>::remove(&grantor);
+        } else {
+            <Tiers<T>>::insert(&grantor, tiers);
+        }
+        Self::set_storage_deposit(&grantor, deposit);
+        Self::update_beneficiary_index(&grantor, &previous, &Self::all_beneficiaries(&grantor));
+        Self::update_armed(&grantor);
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::TiersSet(grantor, count));
+        Ok(())
+    }
+
+    fn do_create_trust_from_template(
+        grantor: T::AccountId,
+        template_id: u32,
+        accounts: Vec<T::AccountId>,
+    ) -> Result {
+        Self::ensure_active(&grantor)?;
+        let template = Self::trust_template(template_id).ok_or("no such template")?;
+        ensure!(!accounts.is_empty(), "no beneficiaries given");
+        let shares: Vec<_> = if template.weights.is_empty() {
+            accounts.into_iter().map(|address| BeneficiaryShare { address, weight: 1 }).collect()
+        } else {
+            ensure!(
+                template.weights.len() == accounts.len(),
+                "template expects a different number of beneficiaries"
+            );
+            accounts
+                .into_iter()
+                .zip(template.weights)
+                .map(|(address, weight)| BeneficiaryShare { address, weight })
+                .collect()
+        };
+        Self::ensure_distinct(&shares)?;
+        let previous = Self::all_beneficiaries(&grantor);
+        let plain = Self::beneficiary_count(&grantor) as usize;
+        let entries = previous.len() - plain + shares.len();
+        let deposit = Self::ensure_beneficiary_deposit(&grantor, entries)?;
+
+        Self::put_beneficiaries(&grantor, &shares);
+        Self::set_storage_deposit(&grantor, deposit);
+        <ShareDenominator<T>>::remove(&grantor);
+        Self::update_beneficiary_index(&grantor, &previous, &Self::all_beneficiaries(&grantor));
+        <LivingSwitchConds<T>>::insert(&grantor, &template.condition);
+        Self::update_armed(&grantor);
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::BeneficiariesSet(grantor.clone(), shares));
+        Self::deposit_event(RawEvent::LivingSwitchCondSet(grantor, template.condition));
+        Ok(())
+    }
+
+    fn do_set_asset_beneficiaries(
+        grantor: T::AccountId,
+        asset_id: T::AssetId,
+        shares: Option<Vec<BeneficiaryShare<T::AccountId>>>,
+    ) -> Result {
+        Self::ensure_active(&grantor)?;
+        if let Some(shares) = &shares {
+            ensure!(!shares.is_empty(), "no beneficiaries given");
+            Self::ensure_distinct(shares)?;
+        }
+        let previous = Self::all_beneficiaries(&grantor);
+        let mut overrides = Self::asset_beneficiaries(&grantor);
+        let replaced: usize = overrides
+            .iter()
+            .filter(|(asset, _)| *asset == asset_id)
+            .map(|(_, shares)| shares.len())
+            .sum();
+        let added = shares.as_ref().map_or(0, |shares| shares.len());
+        let entries = previous.len() - replaced + added;
+        let deposit = Self::ensure_beneficiary_deposit(&grantor, entries)?;
+        overrides.retain(|(asset, _)| *asset != asset_id);
+        if let Some(shares) = shares {
+            overrides.push((asset_id, shares));
+        }
+        if overrides.is_empty() {
+            <AssetBeneficiaries<T>>::remove(&grantor);
+        } else {
+            <AssetBeneficiaries<T>>::insert(&grantor, overrides);
+        }
+        Self::set_storage_deposit(&grantor, deposit);
+        Self::update_beneficiary_index(&grantor, &previous, &Self::all_beneficiaries(&grantor));
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::AssetBeneficiariesSet(grantor, asset_id));
+        Ok(())
+    }
+
+    fn do_set_expiry(
+        grantor: T::AccountId,
+        duration: Option<T::BlockNumber>,
+        reversion: Option<T::AccountId>,
+    ) -> Result {
+        Self::ensure_active(&grantor)?;
+        let expires_at = match duration {
+            Some(duration) => {
+                ensure!(!duration.is_zero(), "trust duration should be non-zero");
+                let expires_at = <system::Module<T>>::block_number() + duration;
+                <ExpiriesDue<T>>::mutate(expires_at, |due| due.push(grantor.clone()));
+                let expiry = TrustExpiry { duration, expires_at, reversion };
+                <TrustExpiries<T>>::insert(&grantor, expiry);
+                Some(expires_at)
+            }
+            None => {
+                <TrustExpiries<T>>::remove(&grantor);
+                None
+            }
+        };
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::TrustExpirySet(grantor, expires_at));
+        Ok(())
+    }
+
+    fn do_set_change_delay(grantor: T::AccountId, delay: T::BlockNumber) -> Result {
+        Self::ensure_active(&grantor)?;
+        if delay.is_zero() {
+            <ChangeDelays<T>>::remove(&grantor);
+        } else {
+            <ChangeDelays<T>>::insert(&grantor, delay);
+        }
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::ChangeDelaySet(grantor, delay));
+        Ok(())
+    }
+
+    fn do_set_guardian_reward(grantor: T::AccountId, reward: Perbill) -> Result {
+        Self::ensure_active(&grantor)?;
+        if reward.is_zero() {
+            <GuardianRewards<T>>::remove(&grantor);
+        } else {
+            <GuardianRewards<T>>::insert(&grantor, reward);
+        }
+        Self::record(&grantor, AuditAction::Configured);
+        Self::deposit_event(RawEvent::GuardianRewardSet(grantor, reward));
+        Ok(())
+    }
+
+    /// Close the untriggered trusts expiring at block `n` and revert their funds.
+    fn expire_trusts(n: T::BlockNumber) {
+        for grantor in <ExpiriesDue<T>>::take(n) {
+            match Self::trust_expiry(&grantor) {
+                Some(ref expiry) if expiry.expires_at == n => (),
+                // The trust was renewed or its expiry changed since this one was scheduled.
+                _ => continue,
+            }
+            let expiry = <TrustExpiries<T>>::take(&grantor).expect("checked above; qed");
+            if Self::trust_state(&grantor) != TrustState::Active {
+                continue;
+            }
+            let recipient = expiry.reversion.unwrap_or_else(|| grantor.clone());
+            for asset_id in Self::trust_assets(&grantor) {
+                let bonded = <BondedFunds<T>>::exists((grantor.clone(), asset_id));
+                if bonded && Self::release_idle_funds(&grantor, asset_id).is_err() {
+                    continue;
+                }
+                let amount = Self::trust_balance(&grantor, &asset_id);
+                let _ = Self::transfer_out(&grantor, asset_id, recipient.clone(), amount);
+            }
+            Self::disarm(&grantor);
+            Self::release_attestations(&grantor, false);
+            Self::set_trust_state(&grantor, TrustState::Closed);
+            Self::record(&grantor, AuditAction::Configured);
+            Self::deposit_event(RawEvent::TrustExpired(grantor, recipient));
+        }
+    }
+
+    /// Make the recurring deposits due at block `n` and schedule the next ones.
+    fn make_top_ups(n: T::BlockNumber) {
+        for (grantor, asset_id) in <TopUpsDue<T>>::take(n) {
+            let mut top_ups = Self::auto_top_ups(&grantor);
+            let position = top_ups
+                .iter()
+                .position(|(asset, top_up)| *asset == asset_id && top_up.next == n);
+            let index = match position {
+                Some(index) => index,
+                // The top-up was changed or stopped since this one was scheduled.
+                None => continue,
+            };
+            let amount = top_ups[index].1.amount;
+            let event = match Self::credit_trust(&grantor, &grantor, asset_id, amount) {
+                Ok(()) => RawEvent::Deposited(grantor.clone(), asset_id, amount),
+                Err(_) => RawEvent::TopUpFailed(grantor.clone(), asset_id, amount),
+            };
+            Self::deposit_event(event);
+            if Self::trust_state(&grantor) == TrustState::Active {
+                let next = n + top_ups[index].1.interval;
+                top_ups[index].1.next = next;
+                <TopUpsDue<T>>::mutate(next, |due| due.push((grantor.clone(), asset_id)));
+                <AutoTopUps<T>>::insert(&grantor, top_ups);
+            } else {
+                <AutoTopUps<T>>::remove(&grantor);
+            }
+        }
+    }
+
+    /// Work through the queued automatic distributions, within the per-block budget.
+    fn process_distribution_queue() {
+        let mut queue = Self::distribution_queue();
+        if queue.is_empty() || Self::distributions_paused() {
+            return;
+        }
+        let budget = (T::AutoDistributionsPerBlock::get() as usize).min(queue.len());
+        for (grantor, asset_id) in queue.drain(..budget) {
+            // A failed distribution is not retried; it can still be withdrawn manually.
+            let _ = Self::distribute(&grantor, asset_id);
+        }
+        <DistributionQueue<T>>::put(queue);
+    }
+
+    fn do_clock_in(grantor: &T::AccountId) {
+        let block_number = <system::Module<T>>::block_number();
+        let moment = <timestamp::Module<T>>::get();
+        <LastClockIn<T>>::insert(grantor, &block_number);
+        <LastClockInMoment<T>>::insert(grantor, moment);
+        if <ExpiryBlock<T>>::exists(grantor) {
+            Self::update_armed(grantor);
+        }
+        <ClockInHistory<T>>::mutate(grantor, |history| {
+            history.push((block_number, moment));
+            let excess = history.len().saturating_sub(T::ClockInHistoryLength::get() as usize);
+            history.drain(..excess);
+        });
+        Self::release_attestations(grantor, true);
+        Self::record(grantor, AuditAction::ClockedIn);
+        for trust in Self::joint_trusts_of(grantor) {
+            Self::do_clock_in(&trust);
+        }
+    }
+
+    /// Clock `grantor` in, or hold the clock-in until their confirmation key confirms it.
+    fn request_clock_in(grantor: &T::AccountId) {
+        if <ConfirmationKeys<T>>::exists(grantor) {
+            <PendingClockIns<T>>::insert(grantor, <system::Module<T>>::block_number());
+            Self::deposit_event(RawEvent::ClockInPending(grantor.clone()));
+        } else {
+            Self::do_clock_in(grantor);
+        }
+    }
+
+    /// Check that `key` confirms `grantor`'s clock-ins, returning its confirmation window.
+    fn ensure_confirmation_key(
+        grantor: &T::AccountId,
+        key: &T::AccountId,
+    ) -> rstd::result::Result<T::BlockNumber, &'static str> {
+        match Self::confirmation_key(grantor) {
+            Some((confirmer, window)) if confirmer == *key => Ok(window),
+            _ => Err("not the confirmation key"),
+        }
+    }
+
+    /// Whether `guardian` is one of `grantor`'s guardians and has confirmed their activity
+    /// recently enough.
+    fn is_active_guardian(grantor: &T::AccountId, guardian: &T::AccountId) -> bool {
+        let active_since = <GuardianActivity<T>>::get(grantor, guardian);
+        Self::guardians(grantor).contains(guardian)
+            && <system::Module<T>>::block_number() < active_since + T::GuardianActivityPeriod::get()
+    }
+
+    /// How many of `grantor`'s active guardians have attested.
+    fn attestation_count(grantor: &T::AccountId) -> u32 {
+        Self::guardian_attestations(grantor)
+            .iter()
+            .filter(|guardian| Self::is_active_guardian(grantor, guardian))
+            .count() as u32
+    }
+
+    fn drop_guardian(grantor: &T::AccountId, guardian: &T::AccountId) {
+        <Guardians<T>>::mutate(grantor, |guardians| guardians.retain(|g| g != guardian));
+        <GuardianAttestations<T>>::mutate(grantor, |attesters| attesters.retain(|g| g != guardian));
+        <GuardianActivity<T>>::remove(grantor, guardian);
+        T::Currency::unreserve(guardian, <AttestationDeposits<T>>::take(grantor, guardian));
+    }
+
+    /// Pay each active guardian that attested to `grantor`'s trust its reward out of every
+    /// holding. Holdings that cannot be paid from, e.g. because they are bonded, are skipped.
+    fn reward_guardians(grantor: &T::AccountId) {
+        let reward = Self::guardian_reward(grantor);
+        if reward.is_zero() {
+            return;
+        }
+        let attesters: Vec<_> = Self::guardian_attestations(grantor)
+            .into_iter()
+            .filter(|guardian| Self::is_active_guardian(grantor, guardian))
+            .collect();
+        for asset_id in Self::trust_assets(grantor) {
+            let amount = Self::portion(reward, Self::trust_balance(grantor, &asset_id));
+            if amount.is_zero() {
+                continue;
+            }
+            for guardian in &attesters {
+                if Self::transfer_out(grantor, asset_id, guardian.clone(), amount).is_ok() {
+                    Self::deposit_event(RawEvent::GuardianRewarded(
+                        grantor.clone(),
+                        guardian.clone(),
+                        asset_id,
+                        amount,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Drop all attestations on `grantor`'s trust, slashing the guardians' deposits if
+    /// `slash` is set and refunding them otherwise.
+    fn release_attestations(grantor: &T::AccountId, slash: bool) {
+        for guardian in <GuardianAttestations<T>>::take(grantor) {
+            let deposit = <AttestationDeposits<T>>::take(grantor, &guardian);
+            if slash {
+                let (imbalance, _) = T::Currency::slash_reserved(&guardian, deposit);
+                T::Slashed::on_unbalanced(imbalance);
+                let event = RawEvent::AttestationSlashed(grantor.clone(), guardian, deposit);
+                Self::deposit_event(event);
+            } else {
+                T::Currency::unreserve(&guardian, deposit);
+            }
+        }
+    }
+
+    /// The storage deposit of a trust storing `beneficiaries` beneficiary entries and holding
+    /// `assets` assets.
+    fn storage_deposit_for(beneficiaries: usize, assets: usize) -> NativeBalanceOf<T> {
+        let count = |n: usize| NativeBalanceOf::<T>::from(n as u32);
+        T::BeneficiaryDeposit::get()
+            .saturating_mul(count(beneficiaries))
+            .saturating_add(T::AssetDeposit::get().saturating_mul(count(assets)))
+    }
+
+    /// Check that `grantor` can cover the storage deposit of their trust once it stores
+    /// `beneficiaries` beneficiary entries and holds `assets` assets, returning that deposit.
+    fn ensure_storage_deposit(
+        grantor: &T::AccountId,
+        beneficiaries: usize,
+        assets: usize,
+    ) -> rstd::result::Result<NativeBalanceOf<T>, &'static str> {
+        let required = Self::storage_deposit_for(beneficiaries, assets);
+        let shortfall = required.saturating_sub(Self::storage_deposit(grantor));
+        ensure!(
+            T::Currency::can_reserve(grantor, shortfall),
+            "not enough free balance for storage deposit"
+        );
+        Ok(required)
+    }
+
+    /// `ensure_storage_deposit` for `grantor`'s trust once it stores `beneficiaries`
+    /// beneficiary entries, holding the assets it holds now.
+    fn ensure_beneficiary_deposit(
+        grantor: &T::AccountId,
+        beneficiaries: usize,
+    ) -> rstd::result::Result<NativeBalanceOf<T>, &'static str> {
+        Self::ensure_storage_deposit(grantor, beneficiaries, Self::trust_assets(grantor).len())
+    }
+
+    /// `ensure_storage_deposit` for `grantor`'s trust once it holds `asset_id` as well.
+    fn ensure_asset_deposit(
+        grantor: &T::AccountId,
+        asset_id: T::AssetId,
+    ) -> rstd::result::Result<NativeBalanceOf<T>, &'static str> {
+        let assets = Self::trust_assets(grantor);
+        let held = assets.len() + if assets.contains(&asset_id) { 0 } else { 1 };
+        Self::ensure_storage_deposit(grantor, Self::all_beneficiaries(grantor).len(), held)
+    }
+
+    /// Reserve or refund the difference between `grantor`'s storage deposit and `deposit`.
+    /// Must be preceded by `ensure_storage_deposit` when the deposit grows.
+    fn set_storage_deposit(grantor: &T::AccountId, deposit: NativeBalanceOf<T>) {
+        let held = <StorageDeposits<T>>::take(grantor);
+        if deposit > held {
+            let _ = T::Currency::reserve(grantor, deposit - held);
+        } else {
+            T::Currency::unreserve(grantor, held - deposit);
+        }
+        if !deposit.is_zero() {
+            <StorageDeposits<T>>::insert(grantor, deposit);
+        }
+    }
+
+    /// Refund whatever `grantor`'s storage deposit exceeds what their trust stores now.
+    fn refund_storage_deposit(grantor: &T::AccountId) {
+        let beneficiaries = Self::all_beneficiaries(grantor).len();
+        let required = Self::storage_deposit_for(beneficiaries, Self::trust_assets(grantor).len());
+        if required < Self::storage_deposit(grantor) {
+            Self::set_storage_deposit(grantor, required);
+        }
+    }
+
+    fn ensure_joint_owner(trust: &T::AccountId, who: &T::AccountId) -> Result {
+        ensure!(
+            Self::joint_trust_owners(trust).contains(who),
+            "not a grantor of this joint trust"
+        );
+        Ok(())
+    }
+
+    /// Apply `change` to the joint trust `trust` if enough grantors approved it, or keep it
+    /// pending with `approvals` otherwise.
+    fn approve_or_execute(trust: T::AccountId, change: Call<T>, approvals: Vec<T::AccountId>) {
+        let owners = Self::joint_trust_owners(&trust);
+        let required = Self::approval_threshold(&trust).map_or(owners.len(), |k| k as usize);
+        let approved = owners.iter().filter(|owner| approvals.contains(owner)).count();
+        if approved >= required {
+            <PendingChanges<T>>::remove(&trust);
+            let result = change.dispatch(system::RawOrigin::Signed(trust.clone()).into());
+            Self::deposit_event(RawEvent::ChangeExecuted(trust, result.is_ok()));
+        } else {
+            <PendingChanges<T>>::insert(&trust, (change, approvals));
+        }
+    }
+
+    /// Append `action` to `grantor`'s audit log, dropping the oldest entries beyond
+    /// `AuditLogLength`.
+    fn record(grantor: &T::AccountId, action: AuditAction) {
+        let block = <system::Module<T>>::block_number();
+        <AuditLog<T>>::mutate(grantor, |log| {
+            log.push(AuditEntry { block, action });
+            let excess = log.len().saturating_sub(T::AuditLogLength::get() as usize);
+            log.drain(..excess);
+        });
+    }
+
+    /// Check that `heartbeat` is fresh and signed by the grantor's authorized key.
+    fn check_heartbeat(
+        heartbeat: &Heartbeat<T::AccountId, T::BlockNumber>,
+        signature: &<T::HeartbeatId as RuntimeAppPublic>::Signature,
+    ) -> Result {
+        let key = Self::authorized_heartbeat_key(&heartbeat.grantor)
+            .ok_or("no heartbeat key authorized")?;
+        ensure!(
+            heartbeat.nonce == Self::heartbeat_nonce(&heartbeat.grantor),
+            "stale heartbeat nonce"
+        );
+        ensure!(
+            heartbeat.block_number <= <system::Module<T>>::block_number(),
+            "heartbeat from the future"
+        );
+        ensure!(
+            heartbeat.using_encoded(|payload| key.verify(&payload, signature)),
+            "invalid heartbeat signature"
+        );
+        Ok(())
+    }
+
+    /// Check that an unsigned clock-in is signed by `grantor`, carries their current nonce and
+    /// is not sent sooner than `UnsignedClockInPeriod` after the previous one.
+    fn check_unsigned_clock_in(grantor: &T::AccountId, signature: &T::Signature, nonce: u64) -> Result {
+        ensure!(Self::has_trust(grantor), "no trust to clock in for");
+        ensure!(nonce == Self::clock_in_nonce(grantor), "stale clock-in nonce");
+        if let Some(last) = Self::last_unsigned_clock_in(grantor) {
+            ensure!(
+                <system::Module<T>>::block_number() >= last + T::UnsignedClockInPeriod::get(),
+                "unsigned clock-in too soon"
+            );
+        }
+        ensure!(
+            (grantor, nonce).using_encoded(|payload| signature.verify(payload, grantor)),
+            "invalid clock-in signature"
+        );
+        Ok(())
+    }
+
+    fn send_heartbeats(now: T::BlockNumber) {
+        for key in T::HeartbeatId::all() {
+            let grantor = match Self::heartbeat_grantor(&key) {
+                Some(grantor) => grantor,
+                None => continue,
+            };
+            if Self::trust_state(&grantor) != TrustState::Active
+                || now.saturating_sub(Self::last_clock_in(&grantor)) < T::HeartbeatInterval::get()
+            {
+                continue;
+            }
+            let heartbeat = Heartbeat {
+                nonce: Self::heartbeat_nonce(&grantor),
+                grantor,
+                block_number: now,
+            };
+            let signature = match heartbeat.using_encoded(|payload| key.sign(&payload)) {
+                Some(signature) => signature,
+                None => continue,
+            };
+            let call = Call::<T>::heartbeat(heartbeat, signature);
+            // The pool rejects duplicates of a pending heartbeat, so failures are harmless.
+            let _ = T::SubmitTransaction::submit_unsigned(call);
+        }
+    }
+
+    fn ensure_active(grantor: &T::AccountId) -> Result {
+        ensure!(
+            Self::trust_state(grantor) == TrustState::Active,
+            "trust configuration is locked"
+        );
+        Ok(())
+    }
+
+    /// Check that `caller` may trigger `grantor`'s trust and trigger it. Returns `false` if
+    /// the attempt was premature and the caller's deposit has been slashed instead.
+    fn trigger_as(
+        caller: &T::AccountId,
+        grantor: &T::AccountId,
+    ) -> rstd::result::Result<bool, &'static str> {
+        let policy = Self::trigger_policy(grantor);
+        match policy {
+            TriggerPolicy::Beneficiaries => ensure!(
+                Self::is_beneficiary(grantor, caller),
+                "only beneficiaries may trigger this trust"
+            ),
+            TriggerPolicy::Trustee => ensure!(
+                Self::trustee(grantor).as_ref() == Some(caller),
+                "only the trustee may trigger this trust"
+            ),
+            TriggerPolicy::AnyoneWithDeposit => {
+                if Self::trust_state(grantor) == TrustState::Active {
+                    let deposit = T::TriggerDeposit::get();
+                    if Self::condition_holds(grantor)? {
+                        ensure!(
+                            T::Currency::can_reserve(caller, deposit),
+                            "not enough free balance for trigger deposit"
+                        );
+                    } else {
+                        T::Currency::reserve(caller, deposit)?;
+                        let (imbalance, _) = T::Currency::slash_reserved(caller, deposit);
+                        T::Slashed::on_unbalanced(imbalance);
+                        Self::deposit_event(RawEvent::TriggerRejected(
+                            grantor.clone(),
+                            caller.clone(),
+                            deposit,
+                        ));
+                        return Ok(false);
+                    }
+                }
+            }
+        }
+        Self::ensure_triggered(grantor)?;
+        Ok(true)
+    }
+
+    /// Fire the living switch of `grantor`'s trust if its condition holds, and fail unless
+    /// the trust has been triggered.
+    fn ensure_triggered(grantor: &T::AccountId) -> Result {
+        match Self::trust_state(grantor) {
+            TrustState::Active => {
+                let can_withdraw = Self::condition_holds(grantor)?;
+                ensure!(can_withdraw, "not withdrawable yet");
+                Self::disarm(grantor);
+                Self::trigger(grantor);
+                Ok(())
+            }
+            TrustState::Triggered | TrustState::Distributing => Ok(()),
+            TrustState::Closed => Err("trust is closed"),
+        }
+    }
+
+    /// Move `grantor`'s trust to `Triggered` and record when that happened. Trusts that
+    /// distribute themselves are queued for distribution.
+    fn trigger(grantor: &T::AccountId) {
+        <TriggeredAtBlock<T>>::insert(grantor, <system::Module<T>>::block_number());
+        Self::set_trust_state(grantor, TrustState::Triggered);
+        if let Ok(Some(index)) = Self::firing_tier(grantor) {
+            <ActiveTier<T>>::insert(grantor, index);
+            Self::deposit_event(RawEvent::TierTriggered(grantor.clone(), index));
+        }
+        Self::reward_guardians(grantor);
+        Self::release_attestations(grantor, false);
+        let snapshot: Vec<_> = Self::trust_assets(grantor)
+            .into_iter()
+            .map(|asset_id| (asset_id, Self::trust_balance(grantor, &asset_id)))
+            .collect();
+        <TriggerSnapshots<T>>::insert(grantor, snapshot);
+        let assets = Self::trust_assets(grantor);
+        let distributions = assets.into_iter().map(|asset_id| (grantor.clone(), asset_id));
+        if Self::auto_distribute(grantor) {
+            <DistributionQueue<T>>::mutate(|queue| queue.extend(distributions));
+        } else {
+            <ClaimableTrusts<T>>::mutate(|claimable| claimable.extend(distributions));
+        }
+        Self::record(grantor, AuditAction::Triggered);
+        Self::deposit_event(RawEvent::TrustTriggered(grantor.clone()));
+        Self::notify_beneficiaries(grantor);
+        for observer in Self::observers(grantor) {
+            Self::deposit_event(RawEvent::ObservedTrigger(grantor.clone(), observer));
+        }
+    }
+
+    /// Tell every beneficiary of `grantor`'s trust what they can claim of each asset.
+    fn notify_beneficiaries(grantor: &T::AccountId) {
+        let mut inventories: Vec<(T::AccountId, Vec<(T::AssetId, BalanceOf<T>)>)> = Vec::new();
+        for asset_id in Self::trust_assets(grantor) {
+            for (beneficiary, amount) in Self::quote_distribution(grantor.clone(), asset_id) {
+                match inventories.iter_mut().find(|(who, _)| *who == beneficiary) {
+                    Some((_, inventory)) => inventory.push((asset_id, amount)),
+                    None => inventories.push((beneficiary, vec![(asset_id, amount)])),
+                }
+            }
+        }
+        for (beneficiary, inventory) in inventories {
+            Self::deposit_event(RawEvent::InheritanceAvailable(grantor.clone(), beneficiary, inventory));
+        }
+    }
+
+    /// Pay the trust's `asset_id` holding out to the beneficiaries, according to the payout
+    /// mode. The trust must have been triggered.
+    fn distribute(grantor: &T::AccountId, asset_id: T::AssetId) -> Result {
+        Self::distribute_for(grantor, asset_id, None)
+    }
+
+    /// Distribute the trust's `asset_id` holding, paying `keeper` the keeper bounty out of
+    /// the payouts if given.
+    fn distribute_for(
+        grantor: &T::AccountId,
+        asset_id: T::AssetId,
+        keeper: Option<&T::AccountId>,
+    ) -> Result {
+        Self::ensure_not_streaming(grantor, asset_id)?;
+        Self::ensure_not_disputed(grantor)?;
+        if <BondedFunds<T>>::exists((grantor.clone(), asset_id)) {
+            Self::release_idle_funds(grantor, asset_id)?;
+        }
+        let total_amount = Self::distributable(grantor, asset_id);
+        let tier = Self::active_tier(grantor);
+        let (beneficiaries, denominator) = Self::payout_plan(grantor, asset_id, tier);
+        let payouts = Self::calc_payouts(grantor, total_amount, &beneficiaries, denominator)?;
+        let (payouts, fee) = Self::deduct_distribution_fee(payouts);
+        let (payouts, levies) = Self::levy_distribution(grantor, asset_id, payouts);
+        let (payouts, bounty) = match keeper {
+            Some(keeper) => Self::deduct_keeper_bounty(keeper, payouts),
+            None => (payouts, None),
+        };
+        let charges: Vec<_> = payouts
+            .iter()
+            .cloned()
+            .chain(levies.iter().map(|(_, recipient, amount)| (recipient.clone(), *amount)))
+            .chain(bounty.clone())
+            .collect();
+        Self::ensure_can_pay_out(grantor, asset_id, &charges, &fee)?;
+        Self::set_trust_state(grantor, TrustState::Distributing);
+        <ClaimableTrusts<T>>::mutate(|claimable| {
+            claimable.retain(|(g, asset)| !(g == grantor && *asset == asset_id))
+        });
+        if let Some((keeper, amount)) = bounty {
+            Self::transfer_out(grantor, asset_id, keeper.clone(), amount)?;
+            let event = RawEvent::KeeperRewarded(grantor.clone(), keeper, asset_id, amount);
+            Self::deposit_event(event);
+        }
+        for (beneficiary, recipient, amount) in levies {
+            Self::transfer_out(grantor, asset_id, recipient.clone(), amount)?;
+            Self::deposit_event(RawEvent::DistributionLevied(
+                grantor.clone(),
+                beneficiary,
+                asset_id,
+                amount,
+                recipient,
+            ));
+        }
+        match Self::payout_mode(grantor) {
+            PayoutMode::LumpSum => Self::pay_out(grantor, asset_id, payouts, fee)?,
+            PayoutMode::Streamed(rate) => Self::open_streams(grantor, asset_id, rate, payouts, fee)?,
+        }
+        Self::record(grantor, AuditAction::Distributed);
+        Self::deposit_event(RawEvent::Withdraw(grantor.clone()));
+        for observer in Self::observers(grantor) {
+            let event = RawEvent::ObservedDistribution(grantor.clone(), observer, asset_id);
+            Self::deposit_event(event);
+        }
+        Ok(())
+    }
+
+    /// How much of its `asset_id` holding `grantor`'s trust pays out: what it held when it
+    /// was triggered, or what it holds now if it has not been triggered yet.
+    fn distributable(grantor: &T::AccountId, asset_id: T::AssetId) -> BalanceOf<T> {
+        let balance = Self::trust_balance(grantor, &asset_id);
+        if !<TriggerSnapshots<T>>::exists(grantor) {
+            return balance;
+        }
+        Self::trigger_snapshot(grantor)
+            .into_iter()
+            .find(|(id, _)| *id == asset_id)
+            .map_or(Zero::zero(), |(_, amount)| amount.min(balance))
+    }
+
+    /// Fail if `grantor`'s trust has an open dispute. A dispute whose window has passed is
+    /// dropped and its deposit refunded.
+    fn ensure_not_disputed(grantor: &T::AccountId) -> Result {
+        if let Some(dispute) = Self::dispute(grantor) {
+            ensure!(
+                <system::Module<T>>::block_number() >= dispute.expires_at,
+                "distribution is disputed"
+            );
+            <Disputes<T>>::remove(grantor);
+            T::Currency::unreserve(&dispute.disputer, dispute.deposit);
+            Self::deposit_event(RawEvent::DisputeExpired(grantor.clone()));
+        }
+        Ok(())
+    }
+
+    fn ensure_not_paused() -> Result {
+        ensure!(!Self::distributions_paused(), "distributions are paused");
+        Ok(())
+    }
+
+    fn ensure_not_streaming(grantor: &T::AccountId, asset_id: T::AssetId) -> Result {
+        ensure!(
+            !<OpenStreams<T>>::exists((grantor.clone(), asset_id)),
+            "payouts are already streaming"
+        );
+        Ok(())
+    }
+
+    /// Stop checking `grantor`'s condition at the end of blocks.
+    fn disarm(grantor: &T::AccountId) {
+        <ArmedTrusts<T>>::mutate(|armed| armed.retain(|g| g != grantor));
+        if let Some(block) = <ExpiryBlock<T>>::take(grantor) {
+            <ExpiringAt<T>>::mutate(block, |expiring| expiring.retain(|g| g != grantor));
+        }
+    }
+
+    /// Check `grantor`'s trust at the end of blocks exactly when it has a condition: only at
+    /// the end of the block it expires in if the condition depends on the block number
+    /// alone, and at the end of every block otherwise.
+    fn update_armed(grantor: &T::AccountId) {
+        Self::disarm(grantor);
+        let condition = <LivingSwitchConds<T>>::get(grantor);
+        if <Tiers<T>>::exists(grantor) {
+            <ArmedTrusts<T>>::mutate(|armed| armed.push(grantor.clone()));
+        } else if let Some(block) = Self::expiry_of(grantor, &condition) {
+            let block = block.max(<system::Module<T>>::block_number());
+            <ExpiringAt<T>>::mutate(block, |expiring| expiring.push(grantor.clone()));
+            <ExpiryBlock<T>>::insert(grantor, block);
+        } else if condition != LivingSwitchCond::None {
+            <ArmedTrusts<T>>::mutate(|armed| armed.push(grantor.clone()));
+        }
+    }
+
+    /// The first block at the end of which `condition` holds for `grantor`, if it depends on
+    /// the block number alone.
+    fn expiry_of(
+        grantor: &T::AccountId,
+        condition: &LivingSwitchCond<T::BlockNumber, T::Moment>,
+    ) -> Option<T::BlockNumber> {
+        match condition {
+            LivingSwitchCond::BlockHeight(height) => Some(height.saturating_add(One::one())),
+            LivingSwitchCond::ClockInInterval(interval) => Some(
+                <LastClockIn<T>>::get(grantor).saturating_add(*interval).saturating_add(One::one()),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Whether `grantor`'s trust may be triggered: its plain condition holds or, if it has
+    /// tiers, one of them fires.
+    fn condition_holds(grantor: &T::AccountId) -> rstd::result::Result<bool, &'static str> {
+        ensure!(Self::is_witnessed(grantor), "configuration not witnessed");
+        if <Tiers<T>>::exists(grantor) {
+            Ok(Self::firing_tier(grantor)?.is_some())
+        } else {
+            let living_cond = <LivingSwitchConds<T>>::get(grantor);
+            Self::check_withdrawable(grantor, &living_cond)
+        }
+    }
+
+    /// Hash of the beneficiaries and conditions of `grantor`'s trust, as co-signed by its
+    /// witnesses.
+    pub fn config_hash(grantor: &T::AccountId) -> T::Hash {
+        T::Hashing::hash_of(&(
+            Self::beneficiaries(grantor),
+            Self::share_denominator(grantor),
+            <LivingSwitchConds<T>>::get(grantor),
+            Self::tiers(grantor),
+            Self::asset_beneficiaries(grantor),
+        ))
+    }
+
+    /// Whether every witness of `grantor`'s trust has co-signed its current configuration.
+    fn is_witnessed(grantor: &T::AccountId) -> bool {
+        let witnesses = Self::witnesses(grantor);
+        if witnesses.is_empty() {
+            return true;
+        }
+        let config_hash = Self::config_hash(grantor);
+        let confirmations = Self::witness_confirmations(grantor);
+        witnesses.iter().all(|witness| {
+            confirmations
+                .iter()
+                .any(|(who, hash, _)| who == witness && *hash == config_hash)
+        })
+    }
+
+    /// Index of the highest priority tier of `grantor`'s trust that fires.
+    fn firing_tier(grantor: &T::AccountId) -> rstd::result::Result<Option<u32>, &'static str> {
+        let attestations = Self::attestation_count(grantor);
+        for (index, tier) in Self::tiers(grantor).iter().enumerate() {
+            let condition_holds = tier.condition == LivingSwitchCond::None
+                || Self::check_withdrawable(grantor, &tier.condition)?;
+            if condition_holds && attestations >= tier.required_attestations {
+                return Ok(Some(index as u32));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The beneficiaries `grantor`'s trust pays its `asset_id` holding out to under `tier`,
+    /// together with their share denominator. Without a tier, the asset's own beneficiaries
+    /// are used if it has any, and the plain set otherwise.
+    fn payout_plan(
+        grantor: &T::AccountId,
+        asset_id: T::AssetId,
+        tier: Option<u32>,
+    ) -> (Vec<BeneficiaryShare<T::AccountId>>, u64) {
+        let tier = tier.and_then(|index| Self::tiers(grantor).into_iter().nth(index as usize));
+        if let Some(tier) = tier {
+            return (tier.beneficiaries, 0);
+        }
+        match Self::asset_beneficiaries(grantor).into_iter().find(|(asset, _)| *asset == asset_id) {
+            Some((_, shares)) => (shares, 0),
+            None => (Self::beneficiaries(grantor), Self::share_denominator(grantor)),
+        }
+    }
+
+    /// The plain beneficiaries of `grantor`'s trust.
+    pub fn beneficiaries(grantor: &T::AccountId) -> Vec<BeneficiaryShare<T::AccountId>> {
+        Self::beneficiary_page(grantor, 0, Self::beneficiary_count(grantor))
+    }
+
+    /// Up to `limit` plain beneficiaries of `grantor`'s trust, from position `start` on.
+    pub fn beneficiary_page(
+        grantor: &T::AccountId,
+        start: u32,
+        limit: u32,
+    ) -> Vec<BeneficiaryShare<T::AccountId>> {
+        let end = start.saturating_add(limit).min(Self::beneficiary_count(grantor));
+        (start..end)
+            .filter_map(|index| Self::beneficiary_at(grantor, &index))
+            .filter_map(|address| {
+                let (_, weight) = Self::beneficiary_share(grantor, &address)?;
+                Some(BeneficiaryShare { address, weight })
+            })
+            .collect()
+    }
+
+    /// Whether `who` is named by `grantor`'s trust, in its plain set or any tier.
+    fn is_beneficiary(grantor: &T::AccountId, who: &T::AccountId) -> bool {
+        let listed =
+            |shares: &[BeneficiaryShare<T::AccountId>]| shares.iter().any(|s| s.address == *who);
+        <BeneficiaryShares<T>>::exists(grantor, who)
+            || Self::tiers(grantor).iter().any(|tier| listed(&tier.beneficiaries))
+            || Self::asset_beneficiaries(grantor).iter().any(|(_, shares)| listed(shares))
+    }
+
+    /// Replace the plain beneficiaries of `grantor`'s trust. Must be preceded by
+    /// `ensure_distinct`.
+    fn put_beneficiaries(grantor: &T::AccountId, shares: &[BeneficiaryShare<T::AccountId>]) {
+        Self::clear_beneficiary_shares(grantor);
+        for share in shares {
+            Self::push_beneficiary(grantor, share.address.clone(), share.weight);
+        }
+    }
+
+    /// Add `who` as the last plain beneficiary of `grantor`'s trust.
+    fn push_beneficiary(grantor: &T::AccountId, who: T::AccountId, weight: u64) {
+        let index = Self::beneficiary_count(grantor);
+        <BeneficiaryShares<T>>::insert(grantor, &who, (index, weight));
+        <BeneficiaryAt<T>>::insert(grantor, &index, who);
+        <BeneficiaryCount<T>>::insert(grantor, index + 1);
+        <TotalBeneficiaryWeight<T>>::mutate(grantor, |total| *total = total.saturating_add(weight));
+    }
+
+    /// Remove `who` from the plain beneficiaries of `grantor`'s trust. The last beneficiary
+    /// takes over the freed position.
+    fn take_beneficiary(grantor: &T::AccountId, who: &T::AccountId) {
+        let (index, weight) = match <BeneficiaryShares<T>>::take(grantor, who) {
+            Some(share) => share,
+            None => return,
+        };
+        let last = Self::beneficiary_count(grantor).saturating_sub(1);
+        if let Some(moved) = <BeneficiaryAt<T>>::take(grantor, &last) {
+            if index != last {
+                if let Some((_, moved_weight)) = Self::beneficiary_share(grantor, &moved) {
+                    <BeneficiaryShares<T>>::insert(grantor, &moved, (index, moved_weight));
+                }
+                <BeneficiaryAt<T>>::insert(grantor, &index, moved);
+            }
+        }
+        if last == 0 {
+            <BeneficiaryCount<T>>::remove(grantor);
+            <TotalBeneficiaryWeight<T>>::remove(grantor);
+        } else {
+            <BeneficiaryCount<T>>::insert(grantor, last);
+            <TotalBeneficiaryWeight<T>>::mutate(grantor, |total| *total = total.saturating_sub(weight));
+        }
+    }
+
+    /// Remove every plain beneficiary of `grantor`'s trust.
+    fn clear_beneficiary_shares(grantor: &T::AccountId) {
+        for index in 0..Self::beneficiary_count(grantor) {
+            if let Some(who) = <BeneficiaryAt<T>>::take(grantor, &index) {
+                <BeneficiaryShares<T>>::remove(grantor, &who);
+            }
+        }
+        <BeneficiaryCount<T>>::remove(grantor);
+        <TotalBeneficiaryWeight<T>>::remove(grantor);
+    }
+
+    /// Scale the weights of `shares` up so that they add up to `total`, keeping their
+    /// proportions. The rounding remainder goes to the last share.
+    fn rescale(
+        shares: Vec<BeneficiaryShare<T::AccountId>>,
+        total: u64,
+    ) -> Vec<BeneficiaryShare<T::AccountId>> {
+        let current = Self::total_weight(&shares);
+        if current == 0 {
+            return shares;
+        }
+        let last = shares.len().saturating_sub(1);
+        let mut assigned = 0_u64;
+        shares
+            .into_iter()
+            .enumerate()
+            .map(|(index, share)| {
+                let weight = if index == last {
+                    total.saturating_sub(assigned)
+                } else {
+                    (u128::from(share.weight) * u128::from(total) / u128::from(current)) as u64
+                };
+                assigned = assigned.saturating_add(weight);
+                BeneficiaryShare { address: share.address, weight }
+            })
+            .collect()
+    }
+
+    /// Remove `who` from `shares`, handing their weight to `fallback` if nobody else is left.
+    fn drop_share(
+        shares: &mut Vec<BeneficiaryShare<T::AccountId>>,
+        who: &T::AccountId,
+        fallback: &Option<T::AccountId>,
+    ) {
+        let weight = Self::total_weight(shares);
+        shares.retain(|share| share.address != *who);
+        if let (true, Some(address)) = (shares.is_empty(), fallback.clone()) {
+            shares.push(BeneficiaryShare { address, weight });
+        }
+    }
+
+    fn ensure_distinct(shares: &[BeneficiaryShare<T::AccountId>]) -> Result {
+        for (index, share) in shares.iter().enumerate() {
+            ensure!(
+                !shares[..index].iter().any(|other| other.address == share.address),
+                "duplicate beneficiary"
+            );
+        }
+        Ok(())
+    }
+
+    /// Every beneficiary named by `grantor`'s trust, in its plain set or any tier.
+    fn all_beneficiaries(grantor: &T::AccountId) -> Vec<BeneficiaryShare<T::AccountId>> {
+        let mut shares = Self::beneficiaries(grantor);
+        for tier in Self::tiers(grantor) {
+            shares.extend(tier.beneficiaries);
+        }
+        for (_, overrides) in Self::asset_beneficiaries(grantor) {
+            shares.extend(overrides);
+        }
+        shares
+    }
+
+    fn set_trust_state(grantor: &T::AccountId, state: TrustState) {
+        if Self::trust_state(grantor) != state {
+            <TrustStates<T>>::insert(grantor, state);
+            Self::deposit_event(RawEvent::TrustStateChanged(grantor.clone(), state));
+        }
+    }
+
+    /// Keep `TrustsByBeneficiary` in sync when `grantor`'s beneficiaries change from
+    /// `previous` to `next`.
+    fn update_beneficiary_index(
+        grantor: &T::AccountId,
+        previous: &[BeneficiaryShare<T::AccountId>],
+        next: &[BeneficiaryShare<T::AccountId>],
+    ) {
+        let listed = |shares: &[BeneficiaryShare<T::AccountId>], who: &T::AccountId| {
+            shares.iter().any(|share| share.address == *who)
+        };
+        for share in previous.iter().filter(|share| !listed(next, &share.address)) {
+            let mut grantors = <TrustsByBeneficiary<T>>::get(&share.address);
+            grantors.retain(|g| g != grantor);
+            if grantors.is_empty() {
+                <TrustsByBeneficiary<T>>::remove(&share.address);
+            } else {
+                <TrustsByBeneficiary<T>>::insert(&share.address, grantors);
+            }
+        }
+        for share in next.iter().filter(|share| !listed(previous, &share.address)) {
+            <TrustsByBeneficiary<T>>::mutate(&share.address, |grantors| {
+                if !grantors.contains(grantor) {
+                    grantors.push(grantor.clone());
+                }
+            });
+        }
+    }
+
+    fn total_weight(shares: &[BeneficiaryShare<T::AccountId>]) -> u64 {
+        shares.iter().fold(0_u64, |acc, share| acc.saturating_add(share.weight))
+    }
+
+    /// Check that percentage shares adding up to `total` stay at most 100%.
+    fn ensure_within_denominator(grantor: &T::AccountId, total: u64) -> Result {
+        let denominator = Self::share_denominator(grantor);
+        ensure!(denominator == 0 || total <= denominator, "shares exceed 100%");
+        Ok(())
+    }
+
+    /// Whether `who` holds any trust configuration or funds.
+    fn has_trust(who: &T::AccountId) -> bool {
+        <BeneficiaryCount<T>>::exists(who)
+            || <LivingSwitchConds<T>>::exists(who)
+            || <TrustAssets<T>>::exists(who)
+            || <TrustStates<T>>::exists(who)
+            || <UniqueAssignments<T>>::exists(who)
+            || <Tiers<T>>::exists(who)
+            || <JointTrustOwners<T>>::exists(who)
+    }
+
+    /// Re-key every storage item of an active trust from `from` to `to`.
+    fn move_trust(from: &T::AccountId, to: &T::AccountId) {
+        macro_rules! move_value {
+            ($($map:ident),*) => {$(
+                if <$map<T>>::exists(from) {
+                    <$map<T>>::insert(to, <$map<T>>::take(from));
+                }
+            )*};
+        }
+        macro_rules! move_option {
+            ($($map:ident),*) => {$(
+                if let Some(value) = <$map<T>>::take(from) {
+                    <$map<T>>::insert(to, value);
+                }
+            )*};
+        }
+        for guardian in Self::guardians(from) {
+            let active_since = <GuardianActivity<T>>::take(from, &guardian);
+            <GuardianActivity<T>>::insert(to, &guardian, active_since);
+        }
+        for guardian in Self::guardian_attestations(from) {
+            let deposit = <AttestationDeposits<T>>::take(from, &guardian);
+            <AttestationDeposits<T>>::insert(to, &guardian, deposit);
+        }
+        for index in 0..Self::beneficiary_count(from) {
+            if let Some(who) = <BeneficiaryAt<T>>::take(from, &index) {
+                if let Some(share) = <BeneficiaryShares<T>>::take(from, &who) {
+                    <BeneficiaryShares<T>>::insert(to, &who, share);
+                }
+                <BeneficiaryAt<T>>::insert(to, &index, who);
+            }
+        }
+        move_value!(
+            BeneficiaryCount,
+            TotalBeneficiaryWeight,
+            ShareDenominator,
+            Tiers,
+            Witnesses,
+            WitnessConfirmations,
+            Observers,
+            Guardians,
+            PendingGuardians,
+            GuardianAttestations,
+            LivingSwitchConds,
+            LastClockIn,
+            LastClockInMoment,
+            ClockInHistory,
+            KeepAlive,
+            RequireVerifiedIdentity,
+            TriggerPolicies,
+            UniqueAssignments,
+            TrustStates,
+            PayoutModes,
+            AutoDistribute,
+            HeartbeatNonce,
+            AuditLog,
+            ChangeDelays,
+            TimelockedChanges,
+            AssetBeneficiaries,
+            GuardianRewards
+        );
+        move_option!(
+            Metadata,
+            Trustees,
+            FallbackAccount,
+            PowersOfAttorney,
+            TrustExpiries,
+            LastConfigChange,
+            ApprovalThresholds,
+            ConfirmationKeys,
+            PendingClockIns
+        );
+        if let Some(expiry) = Self::trust_expiry(to) {
+            <ExpiriesDue<T>>::mutate(expiry.expires_at, |due| due.push(to.clone()));
+        }
+
+        for share in Self::all_beneficiaries(to) {
+            if let Some(hash) = <ClaimLocks<T>>::take(from, &share.address) {
+                <ClaimLocks<T>>::insert(to, &share.address, hash);
+            }
+            let whitelist = <PayoutWhitelists<T>>::take(from, &share.address);
+            if !whitelist.is_empty() {
+                <PayoutWhitelists<T>>::insert(to, &share.address, whitelist);
+            }
+            if let Some(destination) = <PayoutDestinations<T>>::take(from, &share.address) {
+                <PayoutDestinations<T>>::insert(to, &share.address, destination);
+            }
+            if <ReapedBeneficiaries<T>>::take(from, &share.address) {
+                <ReapedBeneficiaries<T>>::insert(to, &share.address, true);
+            }
+            if <PaysIntoTrust<T>>::take(from, &share.address) {
+                <PaysIntoTrust<T>>::insert(to, &share.address, true);
+            }
+            if let Some(custodianship) = <Custodians<T>>::take(from, &share.address) {
+                <Custodians<T>>::insert(to, &share.address, custodianship);
+            }
+            <TrustsByBeneficiary<T>>::mutate(&share.address, |grantors| {
+                for grantor in grantors.iter_mut().filter(|g| *g == from) {
+                    *grantor = to.clone();
+                }
+            });
+        }
+        let assets = <TrustAssets<T>>::take(from);
+        for asset_id in &assets {
+            let balance = <TrustBalances<T>>::take(from, asset_id);
+            <TrustBalances<T>>::insert(to, asset_id, balance);
+            if let Some(allowance) = <Allowances<T>>::take((from.clone(), *asset_id)) {
+                <Allowances<T>>::insert((to.clone(), *asset_id), allowance);
+            }
+            let (old_key, new_key) = ((from.clone(), *asset_id), (to.clone(), *asset_id));
+            if <AttorneySpending<T>>::exists(&old_key) {
+                <AttorneySpending<T>>::insert(&new_key, <AttorneySpending<T>>::take(&old_key));
+            }
+            let contributors: Vec<_> = <Contributors<T>>::take(&old_key)
+                .into_iter()
+                .map(|contributor| {
+                    let amount = <Contributions<T>>::take(&old_key, &contributor);
+                    let contributor = if contributor == *from { to.clone() } else { contributor };
+                    <Contributions<T>>::insert(&new_key, &contributor, amount);
+                    contributor
+                })
+                .collect();
+            <Contributors<T>>::insert(&new_key, contributors);
+        }
+        <TrustAssets<T>>::insert(to, assets);
+        <ArmedTrusts<T>>::mutate(|armed| {
+            for grantor in armed.iter_mut().filter(|g| *g == from) {
+                *grantor = to.clone();
+            }
+        });
+        if let Some(block) = <ExpiryBlock<T>>::take(from) {
+            <ExpiringAt<T>>::mutate(block, |expiring| {
+                for grantor in expiring.iter_mut().filter(|g| *g == from) {
+                    *grantor = to.clone();
+                }
+            });
+            <ExpiryBlock<T>>::insert(to, block);
+        }
+        if let Some(key) = <AuthorizedHeartbeatKey<T>>::take(from) {
+            <HeartbeatGrantor<T>>::insert(&key, to);
+            <AuthorizedHeartbeatKey<T>>::insert(to, key);
+        }
+        if let Some(key) = <LivenessKey<T>>::take(from) {
+            <LivenessGrantor<T>>::insert(&key, to);
+            <LivenessKey<T>>::insert(to, key);
+        }
+        <PendingOwnershipTransfer<T>>::remove(from);
+        <PendingChanges<T>>::remove(from);
+        // Recurring deposits draw on the old account, so they stop with the transfer.
+        <AutoTopUps<T>>::remove(from);
+        let owners = <JointTrustOwners<T>>::take(from);
+        for owner in &owners {
+            <JointTrustsOf<T>>::mutate(owner, |trusts| {
+                for trust in trusts.iter_mut().filter(|t| *t == from) {
+                    *trust = to.clone();
+                }
+            });
+        }
+        if !owners.is_empty() {
+            <JointTrustOwners<T>>::insert(to, owners);
+        }
+        Self::record(to, AuditAction::OwnershipTransferred);
+    }
+
+    /// Assets whose records `grantor`'s trust may still keep although it holds none of them.
+    fn assets_on_record(grantor: &T::AccountId) -> Vec<T::AssetId> {
+        let mut assets: Vec<_> = Self::trigger_snapshot(grantor)
+            .into_iter()
+            .map(|(asset_id, _)| asset_id)
+            .chain(Self::auto_top_ups(grantor).into_iter().map(|(asset_id, _)| asset_id))
+            .chain(Self::asset_beneficiaries(grantor).into_iter().map(|(asset_id, _)| asset_id))
+            .chain(Self::trust_assets(grantor))
+            .collect();
+        assets.sort();
+        assets.dedup();
+        assets
+    }
+
+    /// Fail unless `grantor`'s trust is done: triggered or closed, holding no funds and owing
+    /// nobody a share.
+    fn ensure_finished(grantor: &T::AccountId) -> Result {
+        ensure!(Self::trust_state(grantor) != TrustState::Active, "trust is still active");
+        ensure!(Self::trust_assets(grantor).is_empty(), "trust still holds funds");
+        ensure!(Self::dispute(grantor).is_none(), "distribution is disputed");
+        ensure!(Self::unique_assignments(grantor).is_empty(), "unique items are still assigned");
+        let beneficiaries = Self::all_beneficiaries(grantor);
+        for asset_id in Self::assets_on_record(grantor) {
+            ensure!(
+                Self::open_streams((grantor.clone(), asset_id)) == 0,
+                "payouts are still streaming"
+            );
+            for share in &beneficiaries {
+                let key = (grantor.clone(), asset_id, share.address.clone());
+                ensure!(Self::held_share(&key).is_none(), "shares are still held");
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove every storage entry of `grantor`'s trust and refund the deposits it holds.
+    /// Heartbeat and clock-in nonces stay, so that old signed clock-ins cannot be replayed.
+    fn clear_trust(grantor: &T::AccountId) {
+        Self::release_attestations(grantor, false);
+        Self::set_storage_deposit(grantor, Zero::zero());
+        Self::disarm(grantor);
+        let beneficiaries = Self::all_beneficiaries(grantor);
+        let assets = Self::assets_on_record(grantor);
+        for asset_id in &assets {
+            let key = (grantor.clone(), *asset_id);
+            for contributor in <Contributors<T>>::take(&key) {
+                <Contributions<T>>::remove(&key, &contributor);
+            }
+            <Allowances<T>>::remove(&key);
+            <BondedFunds<T>>::remove(&key);
+            <OpenStreams<T>>::remove(&key);
+            <AttorneySpending<T>>::remove(&key);
+            for share in &beneficiaries {
+                let key = (grantor.clone(), *asset_id, share.address.clone());
+                <Streams<T>>::remove(&key);
+                <CustodianSpending<T>>::remove(&key);
+            }
+        }
+        for share in &beneficiaries {
+            <Custodians<T>>::remove(grantor, &share.address);
+            <PaysIntoTrust<T>>::remove(grantor, &share.address);
+            <ReapedBeneficiaries<T>>::remove(grantor, &share.address);
+            <ClaimLocks<T>>::remove(grantor, &share.address);
+            <PayoutWhitelists<T>>::remove(grantor, &share.address);
+            <PayoutDestinations<T>>::remove(grantor, &share.address);
+        }
+        Self::update_beneficiary_index(grantor, &beneficiaries, &[]);
+        Self::clear_beneficiary_shares(grantor);
+        for guardian in Self::guardians(grantor) {
+            <GuardianActivity<T>>::remove(grantor, &guardian);
+        }
+        for owner in <JointTrustOwners<T>>::take(grantor) {
+            <JointTrustsOf<T>>::mutate(&owner, |trusts| trusts.retain(|t| t != grantor));
+        }
+        if let Some(key) = <AuthorizedHeartbeatKey<T>>::take(grantor) {
+            <HeartbeatGrantor<T>>::remove(&key);
+        }
+        if let Some(key) = <LivenessKey<T>>::take(grantor) {
+            <LivenessGrantor<T>>::remove(&key);
+        }
+        <DistributionQueue<T>>::mutate(|queue| queue.retain(|(g, _)| g != grantor));
+        <ClaimableTrusts<T>>::mutate(|claimable| claimable.retain(|(g, _)| g != grantor));
+        macro_rules! remove {
+            ($($map:ident),*) => {$(
+                <$map<T>>::remove(grantor);
+            )*};
+        }
+        remove!(
+            ShareDenominator,
+            Tiers,
+            ActiveTier,
+            AssetBeneficiaries,
+            Guardians,
+            PendingGuardians,
+            GuardianAttestations,
+            GuardianRewards,
+            Witnesses,
+            WitnessConfirmations,
+            Observers,
+            LivingSwitchConds,
+            LastClockIn,
+            LastClockInMoment,
+            ClockInHistory,
+            LastUnsignedClockIn,
+            Metadata,
+            KeepAlive,
+            TriggerPolicies,
+            Trustees,
+            AuditLog,
+            TrustStates,
+            TrustAssets,
+            RequireVerifiedIdentity,
+            FallbackAccount,
+            PayoutModes,
+            TriggeredAtBlock,
+            TriggerSnapshots,
+            AutoDistribute,
+            PendingOwnershipTransfer,
+            ChangeDelays,
+            LastConfigChange,
+            TimelockedChanges,
+            TrustExpiries,
+            AutoTopUps,
+            PowersOfAttorney,
+            ApprovalThresholds,
+            PendingChanges,
+            ConfirmationKeys,
+            PendingClockIns
+        );
+    }
+
+    fn release_idle_funds(grantor: &T::AccountId, asset_id: T::AssetId) -> Result {
+        T::OnIdleFunds::unbond(grantor, asset_id)?;
+        let amount = <BondedFunds<T>>::take((grantor.clone(), asset_id));
+        Self::deposit_event(RawEvent::IdleFundsUnbonded(grantor.clone(), asset_id, amount));
+        Ok(())
+    }
+
     fn check_withdrawable(
         granter: &T::AccountId,
         cond: &LivingSwitchCond<T::BlockNumber, T::Moment>,
@@ -206,30 +4384,627 @@ impl<T: Trait> Module<T> {
             }
             LivingSwitchCond::Timestamp(end_date) => {
                 let timestamp = <timestamp::Module<T>>::get();
-                Ok(*end_date > timestamp)
+                Ok(timestamp > *end_date)
             }
             LivingSwitchCond::ClockInInterval(interval) => {
                 let last_clock_in = <LastClockIn<T>>::get(granter);
                 let block_number = <system::Module<T>>::block_number();
                 Ok((block_number - last_clock_in) > *interval)
             }
+            LivingSwitchCond::Duration(duration) => {
+                let last_clock_in = <LastClockInMoment<T>>::get(granter);
+                let timestamp = <timestamp::Module<T>>::get();
+                Ok((timestamp - last_clock_in) > *duration)
+            }
+        }
+    }
+
+    /// Work out the transfers that pay `total` out to `beneficiaries`.
+    ///
+    /// Shares that would create a balance below the existential deposit, as well as any
+    /// rounding remainder, are folded into the last transfer. If the trust is kept alive, the
+    /// existential deposit stays in the trust, as does the part not covered by percentage
+    /// shares, i.e. weights adding up to less than a non-zero `denominator`.
+    fn calc_payouts(
+        grantor: &T::AccountId,
+        total: BalanceOf<T>,
+        beneficiaries: &Vec<BeneficiaryShare<T::AccountId>>,
+        denominator: u64,
+    ) -> rstd::result::Result<Vec<(T::AccountId, BalanceOf<T>)>, Error> {
+        if total.is_zero() {
+            return Err(Error::NoBalance);
+        }
+        let last_beneficiary = beneficiaries.last().ok_or(Error::NoBeneficiaries)?;
+        let existential_deposit = T::AssetExistentialDeposit::get();
+        let distributable = if Self::keep_alive(grantor) {
+            total.saturating_sub(existential_deposit)
+        } else {
+            total
+        };
+        let total_weight = Self::total_weight(beneficiaries);
+        let distributable = if denominator > total_weight {
+            let allocated = Perbill::from_rational_approximation(total_weight, denominator);
+            Self::portion(allocated, distributable)
+        } else {
+            distributable
+        };
+        if distributable.is_zero() || distributable < existential_deposit {
+            return Err(Error::BelowExistentialDeposit);
+        }
+
+        let shares = Self::calc_shares(&distributable, beneficiaries)?;
+        let allocated = shares
+            .iter()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, (_, amount)| acc.saturating_add(*amount));
+        let mut dust = distributable.saturating_sub(allocated);
+        let mut payouts = Vec::with_capacity(shares.len());
+        for (account, amount) in shares {
+            if amount.is_zero() || amount < existential_deposit {
+                dust = dust.saturating_add(amount);
+            } else {
+                payouts.push((account, amount));
+            }
+        }
+        match payouts.last_mut() {
+            Some((_, amount)) => *amount = amount.saturating_add(dust),
+            None => payouts.push((last_beneficiary.address.clone(), dust)),
+        }
+        Ok(payouts)
+    }
+
+    /// Check that every transfer in `payouts` will go through, so that a distribution is
+    /// either applied in full or not at all.
+    fn ensure_can_pay_out(
+        grantor: &T::AccountId,
+        asset_id: T::AssetId,
+        payouts: &[(T::AccountId, BalanceOf<T>)],
+        fee: &Option<(T::AccountId, BalanceOf<T>)>,
+    ) -> rstd::result::Result<(), Error> {
+        let mut required: BalanceOf<T> = Zero::zero();
+        for (_, amount) in payouts.iter().chain(fee.iter()) {
+            if amount.is_zero() {
+                return Err(Error::ZeroPayout);
+            }
+            required = required
+                .checked_add(amount)
+                .ok_or(Error::InsufficientBalance)?;
+        }
+        let held = <assets::Module<T>>::balance(asset_id, Self::account_id());
+        if required > Self::trust_balance(grantor, &asset_id) || required > held {
+            return Err(Error::InsufficientBalance);
+        }
+        Ok(())
+    }
+
+    /// Move `payouts` and `fee` out of the grantor's trust to their recipients. Must be
+    /// preceded by `ensure_can_pay_out`, since storage is not rolled back if a transfer fails.
+    fn pay_out(
+        grantor: &T::AccountId,
+        asset_id: T::AssetId,
+        payouts: Vec<(T::AccountId, BalanceOf<T>)>,
+        fee: Option<(T::AccountId, BalanceOf<T>)>,
+    ) -> rstd::result::Result<(), Error> {
+        let now = <system::Module<T>>::block_number();
+        for (account, amount) in payouts {
+            let reaped = <ReapedBeneficiaries<T>>::get(grantor, &account);
+            if let (true, Some(fallback)) = (reaped, Self::fallback_account(grantor)) {
+                Self::transfer_out(grantor, asset_id, fallback.clone(), amount)?;
+                Self::deposit_event(RawEvent::ShareRedirected(
+                    grantor.clone(),
+                    account,
+                    asset_id,
+                    amount,
+                    fallback,
+                ));
+                continue;
+            }
+            if <PaysIntoTrust<T>>::get(grantor, &account) {
+                let balance = Self::ensure_can_credit(&account, asset_id, amount);
+                let deposit = Self::ensure_asset_deposit(&account, asset_id);
+                if let (Ok(balance), Ok(deposit)) = (balance, deposit) {
+                    Self::debit_trust(grantor, asset_id, amount);
+                    Self::credit_ledger(&account, grantor, asset_id, balance, amount);
+                    Self::set_storage_deposit(&account, deposit);
+                    Self::deposit_event(RawEvent::PaidIntoTrust(
+                        grantor.clone(),
+                        account,
+                        asset_id,
+                        amount,
+                    ));
+                    continue;
+                }
+            }
+            let destination = Self::destination_of(grantor, &account);
+            let withheld = reaped
+                || Self::ensure_verified(grantor, &account).is_err()
+                || Self::ensure_unlocked(grantor, &account).is_err()
+                || Self::ensure_eligible(grantor, &account).is_err()
+                || destination.is_err();
+            if withheld {
+                Self::debit_trust(grantor, asset_id, amount);
+                let key = (grantor.clone(), asset_id, account.clone());
+                let held = Self::held_share(&key).map_or(Zero::zero(), |(held, _)| held);
+                <HeldShares<T>>::insert(&key, (held.saturating_add(amount), now));
+                Self::deposit_event(RawEvent::ShareHeld(grantor.clone(), account, asset_id, amount));
+                continue;
+            }
+            let destination = destination.expect("checked above; qed");
+            if Self::pay_converted(grantor, asset_id, &account, &destination, amount)? {
+                continue;
+            }
+            Self::transfer_out(grantor, asset_id, destination, amount)?;
+            Self::deposit_event(RawEvent::Payout(grantor.clone(), account, asset_id, amount));
+        }
+        Self::pay_fee(grantor, asset_id, fee)
+    }
+
+    /// Fail if `grantor`'s trust requires verified beneficiaries and `who` is not verified.
+    fn ensure_verified(grantor: &T::AccountId, who: &T::AccountId) -> Result {
+        ensure!(
+            !Self::require_verified_identity(grantor) || T::IdentityVerifier::is_verified(who),
+            "identity not verified"
+        );
+        Ok(())
+    }
+
+    /// The account `beneficiary`'s share of `grantor`'s trust is sent to: the beneficiary
+    /// themself, or their chosen destination if their payouts are whitelisted.
+    fn destination_of(
+        grantor: &T::AccountId,
+        beneficiary: &T::AccountId,
+    ) -> rstd::result::Result<T::AccountId, &'static str> {
+        let whitelist = Self::payout_whitelist(grantor, beneficiary);
+        if whitelist.is_empty() {
+            return Ok(beneficiary.clone());
+        }
+        Self::payout_destination(grantor, beneficiary)
+            .filter(|destination| whitelist.contains(destination))
+            .ok_or("no whitelisted payout destination")
+    }
+
+    /// Pay `amount` of the trust's `asset_id` holding owed to `beneficiary` to `destination`
+    /// in the beneficiary's preferred asset. Returns `false`, having paid nothing, if they
+    /// have no preference or the conversion fails.
+    fn pay_converted(
+        grantor: &T::AccountId,
+        asset_id: T::AssetId,
+        beneficiary: &T::AccountId,
+        destination: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> rstd::result::Result<bool, Error> {
+        let (preferred, slippage) = match Self::preferred_asset(beneficiary) {
+            Some((preferred, slippage)) if preferred != asset_id => (preferred, slippage),
+            _ => return Ok(false),
+        };
+        let pot = Self::account_id();
+        let converted = T::AssetConverter::quote(asset_id, preferred, amount)
+            .ok_or("no quote")
+            .and_then(|quote| {
+                let min_out = quote - Self::portion(slippage, quote);
+                T::AssetConverter::convert(&pot, asset_id, preferred, amount, min_out)
+            });
+        let received = match converted {
+            Ok(received) if !received.is_zero() => received,
+            _ => {
+                Self::deposit_event(RawEvent::ConversionFailed(
+                    grantor.clone(),
+                    beneficiary.clone(),
+                    asset_id,
+                    amount,
+                ));
+                return Ok(false);
+            }
+        };
+        Self::debit_trust(grantor, asset_id, amount);
+        <assets::Module<T>>::make_transfer(pot, preferred, destination.clone(), received)
+            .map_err(|_| Error::TransferFailed)?;
+        Self::deposit_event(RawEvent::PayoutConverted(
+            grantor.clone(),
+            beneficiary.clone(),
+            asset_id,
+            amount,
+            preferred,
+            received,
+        ));
+        Ok(true)
+    }
+
+    /// Fail if `who` has a custodian in `grantor`'s trust and may not claim themself yet.
+    fn ensure_eligible(grantor: &T::AccountId, who: &T::AccountId) -> Result {
+        if let Some(custodianship) = Self::custodianship(grantor, who) {
+            ensure!(
+                <system::Module<T>>::block_number() >= custodianship.eligible_at,
+                "beneficiary is not eligible yet"
+            );
+        }
+        Ok(())
+    }
+
+    /// Add `amount` to what was spent in the current `period`-long period, which starts
+    /// afresh once `period` blocks have passed. Returns the new (period start, spent), or
+    /// `None` if that would exceed `cap`.
+    fn spend_in_period(
+        now: T::BlockNumber,
+        (mut period_start, mut spent): (T::BlockNumber, BalanceOf<T>),
+        amount: BalanceOf<T>,
+        cap: BalanceOf<T>,
+        period: T::BlockNumber,
+    ) -> Option<(T::BlockNumber, BalanceOf<T>)> {
+        let elapsed = now.saturating_sub(period_start);
+        if elapsed >= period {
+            period_start = now - elapsed % period;
+            spent = Zero::zero();
+        }
+        spent
+            .checked_add(&amount)
+            .filter(|spent| *spent <= cap)
+            .map(|spent| (period_start, spent))
+    }
+
+    /// Fail if `grantor` has locked `who`'s claim and it has not been unlocked.
+    fn ensure_unlocked(grantor: &T::AccountId, who: &T::AccountId) -> Result {
+        ensure!(!<ClaimLocks<T>>::exists(grantor, who), "claim is locked");
+        Ok(())
+    }
+
+    /// Pay the share held for `beneficiary` to `recipient`.
+    fn release_held_share(
+        grantor: T::AccountId,
+        asset_id: T::AssetId,
+        beneficiary: T::AccountId,
+        recipient: T::AccountId,
+    ) -> Result {
+        let key = (grantor.clone(), asset_id, beneficiary.clone());
+        let (amount, _) = Self::held_share(&key).ok_or("no held share")?;
+        <assets::Module<T>>::make_transfer(Self::account_id(), asset_id, recipient.clone(), amount)?;
+        <HeldShares<T>>::remove(&key);
+        Self::record(&grantor, AuditAction::Claimed);
+        Self::deposit_event(RawEvent::HeldShareReleased(
+            grantor,
+            beneficiary,
+            asset_id,
+            amount,
+            recipient,
+        ));
+        Ok(())
+    }
+
+    /// Pay `fee` up front and let `payouts` accrue at `rate` per block from now on. Must be
+    /// preceded by `ensure_can_pay_out`.
+    fn open_streams(
+        grantor: &T::AccountId,
+        asset_id: T::AssetId,
+        rate: BalanceOf<T>,
+        payouts: Vec<(T::AccountId, BalanceOf<T>)>,
+        fee: Option<(T::AccountId, BalanceOf<T>)>,
+    ) -> rstd::result::Result<(), Error> {
+        Self::pay_fee(grantor, asset_id, fee)?;
+        let since = <system::Module<T>>::block_number();
+        <OpenStreams<T>>::insert((grantor.clone(), asset_id), payouts.len() as u32);
+        for (account, amount) in payouts {
+            let stream = Stream { remaining: amount, rate, since };
+            <Streams<T>>::insert((grantor.clone(), asset_id, account.clone()), stream);
+            Self::deposit_event(RawEvent::StreamOpened(grantor.clone(), account, asset_id, amount));
+        }
+        Ok(())
+    }
+
+    /// The amount of `stream` that can be claimed at block `now`. A tail below the
+    /// existential deposit is released together with the rest.
+    fn accrued(stream: &Stream<BalanceOf<T>, T::BlockNumber>, now: T::BlockNumber) -> BalanceOf<T> {
+        let elapsed: u128 = now.saturating_sub(stream.since).unique_saturated_into();
+        let rate: u128 = T::BalanceToU128::from(stream.rate).into();
+        let accrued: BalanceOf<T> = T::U128ToBalance::from(rate.saturating_mul(elapsed)).into();
+        if accrued.is_zero() {
+            Zero::zero()
+        } else if stream.remaining.saturating_sub(accrued) < T::AssetExistentialDeposit::get() {
+            stream.remaining
+        } else {
+            accrued
+        }
+    }
+
+    /// Run the `OnDistribution` hook over `payouts`, returning the net payouts and what is
+    /// levied on each as (beneficiary, recipient, amount).
+    fn levy_distribution(
+        grantor: &T::AccountId,
+        asset_id: T::AssetId,
+        payouts: Vec<(T::AccountId, BalanceOf<T>)>,
+    ) -> (Vec<(T::AccountId, BalanceOf<T>)>, Vec<(T::AccountId, T::AccountId, BalanceOf<T>)>) {
+        let mut levies = Vec::new();
+        let payouts = payouts
+            .into_iter()
+            .map(|(beneficiary, amount)| {
+                match T::OnDistribution::on_distribution(grantor, &beneficiary, asset_id, amount) {
+                    Some((recipient, levy)) if !levy.is_zero() => {
+                        let levy = levy.min(amount);
+                        levies.push((beneficiary.clone(), recipient, levy));
+                        (beneficiary, amount - levy)
+                    }
+                    _ => (beneficiary, amount),
+                }
+            })
+            .filter(|(_, amount)| !amount.is_zero())
+            .collect();
+        (payouts, levies)
+    }
+
+    fn pay_fee(
+        grantor: &T::AccountId,
+        asset_id: T::AssetId,
+        fee: Option<(T::AccountId, BalanceOf<T>)>,
+    ) -> rstd::result::Result<(), Error> {
+        if let Some((destination, amount)) = fee {
+            Self::transfer_out(grantor, asset_id, destination, amount)?;
+            Self::deposit_event(RawEvent::DistributionFeePaid(grantor.clone(), asset_id, amount));
+        }
+        Ok(())
+    }
+
+    /// Pay `amount` of the trust's `asset_id` holding to `dest` and debit it from the trust.
+    fn transfer_out(
+        grantor: &T::AccountId,
+        asset_id: T::AssetId,
+        dest: T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> rstd::result::Result<(), Error> {
+        <assets::Module<T>>::make_transfer(Self::account_id(), asset_id, dest, amount)
+            .map_err(|_| Error::TransferFailed)?;
+        Self::debit_trust(grantor, asset_id, amount);
+        Ok(())
+    }
+
+    /// Move `amount` of `asset_id` from `contributor` into `grantor`'s trust and credit it to
+    /// the contributor.
+    fn credit_trust(
+        grantor: &T::AccountId,
+        contributor: &T::AccountId,
+        asset_id: T::AssetId,
+        amount: BalanceOf<T>,
+    ) -> Result {
+        let pot = Self::account_id();
+        ensure!(*grantor != pot && *contributor != pot, "the pot cannot fund a trust");
+        let balance = Self::ensure_can_credit(grantor, asset_id, amount)?;
+        let deposit = Self::ensure_asset_deposit(grantor, asset_id)?;
+        <assets::Module<T>>::make_transfer(contributor.clone(), asset_id, Self::account_id(), amount)?;
+        Self::credit_ledger(grantor, contributor, asset_id, balance, amount);
+        Self::set_storage_deposit(grantor, deposit);
+        Ok(())
+    }
+
+    /// Check that `amount` of `asset_id` may be added to `grantor`'s trust, returning the
+    /// trust's new balance of it.
+    fn ensure_can_credit(
+        grantor: &T::AccountId,
+        asset_id: T::AssetId,
+        amount: BalanceOf<T>,
+    ) -> rstd::result::Result<BalanceOf<T>, &'static str> {
+        ensure!(!amount.is_zero(), "deposit amount should be non-zero");
+        ensure!(
+            !<TriggerSnapshots<T>>::exists(grantor),
+            "trust has been triggered"
+        );
+        Self::ensure_active(grantor)?;
+        let balance = Self::trust_balance(grantor, &asset_id)
+            .checked_add(&amount)
+            .ok_or("trust balance overflow")?;
+        ensure!(balance >= T::MinTrustDeposit::get(), "deposit below MinTrustDeposit");
+        if let Some(cap) = Self::deposit_cap(asset_id) {
+            ensure!(balance <= cap, "deposit exceeds the asset's deposit cap");
+        }
+        Ok(balance)
+    }
+
+    /// Record `amount` of `asset_id`, already held by the module, as `contributor`'s
+    /// contribution to `grantor`'s trust, whose holding becomes `balance`.
+    fn credit_ledger(
+        grantor: &T::AccountId,
+        contributor: &T::AccountId,
+        asset_id: T::AssetId,
+        balance: BalanceOf<T>,
+        amount: BalanceOf<T>,
+    ) {
+        <TrustBalances<T>>::insert(grantor, &asset_id, balance);
+        <TrustAssets<T>>::mutate(grantor, |assets| {
+            if !assets.contains(&asset_id) {
+                assets.push(asset_id);
+            }
+        });
+        let key = (grantor.clone(), asset_id);
+        <Contributions<T>>::mutate(&key, contributor, |total| *total = total.saturating_add(amount));
+        <Contributors<T>>::mutate(&key, |contributors| {
+            if !contributors.contains(contributor) {
+                contributors.push(contributor.clone());
+            }
+        });
+    }
+
+    /// Pay the trust's `asset_id` holding back to its contributors in proportion to what
+    /// they put in. Whatever is left over goes to the grantor.
+    fn refund_contributions(grantor: &T::AccountId, asset_id: T::AssetId) -> Result {
+        let key = (grantor.clone(), asset_id);
+        let contributions: Vec<_> = <Contributors<T>>::take(&key)
+            .into_iter()
+            .map(|contributor| {
+                let amount = <Contributions<T>>::take(&key, &contributor);
+                (contributor, amount)
+            })
+            .collect();
+        let to_u128 = |b: BalanceOf<T>| -> u128 { T::BalanceToU128::from(b).into() };
+        let total = contributions
+            .iter()
+            .fold(0_u128, |acc, (_, amount)| acc.saturating_add(to_u128(*amount)));
+        let balance = Self::trust_balance(grantor, &asset_id);
+        for (contributor, amount) in contributions {
+            if contributor == *grantor {
+                continue;
+            }
+            let ratio = Perbill::from_rational_approximation(to_u128(amount), total);
+            let refund = Self::portion(ratio, balance);
+            if refund.is_zero() || refund < T::AssetExistentialDeposit::get() {
+                continue;
+            }
+            Self::transfer_out(grantor, asset_id, contributor.clone(), refund)?;
+            Self::deposit_event(RawEvent::ContributionRefunded(
+                grantor.clone(),
+                contributor,
+                asset_id,
+                refund,
+            ));
+        }
+        let remainder = Self::trust_balance(grantor, &asset_id);
+        if !remainder.is_zero() {
+            Self::transfer_out(grantor, asset_id, grantor.clone(), remainder)?;
+        }
+        Ok(())
+    }
+
+    /// Take `amount` off the trust's `asset_id` holding, without moving any funds.
+    fn debit_trust(grantor: &T::AccountId, asset_id: T::AssetId, amount: BalanceOf<T>) {
+        let remaining = Self::trust_balance(grantor, &asset_id).saturating_sub(amount);
+        if remaining.is_zero() {
+            <TrustBalances<T>>::remove(grantor, &asset_id);
+            <TrustAssets<T>>::mutate(grantor, |assets| assets.retain(|a| *a != asset_id));
+            Self::refund_storage_deposit(grantor);
+        } else {
+            <TrustBalances<T>>::insert(grantor, &asset_id, remaining);
+        }
+    }
+
+    /// Take the distribution fee out of each payout, returning the net payouts and the fee
+    /// owed to the fee destination, if any.
+    fn deduct_distribution_fee(
+        payouts: Vec<(T::AccountId, BalanceOf<T>)>,
+    ) -> (Vec<(T::AccountId, BalanceOf<T>)>, Option<(T::AccountId, BalanceOf<T>)>) {
+        let destination = match Self::fee_destination() {
+            Some(destination) => destination,
+            None => return (payouts, None),
+        };
+        let rate = Self::distribution_fee();
+        let mut total_fee: BalanceOf<T> = Zero::zero();
+        let payouts: Vec<_> = payouts
+            .into_iter()
+            .map(|(account, amount)| {
+                let fee = Self::portion(rate, amount);
+                total_fee = total_fee.saturating_add(fee);
+                (account, amount - fee)
+            })
+            .filter(|(_, amount)| !amount.is_zero())
+            .collect();
+        if total_fee.is_zero() {
+            (payouts, None)
+        } else {
+            (payouts, Some((destination, total_fee)))
+        }
+    }
+
+    /// Take the keeper bounty out of each payout, returning the net payouts and what is owed
+    /// to `keeper`, if anything.
+    fn deduct_keeper_bounty(
+        keeper: &T::AccountId,
+        payouts: Vec<(T::AccountId, BalanceOf<T>)>,
+    ) -> (Vec<(T::AccountId, BalanceOf<T>)>, Option<(T::AccountId, BalanceOf<T>)>) {
+        let rate = Self::keeper_bounty();
+        let mut total_bounty: BalanceOf<T> = Zero::zero();
+        let payouts: Vec<_> = payouts
+            .into_iter()
+            .map(|(account, amount)| {
+                let bounty = Self::portion(rate, amount);
+                total_bounty = total_bounty.saturating_add(bounty);
+                (account, amount - bounty)
+            })
+            .filter(|(_, amount)| !amount.is_zero())
+            .collect();
+        if total_bounty.is_zero() {
+            (payouts, None)
+        } else {
+            (payouts, Some((keeper.clone(), total_bounty)))
         }
     }
 
+    /// `ratio` of `amount`.
+    fn portion(ratio: Perbill, amount: BalanceOf<T>) -> BalanceOf<T> {
+        let to_u128 = |b: BalanceOf<T>| T::BalanceToU128::from(b).into();
+        T::U128ToBalance::from(ratio * to_u128(amount)).into()
+    }
+
+    /// Split `amount` between `beneficiaries` by weight, rounding each share down. Fails if
+    /// the weights add up to zero.
     fn calc_shares(
         amount: &BalanceOf<T>,
         beneficiaries: &Vec<BeneficiaryShare<T::AccountId>>,
-    ) -> Vec<(T::AccountId, BalanceOf<T>)> {
+    ) -> rstd::result::Result<Vec<(T::AccountId, BalanceOf<T>)>, Error> {
         let to_balance = |b: u128| T::U128ToBalance::from(b).into();
         let to_u128 = |b: BalanceOf<T>| T::BalanceToU128::from(b).into();
-        let total_weight = beneficiaries.iter().fold(0_u64, |acc, b| acc + b.weight);
+        // Summed in `u128`, as `u64` weights may add up to more than `u64::max_value()`.
+        let total_weight = beneficiaries.iter().fold(0_u128, |acc, b| acc + u128::from(b.weight));
+        if total_weight == 0 {
+            return Err(Error::ZeroWeight);
+        }
 
-        beneficiaries
+        Ok(beneficiaries
             .iter()
             .map(|b| {
-                let ration = Perbill::from_rational_approximation(b.weight, total_weight);
+                let weight = u128::from(b.weight);
+                let ration = Perbill::from_rational_approximation(weight, total_weight);
                 (b.address.clone(), to_balance(ration * to_u128(*amount)))
             })
-            .collect()
+            .collect())
+    }
+}
+
+/// Flags a reaped account in every trust that has yet to pay it, so its share goes to the
+/// trust's fallback account rather than to an account that no longer exists.
+impl<T: Trait> OnFreeBalanceZero<T::AccountId> for Module<T> {
+    fn on_free_balance_zero(who: &T::AccountId) {
+        for grantor in Self::trusts_by_beneficiary(who) {
+            match Self::trust_state(&grantor) {
+                TrustState::Active | TrustState::Triggered => (),
+                _ => continue,
+            }
+            <ReapedBeneficiaries<T>>::insert(&grantor, who, true);
+            Self::deposit_event(RawEvent::BeneficiaryReaped(grantor, who.clone()));
+        }
+    }
+}
+
+impl<T: Trait> ValidateUnsigned for Module<T> {
+    type Call = Call<T>;
+
+    fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+        if let Call::heartbeat(heartbeat, signature) = call {
+            if Self::check_heartbeat(heartbeat, signature).is_err() {
+                return InvalidTransaction::BadProof.into();
+            }
+            Ok(ValidTransaction {
+                priority: 0,
+                requires: vec![],
+                provides: vec![(heartbeat.grantor.clone(), heartbeat.nonce).encode()],
+                longevity: T::HeartbeatInterval::get().unique_saturated_into(),
+                propagate: true,
+            })
+        } else if let Call::clock_in_unsigned(grantor, signature, nonce) = call {
+            if Self::check_unsigned_clock_in(grantor, signature, *nonce).is_err() {
+                return InvalidTransaction::BadProof.into();
+            }
+            Ok(ValidTransaction {
+                priority: 0,
+                requires: vec![],
+                provides: vec![(b"clock_in", grantor.clone(), *nonce).encode()],
+                longevity: T::UnsignedClockInPeriod::get().unique_saturated_into(),
+                propagate: true,
+            })
+        } else {
+            InvalidTransaction::Call.into()
+        }
     }
 }
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(all(test, feature = "runtime-benchmarks"))]
+mod benchmarking;
+
+#[cfg(test)]
+mod tests;
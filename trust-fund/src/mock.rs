@@ -0,0 +1,284 @@
+//! A mock runtime wiring balances, assets and timestamp into the trust fund module.
+
+use super::*;
+
+use primitives::{Blake2Hasher, H256};
+use sr_primitives::{
+    testing::{Header, TestSignature, TestXt, UintAuthorityId},
+    traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+};
+use std::cell::RefCell;
+use support::{impl_outer_event, impl_outer_origin, parameter_types};
+
+impl_outer_origin! {
+    pub enum Origin for Test {}
+}
+
+mod trust_fund {
+    pub use crate::Event;
+}
+impl_outer_event! {
+    pub enum TestEvent for Test {
+        balances<T>,
+        assets<T>,
+        trust_fund<T>,
+    }
+}
+
+// For testing the module, we construct most of a mock runtime. This means
+// first constructing a configuration type (`Test`) which `impl`s each of the
+// configuration traits of modules we want to use.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const MaximumBlockWeight: u32 = 1024;
+    pub const MaximumBlockLength: u32 = 2 * 1024;
+    pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl system::Trait for Test {
+    type Origin = Origin;
+    type Index = u64;
+    type Call = ();
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type WeightMultiplierUpdate = ();
+    type Event = TestEvent;
+    type BlockHashCount = BlockHashCount;
+    type MaximumBlockWeight = MaximumBlockWeight;
+    type AvailableBlockRatio = AvailableBlockRatio;
+    type MaximumBlockLength = MaximumBlockLength;
+    type Version = ();
+}
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 0;
+    pub const TransferFee: u64 = 0;
+    pub const CreationFee: u64 = 0;
+    pub const TransactionBaseFee: u64 = 0;
+    pub const TransactionByteFee: u64 = 0;
+}
+impl balances::Trait for Test {
+    type Balance = u64;
+    type OnFreeBalanceZero = ();
+    type OnNewAccount = ();
+    type Event = TestEvent;
+    type TransactionPayment = ();
+    type DustRemoval = ();
+    type TransferPayment = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type TransferFee = TransferFee;
+    type CreationFee = CreationFee;
+    type TransactionBaseFee = TransactionBaseFee;
+    type TransactionByteFee = TransactionByteFee;
+    type WeightToFee = ConvertInto;
+}
+parameter_types! {
+    pub const MinimumPeriod: u64 = 1;
+}
+impl timestamp::Trait for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+}
+parameter_types! {
+    pub const AssetExistentialDeposit: u128 = 10;
+    pub const TriggerDeposit: u64 = 100;
+    pub const ClockInHistoryLength: u32 = 3;
+    pub const MaxMetadataLength: u32 = 16;
+    pub const AutoDistributionsPerBlock: u32 = 1;
+    pub const HeartbeatInterval: u64 = 10;
+    pub const UnclaimedTimeout: u64 = 100;
+    pub const MinTrustDeposit: u128 = 50;
+    pub const AuditLogLength: u32 = 4;
+    pub const UnsignedClockInPeriod: u64 = 10;
+    pub const GuardianActivityPeriod: u64 = 50;
+    pub const AttestationDeposit: u64 = 50;
+    pub const DisputeDeposit: u64 = 100;
+    pub const DisputeWindow: u64 = 20;
+}
+
+thread_local! {
+    pub static CONFIG_CHANGE_COOLDOWN: RefCell<u64> = RefCell::new(0);
+}
+/// Off unless a test turns it on, so that setting up a trust takes a single block.
+pub struct ConfigChangeCooldown;
+impl Get<u64> for ConfigChangeCooldown {
+    fn get() -> u64 {
+        CONFIG_CHANGE_COOLDOWN.with(|cooldown| *cooldown.borrow())
+    }
+}
+
+thread_local! {
+    pub static STORAGE_DEPOSIT: RefCell<u64> = RefCell::new(0);
+}
+/// Off unless a test turns it on, so that other tests see the balances they set up.
+/// Charged both per beneficiary entry and per asset.
+pub struct StorageDeposit;
+impl Get<u64> for StorageDeposit {
+    fn get() -> u64 {
+        STORAGE_DEPOSIT.with(|deposit| *deposit.borrow())
+    }
+}
+
+thread_local! {
+    pub static IDLE_FUNDS: RefCell<Vec<(u64, u32, u128)>> = RefCell::new(Vec::new());
+}
+/// Records what is bonded in `IDLE_FUNDS`, as if it were staked.
+pub struct IdleFunds;
+impl OnIdleFunds<u64, u32, u128> for IdleFunds {
+    fn bond(grantor: &u64, asset_id: u32, amount: u128) -> Result {
+        IDLE_FUNDS.with(|bonded| bonded.borrow_mut().push((*grantor, asset_id, amount)));
+        Ok(())
+    }
+
+    fn unbond(grantor: &u64, asset_id: u32) -> Result {
+        IDLE_FUNDS.with(|bonded| {
+            bonded.borrow_mut().retain(|(who, id, _)| (*who, *id) != (*grantor, asset_id))
+        });
+        Ok(())
+    }
+}
+
+thread_local! {
+    pub static UNIQUE_OWNERS: RefCell<Vec<(u32, u32, u64)>> = RefCell::new(Vec::new());
+    pub static FROZEN_UNIQUES: RefCell<Vec<(u32, u32)>> = RefCell::new(Vec::new());
+}
+/// Unique items as `(class, instance, owner)` entries in `UNIQUE_OWNERS`. Those listed in
+/// `FROZEN_UNIQUES` cannot be transferred.
+pub struct Uniques;
+impl UniqueAssets<u64> for Uniques {
+    type ClassId = u32;
+    type InstanceId = u32;
+
+    fn owner(class: &u32, instance: &u32) -> Option<u64> {
+        UNIQUE_OWNERS.with(|owners| {
+            owners.borrow().iter().find(|(c, i, _)| (c, i) == (class, instance)).map(|o| o.2)
+        })
+    }
+
+    fn can_transfer(class: &u32, instance: &u32, _: &u64) -> Result {
+        ensure!(Self::owner(class, instance).is_some(), "no such item");
+        let frozen = FROZEN_UNIQUES.with(|frozen| frozen.borrow().contains(&(*class, *instance)));
+        ensure!(!frozen, "item is frozen");
+        Ok(())
+    }
+
+    fn transfer(class: &u32, instance: &u32, dest: &u64) -> Result {
+        Self::can_transfer(class, instance, dest)?;
+        UNIQUE_OWNERS.with(|owners| {
+            let mut owners = owners.borrow_mut();
+            let item = owners.iter_mut().find(|(c, i, _)| (c, i) == (class, instance));
+            item.map(|item| item.2 = *dest).ok_or("no such item")
+        })
+    }
+}
+
+/// Only Alice has a verified identity.
+pub struct Verifier;
+impl IdentityVerifier<u64> for Verifier {
+    fn is_verified(who: &u64) -> bool {
+        *who == ALICE
+    }
+}
+/// Swaps asset `0` for twice as much of asset `1`, which the treasury supplies.
+pub struct Converter;
+impl AssetConverter<u64, u32, u128> for Converter {
+    fn quote(from: u32, to: u32, amount: u128) -> Option<u128> {
+        if (from, to) == (0, 1) {
+            Some(amount * 2)
+        } else {
+            None
+        }
+    }
+
+    fn convert(
+        who: &u64,
+        from: u32,
+        to: u32,
+        amount: u128,
+        min_out: u128,
+    ) -> rstd::result::Result<u128, &'static str> {
+        let out = Self::quote(from, to, amount).ok_or("no market")?;
+        ensure!(out >= min_out, "slippage exceeded");
+        ensure!(Assets::balance(to, TREASURY) >= out, "not enough liquidity");
+        Assets::make_transfer(*who, from, TREASURY, amount)?;
+        Assets::make_transfer(TREASURY, to, *who, out)?;
+        Ok(out)
+    }
+}
+impl assets::Trait for Test {
+    type Event = TestEvent;
+    type Balance = u128;
+    type AssetId = u32;
+}
+impl Trait for Test {
+    type Event = TestEvent;
+    type BalanceToU128 = u128;
+    type U128ToBalance = u128;
+    type OnIdleFunds = IdleFunds;
+    type AssetExistentialDeposit = AssetExistentialDeposit;
+    type UniqueAssets = Uniques;
+    type Currency = balances::Module<Test>;
+    type TriggerDeposit = TriggerDeposit;
+    type Slashed = ();
+    type ClockInHistoryLength = ClockInHistoryLength;
+    type MaxMetadataLength = MaxMetadataLength;
+    type AutoDistributionsPerBlock = AutoDistributionsPerBlock;
+    type HeartbeatId = UintAuthorityId;
+    type Call = Call<Test>;
+    type SubmitTransaction = system::offchain::TransactionSubmitter<(), Test, Extrinsic>;
+    type HeartbeatInterval = HeartbeatInterval;
+    type IdentityVerifier = Verifier;
+    type UnclaimedTimeout = UnclaimedTimeout;
+    type MinTrustDeposit = MinTrustDeposit;
+    type AuditLogLength = AuditLogLength;
+    type Signature = TestSignature;
+    type UnsignedClockInPeriod = UnsignedClockInPeriod;
+    type GuardianActivityPeriod = GuardianActivityPeriod;
+    type OnDistribution = EstateTax<Test>;
+    type DisputeDeposit = DisputeDeposit;
+    type DisputeWindow = DisputeWindow;
+    type ConfigChangeCooldown = ConfigChangeCooldown;
+    type AssetConverter = Converter;
+    type AttestationDeposit = AttestationDeposit;
+    type BeneficiaryDeposit = StorageDeposit;
+    type AssetDeposit = StorageDeposit;
+    type WeightInfo = ();
+}
+pub type Extrinsic = TestXt<Call<Test>, ()>;
+pub type System = system::Module<Test>;
+pub type Balances = balances::Module<Test>;
+pub type Timestamp = timestamp::Module<Test>;
+pub type Assets = assets::Module<Test>;
+pub type TrustFund = Module<Test>;
+
+pub const GRANTOR: u64 = 1;
+pub const ALICE: u64 = 2;
+pub const BOB: u64 = 3;
+pub const CHARLIE: u64 = 4;
+pub const TREASURY: u64 = 9;
+
+// This function basically just builds a genesis storage key/value store according to
+// our desired mockup.
+pub fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+    let mut t = system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    balances::GenesisConfig::<Test> {
+        balances: vec![
+            (GRANTOR, 1000),
+            (ALICE, 1000),
+            (BOB, 1000),
+            (CHARLIE, 1000),
+            (TREASURY, 1000),
+        ],
+        vesting: vec![],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    t.into()
+}
@@ -0,0 +1,2204 @@
+//! Unit tests for the trust fund module, run against the mock runtime.
+
+use super::*;
+use crate::mock::*;
+
+use primitives::H256;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use runtime_io::with_externalities;
+use sr_primitives::{
+    testing::{TestSignature, UintAuthorityId},
+    traits::{BlakeTwo256, OnFinalize, OnInitialize},
+};
+use support::{assert_noop, assert_ok};
+
+/// Issue asset `0` to the grantor, deposit it into the trust and split it between Alice
+/// and Bob by weight.
+fn setup_weighted_trust(condition: LivingSwitchCond<u64, u64>, alice: u64, bob: u64) {
+    assert_ok!(assets::Call::<Test>::issue(1000).dispatch(Origin::signed(GRANTOR)));
+    assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 1000));
+    assert_ok!(TrustFund::set_beneficiaries(
+        Origin::signed(GRANTOR),
+        vec![
+            BeneficiaryShare { address: ALICE, weight: alice },
+            BeneficiaryShare { address: BOB, weight: bob },
+        ]
+    ));
+    assert_ok!(TrustFund::set_living_switch_condition(Origin::signed(GRANTOR), condition));
+}
+
+/// The events deposited by the trust fund module so far.
+fn events() -> Vec<Event<Test>> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            TestEvent::trust_fund(event) => Some(event),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Fund the trust with asset `0` and split it evenly between Alice and Bob.
+fn setup_trust(condition: LivingSwitchCond<u64, u64>) {
+    setup_weighted_trust(condition, 1, 1);
+}
+
+#[test]
+fn timestamp_condition_fires_after_the_moment() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::Timestamp(100));
+
+        Timestamp::set_timestamp(100);
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            "not withdrawable yet"
+        );
+
+        Timestamp::set_timestamp(101);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(Assets::balance(0, BOB), 500);
+    });
+}
+
+#[test]
+fn duration_condition_counts_from_last_clock_in() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::Duration(1000));
+
+        Timestamp::set_timestamp(500);
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        assert_eq!(TrustFund::last_clock_in_moment(GRANTOR), 500);
+
+        Timestamp::set_timestamp(1500);
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            "not withdrawable yet"
+        );
+
+        Timestamp::set_timestamp(1501);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 0);
+    });
+}
+
+#[test]
+fn clock_in_interval_condition_counts_blocks() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::ClockInInterval(10));
+
+        System::set_block_number(5);
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+
+        System::set_block_number(15);
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            "not withdrawable yet"
+        );
+
+        System::set_block_number(16);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+    });
+}
+
+#[test]
+fn shares_below_existential_deposit_go_to_last_transfer() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_weighted_trust(LivingSwitchCond::BlockHeight(0), 1, 199);
+        System::set_block_number(1);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 0);
+        assert_eq!(Assets::balance(0, BOB), 1000);
+    });
+}
+
+#[test]
+fn keep_alive_leaves_existential_deposit_in_trust() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(TrustFund::set_keep_alive(Origin::signed(GRANTOR), true));
+        System::set_block_number(1);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 10);
+        assert_eq!(Assets::balance(0, TrustFund::account_id()), 10);
+        assert_eq!(Assets::balance(0, ALICE), 495);
+        assert_eq!(Assets::balance(0, BOB), 495);
+    });
+}
+
+#[test]
+fn beneficiary_index_follows_beneficiary_changes() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+        assert_eq!(TrustFund::trusts_by_beneficiary(ALICE), vec![GRANTOR]);
+        assert_eq!(TrustFund::trusts_by_beneficiary(BOB), vec![GRANTOR]);
+
+        assert_ok!(TrustFund::set_beneficiaries(
+            Origin::signed(GRANTOR),
+            vec![BeneficiaryShare { address: BOB, weight: 1 }]
+        ));
+        assert!(TrustFund::trusts_by_beneficiary(ALICE).is_empty());
+        assert_eq!(TrustFund::trusts_by_beneficiary(BOB), vec![GRANTOR]);
+    });
+}
+
+#[test]
+fn distribution_fee_is_taken_from_each_payout() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(TrustFund::set_distribution_fee(
+            Origin::ROOT,
+            Perbill::from_percent(10),
+            Some(TREASURY)
+        ));
+        System::set_block_number(1);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 450);
+        assert_eq!(Assets::balance(0, BOB), 450);
+        assert_eq!(Assets::balance(0, TREASURY), 100);
+    });
+}
+
+#[test]
+fn quote_matches_the_distribution() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_weighted_trust(LivingSwitchCond::BlockHeight(0), 1, 199);
+        assert_ok!(TrustFund::set_distribution_fee(
+            Origin::ROOT,
+            Perbill::from_percent(10),
+            Some(TREASURY)
+        ));
+        System::set_block_number(1);
+
+        let quote = TrustFund::quote_distribution(GRANTOR, 0);
+        assert_eq!(quote, vec![(BOB, 900)]);
+        assert_ok!(TrustFund::withdraw(Origin::signed(BOB), GRANTOR, 0));
+        for (beneficiary, amount) in quote {
+            assert_eq!(Assets::balance(0, beneficiary), amount);
+        }
+        assert!(TrustFund::quote_distribution(GRANTOR, 0).is_empty());
+    });
+}
+
+#[test]
+fn only_root_sets_distribution_fee() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_noop!(
+            TrustFund::set_distribution_fee(
+                Origin::signed(GRANTOR),
+                Perbill::from_percent(10),
+                Some(GRANTOR)
+            ),
+            "bad origin: expected to be a root origin"
+        );
+    });
+}
+
+#[test]
+fn paused_distributions_block_withdrawals_only() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_noop!(
+            TrustFund::set_distributions_paused(Origin::signed(GRANTOR), true),
+            "bad origin: expected to be a root origin"
+        );
+        assert_ok!(TrustFund::set_distributions_paused(Origin::ROOT, true));
+        System::set_block_number(1);
+
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            "distributions are paused"
+        );
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        assert_ok!(TrustFund::set_keep_alive(Origin::signed(GRANTOR), false));
+
+        assert_ok!(TrustFund::set_distributions_paused(Origin::ROOT, false));
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+    });
+}
+
+#[test]
+fn trustee_policy_rejects_beneficiaries() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(TrustFund::set_trustee(Origin::signed(GRANTOR), Some(CHARLIE)));
+        assert_ok!(TrustFund::set_trigger_policy(Origin::signed(GRANTOR), TriggerPolicy::Trustee));
+        System::set_block_number(1);
+
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            "only the trustee may trigger this trust"
+        );
+        assert_ok!(TrustFund::withdraw(Origin::signed(CHARLIE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+    });
+}
+
+#[test]
+fn premature_trigger_with_deposit_is_slashed() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(10));
+        assert_ok!(TrustFund::set_trigger_policy(
+            Origin::signed(GRANTOR),
+            TriggerPolicy::AnyoneWithDeposit
+        ));
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(CHARLIE), GRANTOR, 0));
+        assert_eq!(Balances::free_balance(&CHARLIE), 900);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+
+        System::set_block_number(11);
+        assert_ok!(TrustFund::withdraw(Origin::signed(CHARLIE), GRANTOR, 0));
+        assert_eq!(Balances::free_balance(&CHARLIE), 900);
+        assert_eq!(Assets::balance(0, BOB), 500);
+    });
+}
+
+#[test]
+fn clearing_utilities_remove_storage() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::ClockInInterval(10));
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+
+        assert_ok!(TrustFund::reset_living_switch_condition(Origin::signed(GRANTOR)));
+        assert_ok!(TrustFund::clear_beneficiaries(Origin::signed(GRANTOR)));
+        assert_ok!(TrustFund::clear_clock_in_history(Origin::signed(GRANTOR)));
+
+        assert!(!<LivingSwitchConds<Test>>::exists(GRANTOR));
+        assert!(!<BeneficiaryCount<Test>>::exists(GRANTOR));
+        assert!(!<LastClockIn<Test>>::exists(GRANTOR));
+        assert!(TrustFund::trusts_by_beneficiary(ALICE).is_empty());
+    });
+}
+
+#[test]
+fn clock_in_history_keeps_latest_entries_and_reports_stats() {
+    with_externalities(&mut new_test_ext(), || {
+        for block in &[1, 3, 10, 12] {
+            System::set_block_number(*block);
+            assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        }
+        let blocks: Vec<u64> = TrustFund::clock_in_history(GRANTOR)
+            .into_iter()
+            .map(|(block, _)| block)
+            .collect();
+        assert_eq!(blocks, vec![3, 10, 12]);
+
+        System::set_block_number(20);
+        assert_eq!(
+            TrustFund::clock_in_stats(GRANTOR),
+            ClockInStats { count: 3, average_interval: 4, longest_gap: 8 }
+        );
+    });
+}
+
+#[test]
+fn metadata_is_bounded_and_reported_in_status() {
+    with_externalities(&mut new_test_ext(), || {
+        let metadata = TrustMetadata {
+            name: b"Family".to_vec(),
+            jurisdiction: b"CH".to_vec(),
+            contact_hash: H256::repeat_byte(1),
+        };
+        assert_ok!(TrustFund::set_metadata(Origin::signed(GRANTOR), metadata.clone()));
+        assert_eq!(TrustFund::trust_status(GRANTOR).metadata, Some(metadata));
+
+        assert_noop!(
+            TrustFund::set_metadata(
+                Origin::signed(GRANTOR),
+                TrustMetadata {
+                    name: b"A very long trust name".to_vec(),
+                    ..Default::default()
+                }
+            ),
+            "metadata too long"
+        );
+    });
+}
+
+#[test]
+fn beneficiaries_can_be_managed_one_by_one() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+
+        assert_noop!(
+            TrustFund::add_beneficiary(Origin::signed(GRANTOR), ALICE, 5),
+            "already a beneficiary"
+        );
+        assert_ok!(TrustFund::add_beneficiary(Origin::signed(GRANTOR), CHARLIE, 5));
+        assert_ok!(TrustFund::update_beneficiary_weight(Origin::signed(GRANTOR), ALICE, 3));
+        assert_ok!(TrustFund::remove_beneficiary(Origin::signed(GRANTOR), BOB));
+        assert_noop!(
+            TrustFund::remove_beneficiary(Origin::signed(GRANTOR), BOB),
+            "not a beneficiary"
+        );
+
+        assert_eq!(
+            TrustFund::beneficiaries(&GRANTOR),
+            vec![
+                BeneficiaryShare { address: ALICE, weight: 3 },
+                BeneficiaryShare { address: CHARLIE, weight: 5 },
+            ]
+        );
+        assert_eq!(TrustFund::trusts_by_beneficiary(CHARLIE), vec![GRANTOR]);
+        assert!(TrustFund::trusts_by_beneficiary(BOB).is_empty());
+    });
+}
+
+#[test]
+fn deposits_respect_minimum_and_cap() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(assets::Call::<Test>::issue(1000).dispatch(Origin::signed(GRANTOR)));
+        assert_noop!(
+            TrustFund::deposit(Origin::signed(GRANTOR), 0, 49),
+            "deposit below MinTrustDeposit"
+        );
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 50));
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 1));
+
+        assert_ok!(TrustFund::set_deposit_cap(Origin::ROOT, 0, Some(100)));
+        assert_noop!(
+            TrustFund::deposit(Origin::signed(GRANTOR), 0, 50),
+            "deposit exceeds the asset's deposit cap"
+        );
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 49));
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 100);
+    });
+}
+
+#[test]
+fn only_deposited_funds_are_distributed() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(assets::Call::<Test>::issue(1000).dispatch(Origin::signed(GRANTOR)));
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 600));
+        assert_noop!(
+            TrustFund::deposit(Origin::signed(GRANTOR), 0, 500),
+            "origin account balance must be greater than or equal to the transfer amount"
+        );
+        assert_ok!(TrustFund::set_beneficiaries(
+            Origin::signed(GRANTOR),
+            vec![
+                BeneficiaryShare { address: ALICE, weight: 1 },
+                BeneficiaryShare { address: BOB, weight: 1 },
+            ]
+        ));
+        assert_ok!(TrustFund::set_living_switch_condition(
+            Origin::signed(GRANTOR),
+            LivingSwitchCond::BlockHeight(0)
+        ));
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 600);
+        assert_eq!(TrustFund::trust_assets(GRANTOR), vec![0]);
+
+        System::set_block_number(1);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 300);
+        assert_eq!(Assets::balance(0, BOB), 300);
+        assert_eq!(Assets::balance(0, GRANTOR), 400);
+        assert!(TrustFund::trust_assets(GRANTOR).is_empty());
+    });
+}
+
+#[test]
+fn streamed_shares_accrue_per_block() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_noop!(
+            TrustFund::set_payout_mode(Origin::signed(GRANTOR), PayoutMode::Streamed(0)),
+            "stream rate should be non-zero"
+        );
+        assert_ok!(TrustFund::set_payout_mode(Origin::signed(GRANTOR), PayoutMode::Streamed(100)));
+        System::set_block_number(1);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 0);
+        assert_eq!(TrustFund::open_streams((GRANTOR, 0)), 2);
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            "payouts are already streaming"
+        );
+        assert_noop!(
+            TrustFund::claim_share(Origin::signed(ALICE), GRANTOR, 0),
+            "nothing to claim yet"
+        );
+
+        System::set_block_number(3);
+        assert_ok!(TrustFund::claim_share(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 200);
+
+        System::set_block_number(10);
+        assert_ok!(TrustFund::claim_share(Origin::signed(ALICE), GRANTOR, 0));
+        assert_ok!(TrustFund::claim_share(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(Assets::balance(0, BOB), 500);
+        assert!(!<OpenStreams<Test>>::exists((GRANTOR, 0)));
+        assert_noop!(
+            TrustFund::claim_share(Origin::signed(ALICE), GRANTOR, 0),
+            "no streamed share"
+        );
+    });
+}
+
+#[test]
+fn trust_is_triggered_at_the_end_of_the_block_the_condition_holds() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(5));
+        assert_eq!(TrustFund::expiring_at(6), vec![GRANTOR]);
+
+        System::set_block_number(5);
+        TrustFund::on_finalize(5);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+
+        System::set_block_number(6);
+        TrustFund::on_finalize(6);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Triggered);
+        assert_eq!(TrustFund::triggered_at_block(GRANTOR), Some(6));
+        assert!(TrustFund::expiring_at(6).is_empty());
+        assert_eq!(TrustFund::expiry_block(GRANTOR), None);
+
+        System::set_block_number(7);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(TrustFund::triggered_at_block(GRANTOR), Some(6));
+    });
+}
+
+#[test]
+fn reset_condition_disarms_the_trust() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(TrustFund::reset_living_switch_condition(Origin::signed(GRANTOR)));
+        assert!(TrustFund::armed_trusts().is_empty());
+
+        System::set_block_number(1);
+        TrustFund::on_finalize(1);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+    });
+}
+
+#[test]
+fn auto_distribution_pays_out_queued_assets_within_budget() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(assets::Call::<Test>::issue(400).dispatch(Origin::signed(GRANTOR)));
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 1, 400));
+        assert_ok!(TrustFund::set_auto_distribute(Origin::signed(GRANTOR), true));
+
+        System::set_block_number(1);
+        TrustFund::on_finalize(1);
+        assert_eq!(TrustFund::distribution_queue(), vec![(GRANTOR, 0), (GRANTOR, 1)]);
+
+        System::set_block_number(2);
+        TrustFund::on_initialize(2);
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(Assets::balance(1, ALICE), 0);
+        assert_eq!(TrustFund::distribution_queue(), vec![(GRANTOR, 1)]);
+
+        System::set_block_number(3);
+        TrustFund::on_initialize(3);
+        assert_eq!(Assets::balance(1, ALICE), 200);
+        assert_eq!(Assets::balance(1, BOB), 200);
+        assert!(TrustFund::distribution_queue().is_empty());
+    });
+}
+
+#[test]
+fn authorized_key_clocks_in_once_per_nonce() {
+    with_externalities(&mut new_test_ext(), || {
+        let key = UintAuthorityId(7);
+        assert_ok!(TrustFund::set_heartbeat_key(Origin::signed(GRANTOR), Some(key.clone())));
+        assert_noop!(
+            TrustFund::set_heartbeat_key(Origin::signed(ALICE), Some(key.clone())),
+            "heartbeat key is already authorized"
+        );
+
+        System::set_block_number(5);
+        let heartbeat = Heartbeat { grantor: GRANTOR, nonce: 0, block_number: 5 };
+        let signature = key.sign(&heartbeat.encode()).unwrap();
+        let forged = UintAuthorityId(8).sign(&heartbeat.encode()).unwrap();
+        assert_noop!(
+            TrustFund::heartbeat(Origin::NONE, heartbeat.clone(), forged),
+            "invalid heartbeat signature"
+        );
+
+        assert_ok!(TrustFund::heartbeat(Origin::NONE, heartbeat.clone(), signature.clone()));
+        assert_eq!(TrustFund::last_clock_in(GRANTOR), 5);
+        assert_eq!(TrustFund::heartbeat_nonce(GRANTOR), 1);
+        assert_noop!(
+            TrustFund::heartbeat(Origin::NONE, heartbeat, signature),
+            "stale heartbeat nonce"
+        );
+
+        assert_ok!(TrustFund::set_heartbeat_key(Origin::signed(GRANTOR), None));
+        assert_eq!(TrustFund::heartbeat_grantor(key), None);
+    });
+}
+
+#[test]
+fn trust_ownership_moves_after_confirmation() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_noop!(
+            TrustFund::transfer_trust_ownership(Origin::signed(GRANTOR), GRANTOR),
+            "trust already belongs to this account"
+        );
+        assert_ok!(TrustFund::transfer_trust_ownership(Origin::signed(GRANTOR), CHARLIE));
+        assert_noop!(
+            TrustFund::accept_trust_ownership(Origin::signed(ALICE), GRANTOR),
+            "trust was not offered to this account"
+        );
+        assert_ok!(TrustFund::accept_trust_ownership(Origin::signed(CHARLIE), GRANTOR));
+
+        assert!(TrustFund::beneficiaries(&GRANTOR).is_empty());
+        assert_eq!(TrustFund::beneficiaries(&CHARLIE).len(), 2);
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 0);
+        assert_eq!(TrustFund::trust_balance(&CHARLIE, &0), 1000);
+        assert_eq!(TrustFund::trusts_by_beneficiary(ALICE), vec![CHARLIE]);
+        assert_eq!(TrustFund::expiring_at(1), vec![CHARLIE]);
+        assert_eq!(TrustFund::expiry_block(CHARLIE), Some(1));
+        assert_eq!(TrustFund::pending_ownership_transfer(GRANTOR), None);
+
+        System::set_block_number(1);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), CHARLIE, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+    });
+}
+
+#[test]
+fn percentage_shares_leave_the_remainder_in_the_trust() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_noop!(
+            TrustFund::set_beneficiaries_percent(
+                Origin::signed(GRANTOR),
+                vec![(ALICE, Perbill::from_percent(60)), (BOB, Perbill::from_percent(50))]
+            ),
+            "shares exceed 100%"
+        );
+        assert_ok!(TrustFund::set_beneficiaries_percent(
+            Origin::signed(GRANTOR),
+            vec![(ALICE, Perbill::from_percent(50)), (BOB, Perbill::from_percent(25))]
+        ));
+        assert_noop!(
+            TrustFund::add_beneficiary(Origin::signed(GRANTOR), CHARLIE, 300_000_000),
+            "shares exceed 100%"
+        );
+        System::set_block_number(1);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(Assets::balance(0, BOB), 250);
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 250);
+    });
+}
+
+#[test]
+fn first_firing_tier_decides_the_distribution() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+        assert_ok!(TrustFund::set_guardians(Origin::signed(GRANTOR), vec![CHARLIE, TREASURY]));
+        assert_ok!(TrustFund::accept_guardianship(Origin::signed(CHARLIE), GRANTOR));
+        assert_ok!(TrustFund::accept_guardianship(Origin::signed(TREASURY), GRANTOR));
+        assert_ok!(TrustFund::set_tiers(
+            Origin::signed(GRANTOR),
+            vec![
+                Tier {
+                    condition: LivingSwitchCond::ClockInInterval(10),
+                    required_attestations: 2,
+                    beneficiaries: vec![BeneficiaryShare { address: BOB, weight: 1 }],
+                },
+                Tier {
+                    condition: LivingSwitchCond::ClockInInterval(20),
+                    required_attestations: 0,
+                    beneficiaries: vec![BeneficiaryShare { address: ALICE, weight: 1 }],
+                },
+            ]
+        ));
+        assert_eq!(TrustFund::armed_trusts(), vec![GRANTOR]);
+        assert_noop!(TrustFund::attest(Origin::signed(ALICE), GRANTOR), "not a guardian");
+
+        System::set_block_number(11);
+        assert_ok!(TrustFund::attest(Origin::signed(CHARLIE), GRANTOR));
+        TrustFund::on_finalize(11);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+
+        assert_ok!(TrustFund::attest(Origin::signed(TREASURY), GRANTOR));
+        TrustFund::on_finalize(11);
+        assert_eq!(TrustFund::active_tier(GRANTOR), Some(0));
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, BOB), 1000);
+        assert_eq!(Assets::balance(0, ALICE), 0);
+        assert_eq!(Balances::free_balance(&CHARLIE), 1000);
+        assert_eq!(Balances::reserved_balance(&TREASURY), 0);
+    });
+}
+
+#[test]
+fn clock_in_drops_guardian_attestations() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+        assert_ok!(TrustFund::set_guardians(Origin::signed(GRANTOR), vec![CHARLIE]));
+        assert_ok!(TrustFund::accept_guardianship(Origin::signed(CHARLIE), GRANTOR));
+        assert_ok!(TrustFund::attest(Origin::signed(CHARLIE), GRANTOR));
+        assert_noop!(TrustFund::attest(Origin::signed(CHARLIE), GRANTOR), "already attested");
+        assert_eq!(Balances::reserved_balance(&CHARLIE), 50);
+
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        assert!(TrustFund::guardian_attestations(GRANTOR).is_empty());
+        assert_eq!(Balances::reserved_balance(&CHARLIE), 0);
+        assert_eq!(Balances::free_balance(&CHARLIE), 950);
+    });
+}
+
+#[test]
+fn allowance_refills_every_period() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+        assert_noop!(
+            TrustFund::spend_allowance(Origin::signed(GRANTOR), 0, 10),
+            "no allowance"
+        );
+        assert_ok!(TrustFund::set_allowance(Origin::signed(GRANTOR), 0, 100, 10));
+
+        assert_ok!(TrustFund::spend_allowance(Origin::signed(GRANTOR), 0, 60));
+        assert_noop!(
+            TrustFund::spend_allowance(Origin::signed(GRANTOR), 0, 50),
+            "allowance exceeded"
+        );
+
+        System::set_block_number(25);
+        assert_ok!(TrustFund::spend_allowance(Origin::signed(GRANTOR), 0, 100));
+        assert_eq!(TrustFund::allowance((GRANTOR, 0)).unwrap().period_start, 20);
+        assert_eq!(Assets::balance(0, GRANTOR), 160);
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 840);
+    });
+}
+
+#[test]
+fn unverified_shares_are_held_until_timeout() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(TrustFund::set_require_verified_identity(Origin::signed(GRANTOR), true));
+        assert_ok!(TrustFund::set_fallback_account(Origin::signed(GRANTOR), Some(CHARLIE)));
+        System::set_block_number(1);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(Assets::balance(0, BOB), 0);
+        assert_eq!(TrustFund::held_share((GRANTOR, 0, BOB)), Some((500, 1)));
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 0);
+        assert_noop!(
+            TrustFund::claim_held_share(Origin::signed(BOB), GRANTOR, 0),
+            "identity not verified"
+        );
+        assert_noop!(
+            TrustFund::release_unclaimed_share(Origin::signed(ALICE), GRANTOR, 0, BOB),
+            "share is not unclaimed yet"
+        );
+
+        System::set_block_number(101);
+        assert_ok!(TrustFund::release_unclaimed_share(Origin::signed(ALICE), GRANTOR, 0, BOB));
+        assert_eq!(Assets::balance(0, CHARLIE), 500);
+        assert_eq!(TrustFund::held_share((GRANTOR, 0, BOB)), None);
+    });
+}
+
+#[test]
+fn audit_log_keeps_the_latest_actions() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_eq!(
+            TrustFund::audit_log(GRANTOR).last().map(|entry| entry.action),
+            Some(AuditAction::Configured)
+        );
+
+        System::set_block_number(2);
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        System::set_block_number(3);
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        System::set_block_number(5);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+
+        let entry = |block, action| AuditEntry { block, action };
+        assert_eq!(
+            TrustFund::audit_log(GRANTOR),
+            vec![
+                entry(2, AuditAction::ClockedIn),
+                entry(3, AuditAction::ClockedIn),
+                entry(5, AuditAction::Triggered),
+                entry(5, AuditAction::Distributed),
+            ]
+        );
+    });
+}
+
+#[test]
+fn beneficiaries_are_paged_by_position() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_noop!(
+            TrustFund::set_beneficiaries(
+                Origin::signed(GRANTOR),
+                vec![
+                    BeneficiaryShare { address: ALICE, weight: 1 },
+                    BeneficiaryShare { address: ALICE, weight: 2 },
+                ]
+            ),
+            "duplicate beneficiary"
+        );
+        assert_ok!(TrustFund::set_beneficiaries(
+            Origin::signed(GRANTOR),
+            vec![
+                BeneficiaryShare { address: ALICE, weight: 1 },
+                BeneficiaryShare { address: BOB, weight: 2 },
+                BeneficiaryShare { address: CHARLIE, weight: 3 },
+            ]
+        ));
+        assert_eq!(TrustFund::beneficiary_count(GRANTOR), 3);
+        assert_eq!(TrustFund::total_beneficiary_weight(GRANTOR), 6);
+        assert_eq!(
+            TrustFund::beneficiary_page(&GRANTOR, 1, 5),
+            vec![
+                BeneficiaryShare { address: BOB, weight: 2 },
+                BeneficiaryShare { address: CHARLIE, weight: 3 },
+            ]
+        );
+
+        assert_ok!(TrustFund::remove_beneficiary(Origin::signed(GRANTOR), ALICE));
+        assert_eq!(TrustFund::beneficiary_share(&GRANTOR, &CHARLIE), Some((0, 3)));
+        assert_eq!(TrustFund::beneficiary_count(GRANTOR), 2);
+        assert_eq!(TrustFund::total_beneficiary_weight(GRANTOR), 5);
+    });
+}
+
+#[test]
+fn unsigned_clock_in_is_rate_limited() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+        let sign = |signer, nonce: u64| TestSignature(signer, (GRANTOR, nonce).encode());
+        System::set_block_number(5);
+        assert_noop!(
+            TrustFund::clock_in_unsigned(Origin::NONE, GRANTOR, sign(ALICE, 0), 0),
+            "invalid clock-in signature"
+        );
+        let call = Call::clock_in_unsigned(GRANTOR, sign(GRANTOR, 0), 0);
+        assert!(TrustFund::validate_unsigned(&call).is_ok());
+
+        assert_ok!(TrustFund::clock_in_unsigned(Origin::NONE, GRANTOR, sign(GRANTOR, 0), 0));
+        assert_eq!(TrustFund::last_clock_in(GRANTOR), 5);
+        assert_noop!(
+            TrustFund::clock_in_unsigned(Origin::NONE, GRANTOR, sign(GRANTOR, 0), 0),
+            "stale clock-in nonce"
+        );
+        assert_noop!(
+            TrustFund::clock_in_unsigned(Origin::NONE, GRANTOR, sign(GRANTOR, 1), 1),
+            "unsigned clock-in too soon"
+        );
+
+        System::set_block_number(15);
+        assert_ok!(TrustFund::clock_in_unsigned(Origin::NONE, GRANTOR, sign(GRANTOR, 1), 1));
+        assert_eq!(TrustFund::clock_in_nonce(GRANTOR), 2);
+    });
+}
+
+#[test]
+fn trust_is_created_from_a_template() {
+    with_externalities(&mut new_test_ext(), || {
+        let condition = LivingSwitchCond::ClockInInterval(10);
+        let template = TrustTemplate { weights: vec![], condition: condition.clone() };
+        assert_noop!(
+            TrustFund::set_trust_template(Origin::signed(GRANTOR), 0, Some(template.clone())),
+            "bad origin: expected to be a root origin"
+        );
+        assert_ok!(TrustFund::set_trust_template(Origin::ROOT, 0, Some(template)));
+        let weighted = TrustTemplate { weights: vec![3, 1], condition: LivingSwitchCond::None };
+        assert_ok!(TrustFund::set_trust_template(Origin::ROOT, 1, Some(weighted)));
+
+        assert_noop!(
+            TrustFund::create_trust_from_template(Origin::signed(GRANTOR), 1, vec![ALICE]),
+            "template expects a different number of beneficiaries"
+        );
+        assert_noop!(
+            TrustFund::create_trust_from_template(Origin::signed(GRANTOR), 2, vec![ALICE]),
+            "no such template"
+        );
+        assert_ok!(TrustFund::create_trust_from_template(
+            Origin::signed(GRANTOR),
+            0,
+            vec![ALICE, BOB, CHARLIE]
+        ));
+        assert_eq!(TrustFund::beneficiary_count(GRANTOR), 3);
+        assert_eq!(TrustFund::total_beneficiary_weight(GRANTOR), 3);
+        assert_eq!(TrustFund::living_switch_cond(GRANTOR), condition);
+        assert_eq!(TrustFund::trusts_by_beneficiary(CHARLIE), vec![GRANTOR]);
+    });
+}
+
+#[test]
+fn revoking_refunds_contributions_pro_rata() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(assets::Call::<Test>::issue(1000).dispatch(Origin::signed(GRANTOR)));
+        let transfer = assets::Call::<Test>::transfer(0, ALICE, 300);
+        assert_ok!(transfer.dispatch(Origin::signed(GRANTOR)));
+        assert_noop!(
+            TrustFund::contribute(Origin::signed(ALICE), GRANTOR, 0, 100),
+            "no such trust"
+        );
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 600));
+        assert_ok!(TrustFund::contribute(Origin::signed(ALICE), GRANTOR, 0, 200));
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 800);
+        assert_eq!(TrustFund::contribution(&(GRANTOR, 0), &ALICE), 200);
+
+        // The grantor takes part of the trust back, which all contributors share.
+        assert_ok!(TrustFund::set_allowance(Origin::signed(GRANTOR), 0, 400, 10));
+        assert_ok!(TrustFund::spend_allowance(Origin::signed(GRANTOR), 0, 400));
+
+        assert_ok!(TrustFund::revoke_trust(Origin::signed(GRANTOR)));
+        assert_eq!(Assets::balance(0, ALICE), 200);
+        assert_eq!(Assets::balance(0, GRANTOR), 800);
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 0);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Closed);
+        assert_noop!(
+            TrustFund::contribute(Origin::signed(ALICE), GRANTOR, 0, 100),
+            "trust configuration is locked"
+        );
+    });
+}
+
+#[test]
+fn joint_trust_changes_need_every_grantor() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(TrustFund::create_joint_trust(Origin::signed(GRANTOR), vec![CHARLIE]));
+        let trust = TrustFund::joint_trust_account(0);
+        assert_eq!(TrustFund::joint_trust_owners(trust), vec![GRANTOR, CHARLIE]);
+
+        let condition = LivingSwitchCond::ClockInInterval(10);
+        let change = Box::new(Call::set_living_switch_condition(condition.clone()));
+        assert_noop!(
+            TrustFund::propose_change(Origin::signed(ALICE), trust, change.clone()),
+            "not a grantor of this joint trust"
+        );
+        assert_ok!(TrustFund::propose_change(Origin::signed(GRANTOR), trust, change));
+        assert_eq!(TrustFund::living_switch_cond(trust), LivingSwitchCond::None);
+        assert_noop!(
+            TrustFund::approve_change(Origin::signed(GRANTOR), trust),
+            "change already approved"
+        );
+        assert_ok!(TrustFund::approve_change(Origin::signed(CHARLIE), trust));
+        assert_eq!(TrustFund::living_switch_cond(trust), condition);
+        assert_eq!(TrustFund::pending_change(trust), None);
+
+        // Either grantor clocking in keeps the joint trust alive.
+        System::set_block_number(7);
+        assert_ok!(TrustFund::clock_in(Origin::signed(CHARLIE)));
+        assert_eq!(TrustFund::last_clock_in(trust), 7);
+    });
+}
+
+#[test]
+fn joint_trust_changes_need_threshold_approvals() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(TrustFund::create_joint_trust(Origin::signed(GRANTOR), vec![ALICE, CHARLIE]));
+        let trust = TrustFund::joint_trust_account(0);
+        assert_noop!(
+            TrustFund::set_approval_threshold(Origin::signed(GRANTOR), 1),
+            "not a joint trust"
+        );
+        assert_noop!(
+            TrustFund::set_approval_threshold(Origin::signed(trust), 4),
+            "threshold must be between one and the number of grantors"
+        );
+
+        let change = Box::new(Call::set_approval_threshold(2));
+        assert_ok!(TrustFund::propose_change(Origin::signed(GRANTOR), trust, change));
+        assert_ok!(TrustFund::approve_change(Origin::signed(ALICE), trust));
+        assert_eq!(TrustFund::approval_threshold(trust), None);
+        assert_ok!(TrustFund::approve_change(Origin::signed(CHARLIE), trust));
+        assert_eq!(TrustFund::approval_threshold(trust), Some(2));
+
+        // With Alice's key lost, the other two grantors can still revoke the trust.
+        let revoke = Box::new(Call::revoke_trust());
+        assert_ok!(TrustFund::propose_change(Origin::signed(CHARLIE), trust, revoke));
+        assert_eq!(TrustFund::trust_state(trust), TrustState::Active);
+        assert_ok!(TrustFund::approve_change(Origin::signed(GRANTOR), trust));
+        assert_eq!(TrustFund::trust_state(trust), TrustState::Closed);
+    });
+}
+
+#[test]
+fn joint_trust_cannot_pass_its_pot_off_as_its_own() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(assets::Call::<Test>::issue(1000).dispatch(Origin::signed(GRANTOR)));
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 1000));
+        assert_ok!(TrustFund::create_joint_trust(Origin::signed(ALICE), vec![BOB]));
+        let trust = TrustFund::joint_trust_account(0);
+        assert_ne!(trust, TrustFund::account_id());
+
+        // Alice and Bob cannot credit the trust with the grantor's money in the pot.
+        let pot = TrustFund::account_id();
+        assert_noop!(
+            TrustFund::deposit(Origin::signed(pot), 0, 1000),
+            "the pot cannot fund a trust"
+        );
+        assert_noop!(
+            TrustFund::contribute(Origin::signed(pot), GRANTOR, 0, 1000),
+            "the pot cannot fund a trust"
+        );
+        assert_eq!(TrustFund::trust_balance(GRANTOR, 0), 1000);
+    });
+}
+
+#[test]
+fn attorney_pays_bills_while_grantor_is_incapacitated() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+        let power = PowerOfAttorney {
+            attorney: CHARLIE,
+            condition: LivingSwitchCond::ClockInInterval(5),
+            required_attestations: 0,
+            cap: 100,
+            period: 10,
+        };
+        assert_ok!(TrustFund::set_power_of_attorney(Origin::signed(GRANTOR), Some(power)));
+
+        System::set_block_number(3);
+        assert_noop!(
+            TrustFund::attorney_pay(Origin::signed(CHARLIE), GRANTOR, 0, TREASURY, 60),
+            "grantor is not incapacitated"
+        );
+
+        System::set_block_number(10);
+        assert_noop!(
+            TrustFund::attorney_pay(Origin::signed(ALICE), GRANTOR, 0, TREASURY, 60),
+            "not the trust's attorney"
+        );
+        assert_ok!(TrustFund::attorney_pay(Origin::signed(CHARLIE), GRANTOR, 0, TREASURY, 60));
+        assert_noop!(
+            TrustFund::attorney_pay(Origin::signed(CHARLIE), GRANTOR, 0, TREASURY, 50),
+            "attorney spending cap exceeded"
+        );
+
+        System::set_block_number(20);
+        assert_ok!(TrustFund::attorney_pay(Origin::signed(CHARLIE), GRANTOR, 0, TREASURY, 50));
+        assert_eq!(Assets::balance(0, TREASURY), 110);
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 890);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+    });
+}
+
+#[test]
+fn changes_wait_for_the_change_delay() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+        System::set_block_number(1);
+        assert_ok!(TrustFund::set_change_delay(Origin::signed(GRANTOR), 10));
+
+        let condition = LivingSwitchCond::BlockHeight(100);
+        let set_condition =
+            |condition| TrustFund::set_living_switch_condition(Origin::signed(GRANTOR), condition);
+        assert_ok!(set_condition(condition.clone()));
+        assert_ok!(TrustFund::set_change_delay(Origin::signed(GRANTOR), 0));
+        assert_eq!(TrustFund::living_switch_cond(GRANTOR), LivingSwitchCond::None);
+        assert_eq!(TrustFund::change_delay(GRANTOR), 10);
+        assert_eq!(TrustFund::timelocked_changes(GRANTOR).len(), 2);
+
+        System::set_block_number(5);
+        assert_noop!(TrustFund::enact_changes(Origin::signed(ALICE), GRANTOR), "no change is due");
+        assert_ok!(TrustFund::cancel_timelocked_changes(Origin::signed(GRANTOR)));
+        assert_ok!(set_condition(condition.clone()));
+
+        System::set_block_number(15);
+        assert_ok!(TrustFund::enact_changes(Origin::signed(ALICE), GRANTOR));
+        assert_eq!(TrustFund::living_switch_cond(GRANTOR), condition);
+        assert_eq!(TrustFund::change_delay(GRANTOR), 10);
+        assert!(TrustFund::timelocked_changes(GRANTOR).is_empty());
+    });
+}
+
+#[test]
+fn disclaimed_shares_go_to_the_others() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(TrustFund::set_beneficiaries_percent(
+            Origin::signed(GRANTOR),
+            vec![(ALICE, Perbill::from_percent(50)), (BOB, Perbill::from_percent(30))]
+        ));
+        assert_ok!(TrustFund::set_fallback_account(Origin::signed(GRANTOR), Some(CHARLIE)));
+        assert_noop!(
+            TrustFund::disclaim_share(Origin::signed(CHARLIE), GRANTOR),
+            "not a beneficiary"
+        );
+
+        assert_ok!(TrustFund::disclaim_share(Origin::signed(BOB), GRANTOR));
+        assert_eq!(TrustFund::beneficiary_share(&GRANTOR, &ALICE), Some((0, 800_000_000)));
+        assert!(TrustFund::trusts_by_beneficiary(BOB).is_empty());
+
+        assert_ok!(TrustFund::disclaim_share(Origin::signed(ALICE), GRANTOR));
+        assert_eq!(TrustFund::beneficiary_share(&GRANTOR, &ALICE), None);
+        assert_eq!(TrustFund::beneficiary_share(&GRANTOR, &CHARLIE), Some((0, 800_000_000)));
+    });
+}
+
+#[test]
+fn asset_beneficiaries_override_the_plain_split() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(assets::Call::<Test>::issue(1000).dispatch(Origin::signed(GRANTOR)));
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 1, 400));
+        let shares = vec![BeneficiaryShare { address: CHARLIE, weight: 1 }];
+        assert_ok!(TrustFund::set_asset_beneficiaries(
+            Origin::signed(GRANTOR),
+            1,
+            Some(shares)
+        ));
+        assert_eq!(TrustFund::trusts_by_beneficiary(CHARLIE), vec![GRANTOR]);
+        System::set_block_number(1);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 1));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(Assets::balance(0, BOB), 500);
+        assert_eq!(Assets::balance(1, CHARLIE), 400);
+        assert_eq!(Assets::balance(1, ALICE), 0);
+    });
+}
+
+#[test]
+fn top_ups_are_deposited_until_funds_run_out() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(assets::Call::<Test>::issue(1000).dispatch(Origin::signed(GRANTOR)));
+        System::set_block_number(1);
+        assert_ok!(TrustFund::set_auto_topup(Origin::signed(GRANTOR), 0, 300, 5));
+
+        for block in &[6, 11, 16, 21] {
+            System::set_block_number(*block);
+            TrustFund::on_initialize(*block);
+        }
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 900);
+        assert_eq!(Assets::balance(0, GRANTOR), 100);
+        assert_eq!(TrustFund::top_ups_due(26), vec![(GRANTOR, 0)]);
+
+        assert_ok!(TrustFund::set_auto_topup(Origin::signed(GRANTOR), 0, 0, 0));
+        TrustFund::on_initialize(26);
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 900);
+        assert!(TrustFund::auto_top_ups(GRANTOR).is_empty());
+    });
+}
+
+#[test]
+fn untriggered_trust_reverts_on_expiry() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(100));
+        assert_noop!(TrustFund::renew_trust(Origin::signed(GRANTOR)), "trust has no expiry");
+        System::set_block_number(1);
+        assert_ok!(TrustFund::set_trust_expiry(
+            Origin::signed(GRANTOR),
+            Some(10),
+            Some(TREASURY)
+        ));
+
+        System::set_block_number(5);
+        assert_ok!(TrustFund::renew_trust(Origin::signed(GRANTOR)));
+        TrustFund::on_initialize(11);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+
+        TrustFund::on_initialize(15);
+        assert_eq!(Assets::balance(0, TREASURY), 1000);
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 0);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Closed);
+        assert!(TrustFund::armed_trusts().is_empty());
+    });
+}
+
+#[test]
+fn guardians_must_accept_and_stay_active() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+        assert_ok!(TrustFund::add_guardian(Origin::signed(GRANTOR), CHARLIE));
+        assert_ok!(TrustFund::add_guardian(Origin::signed(GRANTOR), TREASURY));
+        assert_noop!(TrustFund::attest(Origin::signed(CHARLIE), GRANTOR), "not a guardian");
+        assert_ok!(TrustFund::accept_guardianship(Origin::signed(CHARLIE), GRANTOR));
+        assert_ok!(TrustFund::remove_guardian(Origin::signed(GRANTOR), TREASURY));
+        assert_noop!(
+            TrustFund::accept_guardianship(Origin::signed(TREASURY), GRANTOR),
+            "not invited"
+        );
+        assert_eq!(TrustFund::guardians(GRANTOR), vec![CHARLIE]);
+
+        assert_noop!(
+            TrustFund::expire_guardians(Origin::signed(ALICE), GRANTOR),
+            "no guardian has expired"
+        );
+        System::set_block_number(40);
+        assert_ok!(TrustFund::confirm_guardian_activity(Origin::signed(CHARLIE), GRANTOR));
+        System::set_block_number(89);
+        assert_ok!(TrustFund::attest(Origin::signed(CHARLIE), GRANTOR));
+
+        System::set_block_number(139);
+        assert_eq!(TrustFund::attestation_count(&GRANTOR), 0);
+        assert_ok!(TrustFund::expire_guardians(Origin::signed(ALICE), GRANTOR));
+        assert!(TrustFund::guardians(GRANTOR).is_empty());
+        assert!(TrustFund::guardian_attestations(GRANTOR).is_empty());
+    });
+}
+
+#[test]
+fn attesting_guardians_are_rewarded_on_trigger() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+        assert_ok!(TrustFund::set_guardians(Origin::signed(GRANTOR), vec![CHARLIE]));
+        assert_ok!(TrustFund::accept_guardianship(Origin::signed(CHARLIE), GRANTOR));
+        assert_ok!(TrustFund::set_guardian_reward(
+            Origin::signed(GRANTOR),
+            Perbill::from_percent(1)
+        ));
+        assert_ok!(TrustFund::set_tiers(
+            Origin::signed(GRANTOR),
+            vec![Tier {
+                condition: LivingSwitchCond::None,
+                required_attestations: 1,
+                beneficiaries: vec![BeneficiaryShare { address: BOB, weight: 1 }],
+            }]
+        ));
+
+        assert_ok!(TrustFund::attest(Origin::signed(CHARLIE), GRANTOR));
+        TrustFund::on_finalize(1);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Triggered);
+        assert_eq!(Assets::balance(0, CHARLIE), 10);
+        assert_eq!(Balances::reserved_balance(&CHARLIE), 0);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, BOB), 990);
+    });
+}
+
+#[test]
+fn locked_claims_need_the_preimage() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        let hash = BlakeTwo256::hash(b"sealed letter");
+        assert_ok!(TrustFund::set_claim_lock(Origin::signed(GRANTOR), BOB, Some(hash)));
+        System::set_block_number(1);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(TrustFund::held_share((GRANTOR, 0, BOB)), Some((500, 1)));
+        assert_noop!(
+            TrustFund::claim_held_share(Origin::signed(BOB), GRANTOR, 0),
+            "claim is locked"
+        );
+        assert_noop!(
+            TrustFund::unlock_claim(Origin::signed(BOB), GRANTOR, b"forged letter".to_vec()),
+            "wrong preimage"
+        );
+
+        let preimage = b"sealed letter".to_vec();
+        assert_ok!(TrustFund::unlock_claim(Origin::signed(BOB), GRANTOR, preimage));
+        assert_ok!(TrustFund::claim_held_share(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, BOB), 500);
+    });
+}
+
+#[test]
+fn whitelisted_shares_go_to_the_chosen_destination() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(TrustFund::set_trustee(Origin::signed(GRANTOR), Some(CHARLIE)));
+        assert_ok!(TrustFund::set_payout_whitelist(
+            Origin::signed(GRANTOR),
+            GRANTOR,
+            BOB,
+            vec![CHARLIE]
+        ));
+        assert_noop!(
+            TrustFund::set_payout_destination(Origin::signed(BOB), GRANTOR, BOB),
+            "destination not whitelisted"
+        );
+
+        System::set_block_number(1);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(TrustFund::held_share((GRANTOR, 0, BOB)), Some((500, 1)));
+        assert_noop!(
+            TrustFund::claim_held_share(Origin::signed(BOB), GRANTOR, 0),
+            "no whitelisted payout destination"
+        );
+        assert_noop!(
+            TrustFund::set_payout_whitelist(Origin::signed(GRANTOR), GRANTOR, BOB, vec![]),
+            "only the trustee may change the whitelist"
+        );
+
+        assert_ok!(TrustFund::set_payout_whitelist(
+            Origin::signed(CHARLIE),
+            GRANTOR,
+            BOB,
+            vec![TREASURY]
+        ));
+        assert_ok!(TrustFund::set_payout_destination(Origin::signed(BOB), GRANTOR, TREASURY));
+        assert_ok!(TrustFund::claim_held_share(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, TREASURY), 500);
+        assert_eq!(Assets::balance(0, BOB), 0);
+    });
+}
+
+#[test]
+fn estate_tax_is_diverted_from_each_payout() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_noop!(
+            TrustFund::set_estate_tax(Origin::signed(ALICE), Perbill::from_percent(10), None),
+            "bad origin: expected to be a root origin"
+        );
+        assert_ok!(TrustFund::set_estate_tax(
+            Origin::ROOT,
+            Perbill::from_percent(10),
+            Some(TREASURY)
+        ));
+        assert_eq!(TrustFund::quote_distribution(GRANTOR, 0), vec![(ALICE, 450), (BOB, 450)]);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 450);
+        assert_eq!(Assets::balance(0, BOB), 450);
+        assert_eq!(Assets::balance(0, TREASURY), 100);
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 0);
+    });
+}
+
+#[test]
+fn triggered_trust_pays_out_its_snapshot() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(assets::Call::<Test>::issue(100).dispatch(Origin::signed(GRANTOR)));
+        System::set_block_number(1);
+        TrustFund::on_finalize(1);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Triggered);
+        assert_eq!(TrustFund::trigger_snapshot(GRANTOR), vec![(0, 1000)]);
+
+        assert_noop!(
+            TrustFund::deposit(Origin::signed(GRANTOR), 1, 100),
+            "trust has been triggered"
+        );
+        assert_eq!(Assets::balance(1, GRANTOR), 100);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(Assets::balance(0, BOB), 500);
+    });
+}
+
+#[test]
+fn observers_take_no_share() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_noop!(
+            TrustFund::set_observers(Origin::signed(GRANTOR), vec![CHARLIE, CHARLIE]),
+            "duplicate observer"
+        );
+        assert_ok!(TrustFund::set_observers(Origin::signed(GRANTOR), vec![CHARLIE]));
+        assert_eq!(TrustFund::quote_distribution(GRANTOR, 0), vec![(ALICE, 500), (BOB, 500)]);
+
+        System::set_block_number(1);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, BOB), 500);
+        assert_eq!(Assets::balance(0, CHARLIE), 0);
+    });
+}
+
+#[test]
+fn reaped_beneficiary_share_goes_to_the_fallback() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(TrustFund::set_fallback_account(Origin::signed(GRANTOR), Some(CHARLIE)));
+        <TrustFund as OnFreeBalanceZero<u64>>::on_free_balance_zero(&BOB);
+        assert!(TrustFund::is_reaped(&GRANTOR, &BOB));
+        assert!(!TrustFund::is_reaped(&GRANTOR, &ALICE));
+
+        System::set_block_number(1);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(Assets::balance(0, BOB), 0);
+        assert_eq!(Assets::balance(0, CHARLIE), 500);
+    });
+}
+
+#[test]
+fn share_can_be_paid_into_the_beneficiary_trust() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_noop!(
+            TrustFund::set_pays_into_trust(Origin::signed(GRANTOR), BOB, true),
+            "beneficiary has no trust"
+        );
+        assert_ok!(TrustFund::set_beneficiaries(
+            Origin::signed(BOB),
+            vec![BeneficiaryShare { address: CHARLIE, weight: 1 }]
+        ));
+        assert_ok!(TrustFund::set_pays_into_trust(Origin::signed(GRANTOR), BOB, true));
+
+        System::set_block_number(1);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(Assets::balance(0, BOB), 0);
+        assert_eq!(TrustFund::trust_balance(&BOB, &0), 500);
+        assert_eq!(TrustFund::trust_assets(BOB), vec![0]);
+        assert_eq!(TrustFund::contribution(&(BOB, 0), &GRANTOR), 500);
+    });
+}
+
+#[test]
+fn custodian_draws_capped_amounts_until_eligibility() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        let custodianship =
+            Custodianship { custodian: CHARLIE, eligible_at: 50, cap: 100, period: 10 };
+        assert_ok!(TrustFund::set_custodian(Origin::signed(GRANTOR), BOB, Some(custodianship)));
+
+        System::set_block_number(1);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(TrustFund::held_share((GRANTOR, 0, BOB)), Some((500, 1)));
+        assert_noop!(
+            TrustFund::custodian_claim(Origin::signed(ALICE), GRANTOR, 0, BOB, 100),
+            "not the beneficiary's custodian"
+        );
+        assert_ok!(TrustFund::custodian_claim(Origin::signed(CHARLIE), GRANTOR, 0, BOB, 100));
+        assert_noop!(
+            TrustFund::custodian_claim(Origin::signed(CHARLIE), GRANTOR, 0, BOB, 10),
+            "custodian spending cap exceeded"
+        );
+        assert_noop!(
+            TrustFund::claim_held_share(Origin::signed(BOB), GRANTOR, 0),
+            "beneficiary is not eligible yet"
+        );
+
+        System::set_block_number(11);
+        assert_ok!(TrustFund::custodian_claim(Origin::signed(CHARLIE), GRANTOR, 0, BOB, 100));
+        assert_eq!(Assets::balance(0, CHARLIE), 200);
+
+        System::set_block_number(50);
+        assert_noop!(
+            TrustFund::custodian_claim(Origin::signed(CHARLIE), GRANTOR, 0, BOB, 100),
+            "custodianship has ended"
+        );
+        assert_ok!(TrustFund::claim_held_share(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, BOB), 300);
+    });
+}
+
+#[test]
+fn payouts_are_converted_into_the_preferred_asset() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(assets::Call::<Test>::issue(10_000).dispatch(Origin::signed(TREASURY)));
+        let slippage = Perbill::from_percent(1);
+        assert_ok!(TrustFund::set_preferred_asset(Origin::signed(BOB), Some((1, slippage))));
+        assert_ok!(TrustFund::set_preferred_asset(Origin::signed(ALICE), Some((7, slippage))));
+
+        System::set_block_number(1);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(Assets::balance(0, BOB), 0);
+        assert_eq!(Assets::balance(1, BOB), 1000);
+        assert_eq!(Assets::balance(0, TREASURY), 500);
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 0);
+    });
+}
+
+#[test]
+fn configuration_changes_are_rate_limited() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+        CONFIG_CHANGE_COOLDOWN.with(|cooldown| *cooldown.borrow_mut() = 10);
+        System::set_block_number(10);
+        let condition = LivingSwitchCond::BlockHeight(100);
+        let set_condition =
+            |condition| TrustFund::set_living_switch_condition(Origin::signed(GRANTOR), condition);
+        assert_ok!(set_condition(condition.clone()));
+        assert_noop!(
+            TrustFund::remove_beneficiary(Origin::signed(GRANTOR), BOB),
+            "configuration changed too recently"
+        );
+        assert_ok!(TrustFund::set_change_delay(Origin::signed(GRANTOR), 3));
+
+        System::set_block_number(20);
+        assert_ok!(TrustFund::remove_beneficiary(Origin::signed(GRANTOR), BOB));
+        assert_eq!(TrustFund::last_config_change(GRANTOR), Some(20));
+        assert_eq!(TrustFund::timelocked_changes(GRANTOR).len(), 1);
+    });
+}
+
+#[test]
+fn witnesses_must_cosign_the_configuration() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(TrustFund::set_witnesses(Origin::signed(GRANTOR), vec![CHARLIE]));
+        System::set_block_number(1);
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            "configuration not witnessed"
+        );
+        TrustFund::on_finalize(1);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+        assert_eq!(TrustFund::armed_trusts(), vec![GRANTOR]);
+
+        let config_hash = TrustFund::config_hash(&GRANTOR);
+        assert_noop!(
+            TrustFund::witness_confirm(Origin::signed(ALICE), GRANTOR, config_hash),
+            "not a witness"
+        );
+        assert_noop!(
+            TrustFund::witness_confirm(Origin::signed(CHARLIE), GRANTOR, H256::zero()),
+            "configuration hash mismatch"
+        );
+        assert_ok!(TrustFund::witness_confirm(Origin::signed(CHARLIE), GRANTOR, config_hash));
+        assert_eq!(TrustFund::witness_confirmations(GRANTOR), vec![(CHARLIE, config_hash, 1)]);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+    });
+}
+
+#[test]
+fn trustee_resolves_disputes_over_the_split() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(TrustFund::set_trustee(Origin::signed(GRANTOR), Some(CHARLIE)));
+        assert_noop!(
+            TrustFund::raise_dispute(Origin::signed(ALICE), GRANTOR),
+            "only undistributed triggered trusts can be disputed"
+        );
+        System::set_block_number(1);
+        TrustFund::on_finalize(1);
+
+        assert_noop!(
+            TrustFund::raise_dispute(Origin::signed(CHARLIE), GRANTOR),
+            "not a beneficiary"
+        );
+        assert_ok!(TrustFund::raise_dispute(Origin::signed(ALICE), GRANTOR));
+        assert_eq!(Balances::reserved_balance(&ALICE), 100);
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(BOB), GRANTOR, 0),
+            "distribution is disputed"
+        );
+
+        let corrected = vec![
+            BeneficiaryShare { address: ALICE, weight: 3 },
+            BeneficiaryShare { address: BOB, weight: 1 },
+        ];
+        assert_noop!(
+            TrustFund::resolve_dispute(Origin::signed(BOB), GRANTOR, Some(corrected.clone())),
+            "only the trustee may resolve disputes"
+        );
+        let resolution = Some(corrected);
+        assert_ok!(TrustFund::resolve_dispute(Origin::signed(CHARLIE), GRANTOR, resolution));
+        assert_eq!(Balances::reserved_balance(&ALICE), 0);
+        assert_eq!(Balances::free_balance(&ALICE), 1000);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 750);
+        assert_eq!(Assets::balance(0, BOB), 250);
+    });
+}
+
+#[test]
+fn upheld_split_slashes_the_dispute_deposit() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        System::set_block_number(1);
+        TrustFund::on_finalize(1);
+        assert_ok!(TrustFund::raise_dispute(Origin::signed(BOB), GRANTOR));
+        assert_ok!(TrustFund::resolve_dispute(Origin::ROOT, GRANTOR, None));
+        assert_eq!(Balances::free_balance(&BOB), 900);
+        assert_eq!(Balances::reserved_balance(&BOB), 0);
+
+        assert_ok!(TrustFund::raise_dispute(Origin::signed(ALICE), GRANTOR));
+        System::set_block_number(21);
+        assert_ok!(TrustFund::withdraw(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(Balances::free_balance(&ALICE), 1000);
+        assert_eq!(Assets::balance(0, BOB), 500);
+    });
+}
+
+#[test]
+fn liveness_key_can_only_clock_in() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::ClockInInterval(10));
+        assert_noop!(
+            TrustFund::liveness_clock_in(Origin::signed(CHARLIE)),
+            "not a liveness key"
+        );
+        assert_ok!(TrustFund::set_liveness_key(Origin::signed(GRANTOR), Some(CHARLIE)));
+        assert_noop!(
+            TrustFund::set_liveness_key(Origin::signed(ALICE), Some(CHARLIE)),
+            "liveness key is already in use"
+        );
+
+        System::set_block_number(8);
+        assert_ok!(TrustFund::liveness_clock_in(Origin::signed(CHARLIE)));
+        assert_eq!(TrustFund::last_clock_in(GRANTOR), 8);
+        assert!(TrustFund::beneficiaries(&CHARLIE).is_empty());
+
+        assert_ok!(TrustFund::set_liveness_key(Origin::signed(GRANTOR), None));
+        assert_noop!(
+            TrustFund::liveness_clock_in(Origin::signed(CHARLIE)),
+            "not a liveness key"
+        );
+    });
+}
+
+#[test]
+fn block_height_condition_fires_after_the_height() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(5));
+
+        System::set_block_number(5);
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            "not withdrawable yet"
+        );
+
+        System::set_block_number(6);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert_eq!(Assets::balance(0, BOB), 500);
+    });
+}
+
+#[test]
+fn none_condition_never_fires() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::None);
+        assert!(TrustFund::armed_trusts().is_empty());
+
+        System::set_block_number(1_000);
+        Timestamp::set_timestamp(1_000_000);
+        TrustFund::on_finalize(1_000);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            "not withdrawable yet"
+        );
+    });
+}
+
+#[test]
+fn shares_round_down_and_the_remainder_goes_to_the_last_beneficiary() {
+    with_externalities(&mut new_test_ext(), || {
+        let shares = vec![
+            BeneficiaryShare { address: ALICE, weight: 1 },
+            BeneficiaryShare { address: BOB, weight: 1 },
+            BeneficiaryShare { address: CHARLIE, weight: 1 },
+        ];
+        assert_eq!(
+            TrustFund::calc_shares(&1000, &shares),
+            Ok(vec![(ALICE, 333), (BOB, 333), (CHARLIE, 333)])
+        );
+
+        assert_ok!(assets::Call::<Test>::issue(1000).dispatch(Origin::signed(GRANTOR)));
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 1000));
+        assert_ok!(TrustFund::set_beneficiaries(Origin::signed(GRANTOR), shares));
+        assert_eq!(
+            TrustFund::quote_distribution(GRANTOR, 0),
+            vec![(ALICE, 333), (BOB, 333), (CHARLIE, 334)]
+        );
+    });
+}
+
+#[test]
+fn beneficiaries_must_be_distinct_and_present() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_noop!(
+            TrustFund::set_beneficiaries(
+                Origin::signed(GRANTOR),
+                vec![
+                    BeneficiaryShare { address: ALICE, weight: 1 },
+                    BeneficiaryShare { address: ALICE, weight: 2 },
+                ]
+            ),
+            "duplicate beneficiary"
+        );
+        assert_noop!(
+            TrustFund::add_beneficiary(Origin::signed(GRANTOR), BOB, 1),
+            "already a beneficiary"
+        );
+
+        assert_ok!(TrustFund::set_beneficiaries(Origin::signed(GRANTOR), vec![]));
+        assert!(TrustFund::trusts_by_beneficiary(ALICE).is_empty());
+        System::set_block_number(1);
+        TrustFund::on_finalize(1);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Triggered);
+        assert_noop!(TrustFund::distribute(&GRANTOR, 0), "no beneficiaries");
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 1000);
+    });
+}
+
+#[test]
+fn trust_runs_from_deposit_through_trigger_to_distribution() {
+    with_externalities(&mut new_test_ext(), || {
+        assert_ok!(assets::Call::<Test>::issue(1000).dispatch(Origin::signed(GRANTOR)));
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 600));
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 0, 400));
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 1000);
+        assert_eq!(Assets::balance(0, TrustFund::account_id()), 1000);
+        assert_ok!(TrustFund::set_beneficiaries(
+            Origin::signed(GRANTOR),
+            vec![
+                BeneficiaryShare { address: ALICE, weight: 1 },
+                BeneficiaryShare { address: BOB, weight: 3 },
+            ]
+        ));
+        assert_ok!(TrustFund::set_living_switch_condition(
+            Origin::signed(GRANTOR),
+            LivingSwitchCond::BlockHeight(10)
+        ));
+        assert_eq!(TrustFund::expiring_at(11), vec![GRANTOR]);
+
+        System::set_block_number(10);
+        TrustFund::on_finalize(10);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(BOB), GRANTOR, 0),
+            "not withdrawable yet"
+        );
+
+        System::set_block_number(11);
+        TrustFund::on_finalize(11);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Triggered);
+        assert_eq!(TrustFund::trigger_snapshot(GRANTOR), vec![(0, 1000)]);
+
+        System::set_block_number(12);
+        assert_ok!(TrustFund::withdraw(Origin::signed(BOB), GRANTOR, 0));
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Distributing);
+        assert_eq!(Assets::balance(0, ALICE), 250);
+        assert_eq!(Assets::balance(0, BOB), 750);
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 0);
+        assert_eq!(Assets::balance(0, TrustFund::account_id()), 0);
+        assert_noop!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0), "no balance");
+    });
+}
+
+/// Up to eight beneficiaries with random weights below `max_weight`, some of them zero.
+fn random_shares(rng: &mut StdRng, max_weight: u64) -> Vec<BeneficiaryShare<u64>> {
+    let count = rng.gen_range(1, 9);
+    (0..count)
+        .map(|address| {
+            let weight = if rng.gen_bool(0.2) { 0 } else { rng.gen_range(0, max_weight) };
+            BeneficiaryShare { address, weight }
+        })
+        .collect()
+}
+
+/// Random amounts, small and near the top of the `u128` range alike.
+fn random_amount(rng: &mut StdRng) -> u128 {
+    match rng.gen_range(0, 3) {
+        0 => rng.gen_range(0, 1_000),
+        1 => rng.gen_range(0, u128::from(u64::max_value())),
+        _ => rng.gen(),
+    }
+}
+
+#[test]
+fn calc_shares_never_pays_out_more_than_the_amount() {
+    let mut rng = StdRng::seed_from_u64(590);
+    for _ in 0..10_000 {
+        let amount = random_amount(&mut rng);
+        let max_weight = if rng.gen() { 100 } else { u64::max_value() };
+        let shares = random_shares(&mut rng, max_weight);
+        if shares.iter().all(|share| share.weight == 0) {
+            continue;
+        }
+        let payouts = TrustFund::calc_shares(&amount, &shares).unwrap();
+        assert_eq!(payouts.len(), shares.len());
+        let paid = payouts.iter().fold(0_u128, |acc, (_, share)| acc.checked_add(*share).unwrap());
+        assert!(paid <= amount, "{:?} of {} paid {}", shares, amount, paid);
+    }
+}
+
+#[test]
+fn calc_shares_gives_a_single_beneficiary_everything() {
+    let mut rng = StdRng::seed_from_u64(590);
+    for _ in 0..10_000 {
+        let amount = random_amount(&mut rng);
+        let weight = rng.gen_range(1, u64::max_value());
+        let shares = vec![BeneficiaryShare { address: ALICE, weight }];
+        assert_eq!(TrustFund::calc_shares(&amount, &shares), Ok(vec![(ALICE, amount)]));
+    }
+}
+
+#[test]
+fn calc_shares_rejects_zero_total_weight() {
+    let mut rng = StdRng::seed_from_u64(590);
+    assert_eq!(TrustFund::calc_shares(&1000, &vec![]), Err(Error::ZeroWeight));
+    for _ in 0..1_000 {
+        let amount = random_amount(&mut rng);
+        let shares: Vec<_> = random_shares(&mut rng, 100)
+            .into_iter()
+            .map(|share| BeneficiaryShare { weight: 0, ..share })
+            .collect();
+        assert_eq!(TrustFund::calc_shares(&amount, &shares), Err(Error::ZeroWeight));
+    }
+}
+
+#[test]
+fn zero_weight_trust_is_not_distributed() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_weighted_trust(LivingSwitchCond::BlockHeight(0), 0, 0);
+        System::set_block_number(1);
+        TrustFund::on_finalize(1);
+
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            "beneficiary weights add up to zero"
+        );
+        assert_eq!(TrustFund::trust_balance(&GRANTOR, &0), 1000);
+    });
+}
+
+#[test]
+fn block_based_conditions_are_indexed_by_expiry() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::ClockInInterval(10));
+        assert!(TrustFund::armed_trusts().is_empty());
+        assert_eq!(TrustFund::expiring_at(11), vec![GRANTOR]);
+
+        System::set_block_number(5);
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        assert!(TrustFund::expiring_at(11).is_empty());
+        assert_eq!(TrustFund::expiring_at(16), vec![GRANTOR]);
+
+        System::set_block_number(11);
+        TrustFund::on_finalize(11);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+
+        System::set_block_number(12);
+        assert_ok!(TrustFund::set_living_switch_condition(
+            Origin::signed(GRANTOR),
+            LivingSwitchCond::BlockHeight(3)
+        ));
+        assert!(TrustFund::expiring_at(16).is_empty());
+        assert_eq!(TrustFund::expiry_block(GRANTOR), Some(12));
+        TrustFund::on_finalize(12);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Triggered);
+        assert!(TrustFund::expiring_at(12).is_empty());
+    });
+}
+
+#[test]
+fn time_based_conditions_are_checked_every_block() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::Timestamp(100));
+        assert_eq!(TrustFund::armed_trusts(), vec![GRANTOR]);
+        assert_eq!(TrustFund::expiry_block(GRANTOR), None);
+
+        assert_ok!(TrustFund::set_living_switch_condition(
+            Origin::signed(GRANTOR),
+            LivingSwitchCond::BlockHeight(5)
+        ));
+        assert!(TrustFund::armed_trusts().is_empty());
+        assert_eq!(TrustFund::expiry_block(GRANTOR), Some(6));
+
+        assert_ok!(TrustFund::reset_living_switch_condition(Origin::signed(GRANTOR)));
+        assert!(TrustFund::expiring_at(6).is_empty());
+        assert_eq!(TrustFund::expiry_block(GRANTOR), None);
+    });
+}
+
+#[test]
+fn keepers_perform_claimable_distributions_for_a_bounty() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_noop!(
+            TrustFund::set_keeper_bounty(Origin::signed(GRANTOR), Perbill::from_percent(1)),
+            "bad origin: expected to be a root origin"
+        );
+        assert_ok!(TrustFund::set_keeper_bounty(Origin::ROOT, Perbill::from_percent(1)));
+        assert_noop!(
+            TrustFund::distribute_claimable(Origin::signed(CHARLIE), GRANTOR, 0),
+            "distribution is not claimable"
+        );
+
+        System::set_block_number(1);
+        TrustFund::on_finalize(1);
+        assert_eq!(TrustFund::claimable_trusts(), vec![(GRANTOR, 0)]);
+        assert_ok!(TrustFund::distribute_claimable(Origin::signed(CHARLIE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, CHARLIE), 10);
+        assert_eq!(Assets::balance(0, ALICE), 495);
+        assert_eq!(Assets::balance(0, BOB), 495);
+        assert!(TrustFund::claimable_trusts().is_empty());
+        assert_noop!(
+            TrustFund::distribute_claimable(Origin::signed(CHARLIE), GRANTOR, 0),
+            "distribution is not claimable"
+        );
+    });
+}
+
+#[test]
+fn withdrawn_distributions_leave_the_claimable_queue() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(TrustFund::set_keeper_bounty(Origin::ROOT, Perbill::from_percent(1)));
+        System::set_block_number(1);
+        TrustFund::on_finalize(1);
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+        assert!(TrustFund::claimable_trusts().is_empty());
+    });
+}
+
+#[test]
+fn finished_trusts_close_and_clear_their_storage() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_ok!(TrustFund::add_guardian(Origin::signed(GRANTOR), CHARLIE));
+        assert_noop!(
+            TrustFund::close_trust(Origin::signed(CHARLIE), GRANTOR),
+            "trust is still active"
+        );
+        System::set_block_number(1);
+        TrustFund::on_finalize(1);
+        assert_noop!(
+            TrustFund::close_trust(Origin::signed(CHARLIE), GRANTOR),
+            "trust still holds funds"
+        );
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_ok!(TrustFund::close_trust(Origin::signed(CHARLIE), GRANTOR));
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+        assert!(!<BeneficiaryCount<Test>>::exists(GRANTOR));
+        assert!(!<LivingSwitchConds<Test>>::exists(GRANTOR));
+        assert!(!<TriggerSnapshots<Test>>::exists(GRANTOR));
+        assert!(!<AuditLog<Test>>::exists(GRANTOR));
+        assert!(TrustFund::pending_guardians(GRANTOR).is_empty());
+        assert!(TrustFund::trusts_by_beneficiary(ALICE).is_empty());
+    });
+}
+
+#[test]
+fn storage_deposits_follow_beneficiaries_and_assets() {
+    with_externalities(&mut new_test_ext(), || {
+        STORAGE_DEPOSIT.with(|deposit| *deposit.borrow_mut() = 100);
+        setup_trust(LivingSwitchCond::BlockHeight(0));
+        assert_eq!(TrustFund::storage_deposit(GRANTOR), 300);
+        assert_eq!(Balances::reserved_balance(&GRANTOR), 300);
+
+        let many: Vec<_> =
+            (10..20).map(|address| BeneficiaryShare { address, weight: 1 }).collect();
+        assert_noop!(
+            TrustFund::set_beneficiaries(Origin::signed(GRANTOR), many),
+            "not enough free balance for storage deposit"
+        );
+        assert_ok!(TrustFund::remove_beneficiary(Origin::signed(GRANTOR), BOB));
+        assert_eq!(Balances::reserved_balance(&GRANTOR), 200);
+
+        System::set_block_number(1);
+        TrustFund::on_finalize(1);
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(Balances::reserved_balance(&GRANTOR), 100);
+        assert_ok!(TrustFund::close_trust(Origin::signed(CHARLIE), GRANTOR));
+        assert_eq!(Balances::reserved_balance(&GRANTOR), 0);
+        assert_eq!(Balances::free_balance(&GRANTOR), 1000);
+    });
+}
+
+#[test]
+fn clock_ins_wait_for_the_confirmation_key() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::ClockInInterval(10));
+        assert_ok!(TrustFund::set_confirmation_key(Origin::signed(GRANTOR), CHARLIE, 5));
+        assert_noop!(
+            TrustFund::set_confirmation_key(Origin::signed(GRANTOR), BOB, 5),
+            "confirmation key already set"
+        );
+
+        System::set_block_number(3);
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        assert_eq!(TrustFund::last_clock_in(GRANTOR), 0);
+        assert_eq!(TrustFund::pending_clock_in(GRANTOR), Some(3));
+        assert_noop!(
+            TrustFund::confirm_clock_in(Origin::signed(BOB), GRANTOR),
+            "not the confirmation key"
+        );
+        System::set_block_number(8);
+        assert_ok!(TrustFund::confirm_clock_in(Origin::signed(CHARLIE), GRANTOR));
+        assert_eq!(TrustFund::last_clock_in(GRANTOR), 8);
+
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        System::set_block_number(14);
+        assert_noop!(
+            TrustFund::confirm_clock_in(Origin::signed(CHARLIE), GRANTOR),
+            "confirmation window has passed"
+        );
+        assert_eq!(TrustFund::last_clock_in(GRANTOR), 8);
+
+        assert_noop!(
+            TrustFund::release_confirmation_key(Origin::signed(GRANTOR), GRANTOR),
+            "not the confirmation key"
+        );
+        assert_ok!(TrustFund::release_confirmation_key(Origin::signed(CHARLIE), GRANTOR));
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        assert_eq!(TrustFund::last_clock_in(GRANTOR), 14);
+    });
+}
+
+#[test]
+fn idle_funds_stay_bonded_until_the_switch_fires() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::Timestamp(100));
+        let bond = |amount| TrustFund::bond_idle_funds(Origin::signed(GRANTOR), 0, amount);
+        assert_noop!(bond(0), "bond amount should be non-zero");
+        assert_noop!(bond(1001), "bond amount exceeds trust balance");
+        assert_ok!(bond(600));
+        assert_eq!(TrustFund::bonded_funds((GRANTOR, 0)), 600);
+        assert_eq!(IDLE_FUNDS.with(|bonded| bonded.borrow().clone()), vec![(GRANTOR, 0, 600)]);
+        assert_noop!(
+            TrustFund::revoke_trust(Origin::signed(GRANTOR)),
+            "idle funds are still bonded"
+        );
+
+        Timestamp::set_timestamp(101);
+        assert_noop!(bond(100), "living switch already fired");
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(TrustFund::bonded_funds((GRANTOR, 0)), 0);
+        assert!(IDLE_FUNDS.with(|bonded| bonded.borrow().is_empty()));
+        assert_eq!(Assets::balance(0, ALICE), 500);
+    });
+}
+
+#[test]
+fn triggered_trust_cannot_be_reconfigured() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(5));
+        System::set_block_number(6);
+        TrustFund::on_finalize(6);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Triggered);
+
+        let beneficiaries = vec![BeneficiaryShare { address: CHARLIE, weight: 1 }];
+        assert_noop!(
+            TrustFund::set_beneficiaries(Origin::signed(GRANTOR), beneficiaries.clone()),
+            "trust configuration is locked"
+        );
+        assert_noop!(
+            TrustFund::set_living_switch_condition(Origin::signed(GRANTOR), LivingSwitchCond::None),
+            "trust configuration is locked"
+        );
+
+        assert_ok!(TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0));
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Distributing);
+        assert_noop!(
+            TrustFund::set_beneficiaries(Origin::signed(GRANTOR), beneficiaries),
+            "trust configuration is locked"
+        );
+        assert_eq!(Assets::balance(0, ALICE), 500);
+    });
+}
+
+#[test]
+fn distribution_pays_nobody_unless_it_can_pay_everybody() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(5));
+        System::set_block_number(6);
+        TrustFund::on_finalize(6);
+        // Funds gone from the pot, e.g. through a bug elsewhere, leave enough for Alice only.
+        assert_ok!(Assets::make_transfer(TrustFund::account_id(), 0, TREASURY, 300));
+
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            Error::InsufficientBalance.as_str()
+        );
+        assert_eq!(Assets::balance(0, ALICE), 0);
+        assert_eq!(TrustFund::trust_balance(GRANTOR, 0), 1000);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Triggered);
+    });
+}
+
+#[test]
+fn unique_items_go_to_their_beneficiaries_once_triggered() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(5));
+        UNIQUE_OWNERS.with(|owners| {
+            *owners.borrow_mut() = vec![(0, 1, GRANTOR), (0, 2, GRANTOR), (0, 3, CHARLIE)]
+        });
+
+        assert_noop!(
+            TrustFund::assign_unique(Origin::signed(GRANTOR), 0, 3, ALICE),
+            "grantor does not own the item"
+        );
+        assert_ok!(TrustFund::assign_unique(Origin::signed(GRANTOR), 0, 1, ALICE));
+        assert_noop!(
+            TrustFund::assign_unique(Origin::signed(GRANTOR), 0, 1, BOB),
+            "item already assigned"
+        );
+        assert_ok!(TrustFund::assign_unique(Origin::signed(GRANTOR), 0, 2, BOB));
+        assert_noop!(
+            TrustFund::unassign_unique(Origin::signed(GRANTOR), 0, 3),
+            "item is not assigned"
+        );
+        assert_noop!(
+            TrustFund::withdraw_unique(Origin::signed(ALICE), GRANTOR),
+            "not withdrawable yet"
+        );
+
+        // The grantor gives the second item away before the switch fires.
+        assert_ok!(Uniques::transfer(&0, &2, &CHARLIE));
+        System::set_block_number(6);
+        assert_ok!(TrustFund::withdraw_unique(Origin::signed(ALICE), GRANTOR));
+        assert_eq!(Uniques::owner(&0, &1), Some(ALICE));
+        assert_eq!(Uniques::owner(&0, &2), Some(CHARLIE));
+        assert!(TrustFund::unique_assignments(GRANTOR).is_empty());
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Distributing);
+        assert_noop!(
+            TrustFund::withdraw_unique(Origin::signed(ALICE), GRANTOR),
+            "no unique items assigned"
+        );
+    });
+}
+
+#[test]
+fn unassigned_unique_item_stays_with_the_grantor() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(5));
+        UNIQUE_OWNERS.with(|owners| *owners.borrow_mut() = vec![(0, 1, GRANTOR)]);
+        assert_ok!(TrustFund::assign_unique(Origin::signed(GRANTOR), 0, 1, ALICE));
+        assert_ok!(TrustFund::unassign_unique(Origin::signed(GRANTOR), 0, 1));
+
+        System::set_block_number(6);
+        assert_eq!(
+            TrustFund::withdraw_unique(Origin::signed(ALICE), GRANTOR),
+            Err("no unique items assigned")
+        );
+        assert_eq!(Uniques::owner(&0, &1), Some(GRANTOR));
+    });
+}
+
+#[test]
+fn trigger_tells_each_beneficiary_what_they_can_claim() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(1);
+        setup_trust(LivingSwitchCond::BlockHeight(5));
+        assert_ok!(assets::Call::<Test>::issue(200).dispatch(Origin::signed(GRANTOR)));
+        assert_ok!(TrustFund::deposit(Origin::signed(GRANTOR), 1, 200));
+        assert_noop!(
+            TrustFund::withdraw(Origin::signed(ALICE), GRANTOR, 0),
+            "not withdrawable yet"
+        );
+        let notified = |events: Vec<Event<Test>>| {
+            events
+                .into_iter()
+                .filter(|event| match event {
+                    RawEvent::InheritanceAvailable(..) => true,
+                    _ => false,
+                })
+                .count()
+        };
+        assert_eq!(notified(events()), 0);
+
+        System::set_block_number(6);
+        TrustFund::on_finalize(6);
+        let deposited = events();
+        assert_eq!(notified(deposited.clone()), 2);
+        assert!(deposited.contains(&RawEvent::InheritanceAvailable(
+            GRANTOR,
+            ALICE,
+            vec![(0, 500), (1, 100)]
+        )));
+        assert!(deposited.contains(&RawEvent::InheritanceAvailable(
+            GRANTOR,
+            BOB,
+            vec![(0, 500), (1, 100)]
+        )));
+    });
+}
+
+#[test]
+fn attesting_a_living_grantor_costs_the_deposit() {
+    with_externalities(&mut new_test_ext(), || {
+        System::set_block_number(1);
+        setup_trust(LivingSwitchCond::None);
+        assert_ok!(TrustFund::set_guardians(Origin::signed(GRANTOR), vec![CHARLIE, TREASURY]));
+        assert_ok!(TrustFund::accept_guardianship(Origin::signed(CHARLIE), GRANTOR));
+        assert_ok!(TrustFund::accept_guardianship(Origin::signed(TREASURY), GRANTOR));
+        assert_ok!(TrustFund::set_tiers(
+            Origin::signed(GRANTOR),
+            vec![Tier {
+                condition: LivingSwitchCond::ClockInInterval(10),
+                required_attestations: 2,
+                beneficiaries: vec![BeneficiaryShare { address: BOB, weight: 1 }],
+            }]
+        ));
+        assert_ok!(Balances::transfer(Origin::signed(TREASURY), BOB, 960));
+
+        System::set_block_number(11);
+        assert_noop!(
+            TrustFund::attest(Origin::signed(TREASURY), GRANTOR),
+            "not enough free balance for attestation deposit"
+        );
+        assert_ok!(TrustFund::attest(Origin::signed(CHARLIE), GRANTOR));
+        assert_eq!(Balances::reserved_balance(&CHARLIE), 50);
+        TrustFund::on_finalize(11);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+
+        // The grantor turns up within the grace period, so the attestation was false.
+        System::set_block_number(12);
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        assert!(TrustFund::guardian_attestations(GRANTOR).is_empty());
+        assert_eq!(Balances::reserved_balance(&CHARLIE), 0);
+        assert_eq!(Balances::free_balance(&CHARLIE), 950);
+        assert_eq!(Balances::free_balance(&TREASURY), 40);
+        assert!(events().contains(&RawEvent::AttestationSlashed(GRANTOR, CHARLIE, 50)));
+    });
+}
+
+#[test]
+fn unique_items_are_handed_over_all_or_none() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::BlockHeight(5));
+        UNIQUE_OWNERS.with(|owners| *owners.borrow_mut() = vec![(0, 1, GRANTOR), (0, 2, GRANTOR)]);
+        assert_ok!(TrustFund::assign_unique(Origin::signed(GRANTOR), 0, 1, ALICE));
+        assert_ok!(TrustFund::assign_unique(Origin::signed(GRANTOR), 0, 2, BOB));
+        System::set_block_number(6);
+        TrustFund::on_finalize(6);
+
+        // The second item cannot move, so the first one must not either.
+        FROZEN_UNIQUES.with(|frozen| *frozen.borrow_mut() = vec![(0, 2)]);
+        assert_noop!(
+            TrustFund::withdraw_unique(Origin::signed(ALICE), GRANTOR),
+            "item is frozen"
+        );
+        assert_eq!(Uniques::owner(&0, &1), Some(GRANTOR));
+        assert_eq!(TrustFund::unique_assignments(GRANTOR).len(), 2);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Triggered);
+
+        FROZEN_UNIQUES.with(|frozen| frozen.borrow_mut().clear());
+        assert_ok!(TrustFund::withdraw_unique(Origin::signed(ALICE), GRANTOR));
+        assert_eq!(Uniques::owner(&0, &1), Some(ALICE));
+        assert_eq!(Uniques::owner(&0, &2), Some(BOB));
+    });
+}
+
+#[test]
+fn clearing_clock_in_history_waits_for_the_change_delay() {
+    with_externalities(&mut new_test_ext(), || {
+        setup_trust(LivingSwitchCond::ClockInInterval(10));
+        CONFIG_CHANGE_COOLDOWN.with(|cooldown| *cooldown.borrow_mut() = 5);
+        System::set_block_number(20);
+        assert_ok!(TrustFund::clock_in(Origin::signed(GRANTOR)));
+        assert_ok!(TrustFund::set_change_delay(Origin::signed(GRANTOR), 10));
+
+        // Forgetting the clock-ins would let the switch fire at once.
+        assert_ok!(TrustFund::clear_clock_in_history(Origin::signed(GRANTOR)));
+        assert_eq!(TrustFund::last_clock_in(GRANTOR), 20);
+        assert_eq!(TrustFund::timelocked_changes(GRANTOR).len(), 1);
+        assert_noop!(
+            TrustFund::clear_clock_in_history(Origin::signed(GRANTOR)),
+            "configuration changed too recently"
+        );
+        TrustFund::on_finalize(20);
+        assert_eq!(TrustFund::trust_state(GRANTOR), TrustState::Active);
+
+        System::set_block_number(30);
+        assert_ok!(TrustFund::enact_changes(Origin::signed(ALICE), GRANTOR));
+        assert!(!<LastClockIn<Test>>::exists(GRANTOR));
+        assert!(TrustFund::clock_in_history(GRANTOR).is_empty());
+    });
+}
@@ -0,0 +1,85 @@
+//! Weights of the trust fund module's dispatchables.
+
+use rstd::{marker::PhantomData, prelude::*};
+use support::weights::{ClassifyDispatch, DispatchClass, WeighData, Weight};
+
+use crate::{BeneficiaryShare, Module, Trait};
+
+/// The weight of every dispatchable that does not declare its own.
+pub const DEFAULT_WEIGHT: Weight = 10_000;
+
+/// Weights of the dispatchables whose cost grows with the size of a trust, in the units of
+/// `MaximumBlockWeight`. Runtimes implement this from the figures the module's benchmarks
+/// report, run with `cargo test -p trust-fund --release --features runtime-benchmarks --
+/// --nocapture bench`. `()` charges every call the default weight.
+pub trait WeightInfo {
+    /// `deposit` into a trust that already holds many assets. The depositor is not among
+    /// the arguments, so this cannot depend on how many their trust holds.
+    fn deposit() -> Weight;
+    /// `set_beneficiaries` with `b` beneficiaries.
+    fn set_beneficiaries(b: u32) -> Weight;
+    /// `clock_in`.
+    fn clock_in() -> Weight;
+    /// `withdraw` paying out to `b` beneficiaries.
+    fn withdraw(b: u32) -> Weight;
+}
+
+impl WeightInfo for () {
+    fn deposit() -> Weight {
+        DEFAULT_WEIGHT
+    }
+
+    fn set_beneficiaries(_b: u32) -> Weight {
+        DEFAULT_WEIGHT
+    }
+
+    fn clock_in() -> Weight {
+        DEFAULT_WEIGHT
+    }
+
+    fn withdraw(_b: u32) -> Weight {
+        DEFAULT_WEIGHT
+    }
+}
+
+/// Weighs `set_beneficiaries` by the number of beneficiaries it is given.
+pub struct SetBeneficiariesWeight<T>(PhantomData<T>);
+
+/// Weighs `withdraw` by the number of plain beneficiaries the trust names.
+pub struct WithdrawWeight<T>(PhantomData<T>);
+
+impl<T> Default for SetBeneficiariesWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for WithdrawWeight<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Trait> WeighData<(&Vec<BeneficiaryShare<T::AccountId>>,)> for SetBeneficiariesWeight<T> {
+    fn weigh_data(&self, (shares,): (&Vec<BeneficiaryShare<T::AccountId>>,)) -> Weight {
+        T::WeightInfo::set_beneficiaries(shares.len() as u32)
+    }
+}
+
+impl<T: Trait> WeighData<(&T::AccountId, &T::AssetId)> for WithdrawWeight<T> {
+    fn weigh_data(&self, (grantor, _): (&T::AccountId, &T::AssetId)) -> Weight {
+        T::WeightInfo::withdraw(Module::<T>::beneficiary_count(grantor))
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for SetBeneficiariesWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}
+
+impl<T, Args> ClassifyDispatch<Args> for WithdrawWeight<T> {
+    fn classify_dispatch(&self, _: Args) -> DispatchClass {
+        DispatchClass::Normal
+    }
+}